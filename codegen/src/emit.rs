@@ -0,0 +1,80 @@
+//! Formatting helpers shared by the emitters
+
+use std::fmt::Write;
+
+/// Line width the generated code is wrapped at
+pub const WIDTH: usize = 100;
+
+/// `swap_base_input` to `SwapBaseInput`
+pub fn pascal_case(name: &str) -> String {
+    name.split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars.next().map(|first| first.to_ascii_uppercase().to_string() + chars.as_str()).unwrap_or_default()
+        })
+        .collect()
+}
+
+/// `ObservationState` to `observation_state`
+pub fn snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() {
+            if i > 0 {
+                snake.push('_');
+            }
+            snake.push(c.to_ascii_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+    snake
+}
+
+/// Write `docs` as `///` lines
+pub fn docs(out: &mut String, indent: &str, docs: &[String]) {
+    for line in docs {
+        match line.trim_end() {
+            "" => writeln!(out, "{indent}///").unwrap(),
+            line => writeln!(out, "{indent}/// {line}").unwrap(),
+        }
+    }
+}
+
+/// Write `items` as the lines of a list literal, filling lines up to `WIDTH`
+pub fn fill(out: &mut String, indent: &str, items: &[String]) {
+    let mut line = String::new();
+    for item in items {
+        if !line.is_empty() && indent.len() + line.len() + item.len() + 2 > WIDTH {
+            writeln!(out, "{indent}{}", line.trim_end()).unwrap();
+            line.clear();
+        }
+        line.push_str(item);
+        line.push_str(", ");
+    }
+    if !line.is_empty() {
+        writeln!(out, "{indent}{}", line.trim_end()).unwrap();
+    }
+}
+
+/// Write `items` as the lines of a list literal, `per_line` items per line
+pub fn chunks(out: &mut String, indent: &str, items: &[String], per_line: usize) {
+    for chunk in items.chunks(per_line) {
+        writeln!(out, "{indent}{},", chunk.join(", ")).unwrap();
+    }
+}
+
+/// Write a call or signature `head(items)tail`, on one line when it fits `WIDTH`,
+/// otherwise one item per line
+pub fn call(out: &mut String, indent: &str, head: &str, items: &[String], tail: &str) {
+    let line = format!("{indent}{head}({}){tail}", items.join(", "));
+    if line.len() <= WIDTH {
+        writeln!(out, "{line}").unwrap();
+        return;
+    }
+    writeln!(out, "{indent}{head}(").unwrap();
+    for item in items {
+        writeln!(out, "{indent}    {item},").unwrap();
+    }
+    writeln!(out, "{indent}){tail}").unwrap();
+}
@@ -0,0 +1,200 @@
+//! The parts of an Anchor IDL the generator reads
+
+use crate::json::Value;
+
+pub struct Idl {
+    pub instructions: Vec<Instruction>,
+    pub accounts: Vec<Account>,
+    pub types: Vec<TypeDef>,
+}
+
+pub struct Instruction {
+    pub name: String,
+    pub docs: Vec<String>,
+    pub discriminator: Vec<u8>,
+    pub accounts: Vec<InstructionAccount>,
+    pub args: Vec<Field>,
+}
+
+pub struct InstructionAccount {
+    pub name: String,
+    pub docs: Vec<String>,
+    pub writable: bool,
+    pub signer: bool,
+}
+
+pub struct Account {
+    pub name: String,
+    pub discriminator: Vec<u8>,
+}
+
+pub struct TypeDef {
+    pub name: String,
+    pub docs: Vec<String>,
+    pub kind: TypeKind,
+}
+
+pub enum TypeKind {
+    Struct(Vec<Field>),
+    Enum(Vec<Variant>),
+}
+
+pub struct Variant {
+    pub name: String,
+    pub docs: Vec<String>,
+}
+
+pub struct Field {
+    pub name: String,
+    pub docs: Vec<String>,
+    pub ty: Type,
+}
+
+#[derive(Clone)]
+pub enum Type {
+    /// `u8`..`u128`, `i8`..`i128` and `bool`
+    Primitive(String),
+    Pubkey,
+    Array(Box<Type>, usize),
+    Defined(String),
+}
+
+impl Idl {
+    pub fn parse(idl: &Value) -> Result<Self, String> {
+        Ok(Idl {
+            instructions: idl["instructions"].as_array().iter().map(Instruction::parse).collect::<Result<_, _>>()?,
+            accounts: idl["accounts"].as_array().iter().map(Account::parse).collect(),
+            types: idl["types"].as_array().iter().map(TypeDef::parse).collect::<Result<_, _>>()?,
+        })
+    }
+
+    pub fn type_def(&self, name: &str) -> Result<&TypeDef, String> {
+        self.types
+            .iter()
+            .find(|type_def| type_def.name == name)
+            .ok_or_else(|| format!("type {name} is not defined"))
+    }
+
+    /// Size of `ty` in a packed, zero-copy layout
+    pub fn size_of(&self, ty: &Type) -> Result<usize, String> {
+        match ty {
+            Type::Primitive(name) => primitive_size(name),
+            Type::Pubkey => Ok(32),
+            Type::Array(element, len) => Ok(self.size_of(element)? * len),
+            Type::Defined(name) => match &self.type_def(name)?.kind {
+                TypeKind::Struct(fields) => fields.iter().map(|field| self.size_of(&field.ty)).sum(),
+                TypeKind::Enum(_) => Ok(1),
+            },
+        }
+    }
+}
+
+impl Instruction {
+    fn parse(instruction: &Value) -> Result<Self, String> {
+        let name = instruction["name"].as_str().to_string();
+        let accounts = instruction["accounts"]
+            .as_array()
+            .iter()
+            .map(|account| {
+                if account.get("accounts").is_some() {
+                    return Err(format!("{name}: nested account groups are not supported"));
+                }
+                Ok(InstructionAccount {
+                    name: account["name"].as_str().to_string(),
+                    docs: docs(account),
+                    writable: account.get("writable").is_some_and(Value::as_bool),
+                    signer: account.get("signer").is_some_and(Value::as_bool),
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Instruction {
+            docs: docs(instruction),
+            discriminator: discriminator(instruction),
+            accounts,
+            args: fields(&instruction["args"])?,
+            name,
+        })
+    }
+}
+
+impl Account {
+    fn parse(account: &Value) -> Self {
+        Account { name: account["name"].as_str().to_string(), discriminator: discriminator(account) }
+    }
+}
+
+impl TypeDef {
+    fn parse(type_def: &Value) -> Result<Self, String> {
+        let ty = &type_def["type"];
+        let kind = match ty["kind"].as_str() {
+            "struct" => TypeKind::Struct(fields(&ty["fields"])?),
+            "enum" => TypeKind::Enum(
+                ty["variants"]
+                    .as_array()
+                    .iter()
+                    .map(|variant| {
+                        if variant.get("fields").is_some() {
+                            return Err(format!("{}: enum variants with fields are not supported", type_def["name"].as_str()));
+                        }
+                        Ok(Variant { name: variant["name"].as_str().to_string(), docs: docs(variant) })
+                    })
+                    .collect::<Result<_, _>>()?,
+            ),
+            kind => return Err(format!("{}: unsupported type kind {kind}", type_def["name"].as_str())),
+        };
+        Ok(TypeDef { name: type_def["name"].as_str().to_string(), docs: docs(type_def), kind })
+    }
+}
+
+impl Type {
+    fn parse(ty: &Value) -> Result<Self, String> {
+        if let Value::String(name) = ty {
+            return match name.as_str() {
+                "pubkey" => Ok(Type::Pubkey),
+                name => primitive_size(name).map(|_| Type::Primitive(name.to_string())),
+            };
+        }
+        if let Some(array) = ty.get("array") {
+            let [element, len] = array.as_array() else {
+                return Err("array types take an element type and a length".to_string());
+            };
+            return Ok(Type::Array(Box::new(Type::parse(element)?), len.as_u64() as usize));
+        }
+        if let Some(defined) = ty.get("defined") {
+            return Ok(Type::Defined(defined["name"].as_str().to_string()));
+        }
+        Err(format!("unsupported type {ty:?}"))
+    }
+}
+
+fn primitive_size(name: &str) -> Result<usize, String> {
+    match name {
+        "u8" | "i8" | "bool" => Ok(1),
+        "u16" | "i16" => Ok(2),
+        "u32" | "i32" => Ok(4),
+        "u64" | "i64" => Ok(8),
+        "u128" | "i128" => Ok(16),
+        name => Err(format!("unsupported type {name}")),
+    }
+}
+
+fn fields(fields: &Value) -> Result<Vec<Field>, String> {
+    fields
+        .as_array()
+        .iter()
+        .map(|field| {
+            Ok(Field { name: field["name"].as_str().to_string(), docs: docs(field), ty: Type::parse(&field["type"])? })
+        })
+        .collect()
+}
+
+fn docs(item: &Value) -> Vec<String> {
+    match item.get("docs") {
+        Some(docs) => docs.as_array().iter().map(|line| line.as_str().to_string()).collect(),
+        None => Vec::new(),
+    }
+}
+
+fn discriminator(item: &Value) -> Vec<u8> {
+    item["discriminator"].as_array().iter().map(|byte| byte.as_u64() as u8).collect()
+}
@@ -0,0 +1,269 @@
+//! Emits `src/instructions/<name>.rs`
+
+use std::fmt::Write;
+
+use crate::emit::{call, chunks, docs, fill, pascal_case, WIDTH};
+use crate::idl::{Field, Idl, Instruction, Type, TypeKind};
+
+/// How an instruction argument is held and written
+struct Arg<'a> {
+    field: &'a Field,
+    rust_type: String,
+    /// Type shown in the data layout comment
+    label: String,
+    size: usize,
+    bytes: String,
+}
+
+impl<'a> Arg<'a> {
+    fn new(idl: &Idl, field: &'a Field) -> Result<Self, String> {
+        let name = &field.name;
+        let (rust_type, label, bytes) = match &field.ty {
+            Type::Primitive(ty) if ty == "u8" => (ty.clone(), ty.clone(), format!("&[{name}]")),
+            Type::Primitive(ty) if ty == "i8" || ty == "bool" => (ty.clone(), ty.clone(), format!("&[{name} as u8]")),
+            Type::Primitive(ty) => (ty.clone(), ty.clone(), format!("&{name}.to_le_bytes()")),
+            Type::Pubkey => ("Pubkey".to_string(), "Pubkey".to_string(), format!("&{name}")),
+            Type::Array(element, len) if matches!(&**element, Type::Primitive(ty) if ty == "u8") => {
+                let ty = format!("[u8; {len}]");
+                (ty.clone(), ty, format!("&{name}"))
+            }
+            Type::Defined(ty) if matches!(idl.type_def(ty)?.kind, TypeKind::Enum(_)) => {
+                (ty.clone(), "u8".to_string(), format!("&[{name} as u8]"))
+            }
+            _ => return Err(format!("argument {name}: only primitives, pubkeys, byte arrays and enums are supported")),
+        };
+        Ok(Arg { field, rust_type, label, size: idl.size_of(&field.ty)?, bytes })
+    }
+}
+
+pub fn emit(idl: &Idl, instruction: &Instruction) -> Result<String, String> {
+    let name = pascal_case(&instruction.name);
+    let args = instruction.args.iter().map(|field| Arg::new(idl, field)).collect::<Result<Vec<_>, _>>()?;
+    let data_len = 8 + args.iter().map(|arg| arg.size).sum::<usize>();
+    let account_count = instruction.accounts.len();
+    let mut out = String::new();
+
+    // Imports
+    writeln!(out, "use core::{{mem::MaybeUninit, slice::from_raw_parts}};\n").unwrap();
+    writeln!(out, "use pinocchio::{{").unwrap();
+    writeln!(out, "    account_info::AccountInfo,").unwrap();
+    writeln!(out, "    instruction::{{Instruction, Signer}},").unwrap();
+    writeln!(out, "    program::invoke_signed,").unwrap();
+    writeln!(out, "    program_error::ProgramError,").unwrap();
+    if args.iter().any(|arg| matches!(arg.field.ty, Type::Pubkey)) {
+        writeln!(out, "    pubkey::Pubkey,").unwrap();
+    }
+    writeln!(out, "    ProgramResult,").unwrap();
+    writeln!(out, "}};\n").unwrap();
+    let mut states: Vec<&str> = args
+        .iter()
+        .filter_map(|arg| match &arg.field.ty {
+            Type::Defined(ty) => Some(ty.as_str()),
+            _ => None,
+        })
+        .collect();
+    states.sort_unstable();
+    states.dedup();
+    match states.as_slice() {
+        [] => writeln!(out, "use crate::{{write_bytes, UNINIT_BYTE}};\n").unwrap(),
+        [state] => writeln!(out, "use crate::{{states::{state}, write_bytes, UNINIT_BYTE}};\n").unwrap(),
+        states => writeln!(
+            out,
+            "use crate::{{states::{{{}}}, write_bytes, UNINIT_BYTE}};\n",
+            states.join(", ")
+        )
+        .unwrap(),
+    }
+    writeln!(out, "use super::encoding::{{account_metas, AccountRole}};\n").unwrap();
+
+    // Struct
+    docs(&mut out, "", &instruction.docs);
+    if !instruction.docs.is_empty() {
+        writeln!(out, "///").unwrap();
+    }
+    writeln!(out, "/// ### Accounts:").unwrap();
+    for (i, account) in instruction.accounts.iter().enumerate() {
+        let flags = match (account.writable, account.signer) {
+            (true, true) => "[WRITE, SIGNER]",
+            (true, false) => "[WRITE]",
+            (false, true) => "[SIGNER]",
+            (false, false) => "[]",
+        };
+        match account.docs.first() {
+            Some(doc) => writeln!(out, "///   {i}. `{flags}` {} - {doc}", account.name).unwrap(),
+            None => writeln!(out, "///   {i}. `{flags}` {}", account.name).unwrap(),
+        }
+    }
+    writeln!(out, "pub struct {name}<'a> {{").unwrap();
+    for account in &instruction.accounts {
+        docs(&mut out, "    ", &account.docs);
+        writeln!(out, "    pub {}: &'a AccountInfo,", account.name).unwrap();
+    }
+    if !args.is_empty() {
+        writeln!(out, "\n    /// Instruction parameters").unwrap();
+        for (i, arg) in args.iter().enumerate() {
+            if i == 0 && !arg.field.docs.is_empty() {
+                writeln!(out, "    ///").unwrap();
+            }
+            docs(&mut out, "    ", &arg.field.docs);
+            writeln!(out, "    pub {}: {},", arg.field.name, arg.rust_type).unwrap();
+        }
+    }
+    writeln!(out, "}}\n").unwrap();
+
+    // Constructor
+    writeln!(out, "impl<'a> {name}<'a> {{").unwrap();
+    writeln!(out, "    /// Bind `accounts` in the documented order.").unwrap();
+    writeln!(out, "    ///").unwrap();
+    writeln!(out, "    /// Accounts past the ones of the instruction are ignored, so the accounts of the calling").unwrap();
+    writeln!(out, "    /// instruction can be passed as is, fewer fail with `NotEnoughAccountKeys`.").unwrap();
+    let mut params = vec!["accounts: &'a [AccountInfo]".to_string()];
+    params.extend(args.iter().map(|arg| format!("{}: {}", arg.field.name, arg.rust_type)));
+    call(&mut out, "    ", "pub fn from_accounts", &params, " -> Result<Self, ProgramError> {");
+    let mut bindings: Vec<String> = instruction.accounts.iter().map(|account| account.name.clone()).collect();
+    bindings.push("..".to_string());
+    let pattern = format!("        let [{}] = accounts else {{", bindings.join(", "));
+    if pattern.len() <= WIDTH {
+        writeln!(out, "{pattern}").unwrap();
+    } else {
+        writeln!(out, "        let [").unwrap();
+        let mut lines = String::new();
+        fill(&mut lines, "            ", &bindings);
+        out.push_str(&lines.replace("..,\n", "..\n"));
+        writeln!(out, "        ] = accounts else {{").unwrap();
+    }
+    writeln!(out, "            return Err(ProgramError::NotEnoughAccountKeys);").unwrap();
+    writeln!(out, "        }};").unwrap();
+    let mut fields: Vec<String> = instruction.accounts.iter().map(|account| account.name.clone()).collect();
+    fields.extend(args.iter().map(|arg| arg.field.name.clone()));
+    let literal = format!("        Ok(Self {{ {} }})", fields.join(", "));
+    if literal.len() <= WIDTH {
+        writeln!(out, "{literal}").unwrap();
+    } else {
+        writeln!(out, "        Ok(Self {{").unwrap();
+        fill(&mut out, "            ", &fields);
+        writeln!(out, "        }})").unwrap();
+    }
+    writeln!(out, "    }}").unwrap();
+    writeln!(out, "}}\n").unwrap();
+
+    // Constants
+    writeln!(out, "impl {name}<'_> {{").unwrap();
+    writeln!(out, "    /// Anchor discriminator of the instruction").unwrap();
+    let discriminator: Vec<String> = instruction.discriminator.iter().map(u8::to_string).collect();
+    writeln!(out, "    pub const DISCRIMINATOR: [u8; 8] = [{}];\n", discriminator.join(", ")).unwrap();
+    writeln!(out, "    /// Length of the instruction data").unwrap();
+    writeln!(out, "    pub const DATA_LEN: usize = {data_len};\n").unwrap();
+    writeln!(out, "    /// Roles of the accounts, in order").unwrap();
+    writeln!(out, "    pub const ACCOUNTS: [AccountRole; {account_count}] = [").unwrap();
+    for account in &instruction.accounts {
+        let role = match (account.writable, account.signer) {
+            (true, true) => "WRITABLE_SIGNER",
+            (true, false) => "WRITABLE",
+            (false, true) => "READONLY_SIGNER",
+            (false, false) => "READONLY",
+        };
+        writeln!(out, "        AccountRole::{role},").unwrap();
+    }
+    writeln!(out, "    ];\n").unwrap();
+    writeln!(out, "    /// Names of the accounts, in order").unwrap();
+    writeln!(out, "    pub const ACCOUNT_NAMES: [&'static str; {account_count}] = [").unwrap();
+    let names: Vec<String> = instruction.accounts.iter().map(|account| format!("{:?}", account.name)).collect();
+    fill(&mut out, "        ", &names);
+    writeln!(out, "    ];\n").unwrap();
+
+    // Data
+    writeln!(out, "    /// Write the instruction data, initializing every byte of `data`").unwrap();
+    writeln!(out, "    #[inline(always)]").unwrap();
+    let mut params = vec![format!("data: &mut [MaybeUninit<u8>; {data_len}]")];
+    params.extend(args.iter().map(|arg| format!("{}: {}", arg.field.name, arg.rust_type)));
+    call(&mut out, "    ", "pub fn write_data", &params, " {");
+    writeln!(out, "        // Instruction data layout:").unwrap();
+    writeln!(out, "        // -  [0..8]: instruction discriminator (8 bytes)").unwrap();
+    let mut offset = 8;
+    for arg in &args {
+        let (range, size) = match arg.size {
+            1 => (format!("[{offset}]"), "1 byte".to_string()),
+            size => (format!("[{offset}..{}]", offset + size), format!("{size} bytes")),
+        };
+        writeln!(out, "        // -  {range}: {} ({size}, {})", arg.field.name, arg.label).unwrap();
+        offset += arg.size;
+    }
+    writeln!(out).unwrap();
+    writeln!(out, "        // Set discriminator (8 bytes)").unwrap();
+    writeln!(out, "        write_bytes(&mut data[0..8], &Self::DISCRIMINATOR);").unwrap();
+    let mut offset = 8;
+    for arg in &args {
+        let size = match arg.size {
+            1 => "1 byte".to_string(),
+            size => format!("{size} bytes"),
+        };
+        writeln!(out, "\n        // Set {} ({size})", arg.field.name).unwrap();
+        writeln!(out, "        write_bytes(&mut data[{offset}..{}], {});", offset + arg.size, arg.bytes).unwrap();
+        offset += arg.size;
+    }
+    writeln!(out, "    }}\n").unwrap();
+
+    // Invocation
+    writeln!(out, "    #[inline(always)]").unwrap();
+    writeln!(out, "    pub fn invoke(&self) -> ProgramResult {{").unwrap();
+    writeln!(out, "        self.invoke_signed(&[])").unwrap();
+    writeln!(out, "    }}\n").unwrap();
+    writeln!(out, "    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {{").unwrap();
+    writeln!(
+        out,
+        "        self.with_instruction(|instruction, accounts| invoke_signed(instruction, accounts, signers))"
+    )
+    .unwrap();
+    writeln!(out, "    }}\n").unwrap();
+    writeln!(out, "    /// Build the instruction and call `f` with it and the account infos, in instruction order.").unwrap();
+    writeln!(out, "    ///").unwrap();
+    writeln!(out, "    /// `invoke_signed` passes them to `pinocchio::program::invoke_signed`, other executors such").unwrap();
+    writeln!(out, "    /// as `test_utils::svm::Svm` can run the same instruction.").unwrap();
+    writeln!(out, "    #[inline(always)]").unwrap();
+    let signature = format!(
+        "    pub fn with_instruction<R>(&self, f: impl FnOnce(&Instruction, &[&AccountInfo; {account_count}]) -> R) -> R {{"
+    );
+    if signature.len() <= WIDTH {
+        writeln!(out, "{signature}").unwrap();
+    } else {
+        writeln!(out, "    pub fn with_instruction<R>(").unwrap();
+        writeln!(out, "        &self,").unwrap();
+        writeln!(out, "        f: impl FnOnce(&Instruction, &[&AccountInfo; {account_count}]) -> R,").unwrap();
+        writeln!(out, "    ) -> R {{").unwrap();
+    }
+    writeln!(out, "        let accounts = [").unwrap();
+    let accounts: Vec<String> = instruction.accounts.iter().map(|account| format!("self.{}", account.name)).collect();
+    chunks(&mut out, "            ", &accounts, 4);
+    writeln!(out, "        ];").unwrap();
+    writeln!(out, "        let account_metas = account_metas(&Self::ACCOUNTS, &accounts);\n").unwrap();
+    writeln!(out, "        let mut instruction_data = [UNINIT_BYTE; {data_len}];").unwrap();
+    let mut values = vec!["&mut instruction_data".to_string()];
+    values.extend(args.iter().map(|arg| format!("self.{}", arg.field.name)));
+    call(&mut out, "        ", "Self::write_data", &values, ";");
+    writeln!(out).unwrap();
+    writeln!(out, "        let instruction = Instruction {{").unwrap();
+    writeln!(out, "            program_id: crate::program_id(),").unwrap();
+    writeln!(out, "            accounts: &account_metas,").unwrap();
+    writeln!(out, "            data: unsafe {{ from_raw_parts(instruction_data.as_ptr() as _, {data_len}) }},").unwrap();
+    writeln!(out, "        }};\n").unwrap();
+    writeln!(out, "        f(&instruction, &accounts)").unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(out, "}}").unwrap();
+
+    Ok(out)
+}
+
+/// Emits `src/instructions/mod.rs` for the generated modules
+pub fn emit_mod(idl: &Idl) -> String {
+    let mut out = String::from("pub mod encoding;\n");
+    for instruction in &idl.instructions {
+        writeln!(out, "pub mod {};", instruction.name).unwrap();
+    }
+    writeln!(out).unwrap();
+    for instruction in &idl.instructions {
+        writeln!(out, "pub use {}::*;", instruction.name).unwrap();
+    }
+    writeln!(out, "pub use encoding::AccountRole;").unwrap();
+    out
+}
@@ -0,0 +1,75 @@
+//! Generates the instruction and state structs of the crate from an Anchor IDL.
+//!
+//! ```sh
+//! cargo run --manifest-path codegen/Cargo.toml -- idl/raydium_cp_swap.json <OUT_DIR>
+//! ```
+//!
+//! Writes `<OUT_DIR>/instructions/<name>.rs` for every instruction and
+//! `<OUT_DIR>/states/<name>.rs` for every account, the structs they embed and the
+//! enums taken as arguments, each with a `mod.rs` listing them. The output is the
+//! generated core of the files in `src/`: the hand-written additions (`validate`,
+//! slippage constructors, `UpdateAmmConfigParam`, the state helpers) are not.
+//! `tests/codegen.rs` in the crate runs the generator and checks the instruction constants
+//! and state layouts of `src/` against its output.
+//!
+//! To follow an upgrade of the program, generate from the IDL of the current and the
+//! new release and apply the difference to `src/`:
+//!
+//! ```sh
+//! cargo run --manifest-path codegen/Cargo.toml -- idl/raydium_cp_swap.json /tmp/old
+//! # replace idl/raydium_cp_swap.json with the new IDL
+//! cargo run --manifest-path codegen/Cargo.toml -- idl/raydium_cp_swap.json /tmp/new
+//! diff -ruN /tmp/old /tmp/new | patch -p3 --no-backup-if-mismatch -d src
+//! ```
+
+mod emit;
+mod idl;
+mod instruction;
+mod json;
+mod state;
+
+use std::{fs, path::Path, process::ExitCode};
+
+use idl::Idl;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let [idl, out_dir] = args.as_slice() else {
+        eprintln!("usage: codegen <IDL> <OUT_DIR>");
+        return ExitCode::FAILURE;
+    };
+    match generate(Path::new(idl), Path::new(out_dir)) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn generate(idl: &Path, out_dir: &Path) -> Result<(), String> {
+    let source = fs::read_to_string(idl).map_err(|error| format!("{}: {error}", idl.display()))?;
+    let idl = Idl::parse(&json::parse(&source).map_err(|error| format!("{}: {error}", idl.display()))?)?;
+
+    let mut files = vec![("instructions/mod.rs".to_string(), instruction::emit_mod(&idl))];
+    for ix in &idl.instructions {
+        let code = instruction::emit(&idl, ix).map_err(|error| format!("{}: {error}", ix.name))?;
+        files.push((format!("instructions/{}.rs", ix.name), code));
+    }
+    let types = state::types(&idl)?;
+    files.push(("states/mod.rs".to_string(), state::emit_mod(&types)));
+    for type_def in &types {
+        let code = state::emit(&idl, type_def).map_err(|error| format!("{}: {error}", type_def.name))?;
+        files.push((format!("states/{}.rs", emit::snake_case(&type_def.name)), code));
+    }
+
+    for dir in ["instructions", "states"] {
+        fs::create_dir_all(out_dir.join(dir)).map_err(|error| format!("{}: {error}", out_dir.display()))?;
+    }
+    for (file, code) in files {
+        let path = out_dir.join(file);
+        fs::write(&path, code).map_err(|error| format!("{}: {error}", path.display()))?;
+        println!("{}", path.display());
+    }
+    Ok(())
+}
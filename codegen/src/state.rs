@@ -0,0 +1,254 @@
+//! Emits `src/states/<name>.rs`
+
+use std::fmt::Write;
+
+use crate::emit::{docs, snake_case};
+use crate::idl::{Field, Idl, Type, TypeDef, TypeKind};
+
+/// The types to emit: the IDL accounts, the structs they embed and the enums taken as
+/// instruction arguments, in IDL order
+pub fn types(idl: &Idl) -> Result<Vec<&TypeDef>, String> {
+    let mut names = Vec::new();
+    for account in &idl.accounts {
+        collect(idl, &Type::Defined(account.name.clone()), &mut names)?;
+    }
+    for instruction in &idl.instructions {
+        for arg in &instruction.args {
+            collect(idl, &arg.ty, &mut names)?;
+        }
+    }
+    Ok(idl.types.iter().filter(|type_def| names.contains(&type_def.name)).collect())
+}
+
+fn collect(idl: &Idl, ty: &Type, names: &mut Vec<String>) -> Result<(), String> {
+    match ty {
+        Type::Array(element, _) => collect(idl, element, names),
+        Type::Defined(name) if !names.contains(name) => {
+            names.push(name.clone());
+            if let TypeKind::Struct(fields) = &idl.type_def(name)?.kind {
+                for field in fields {
+                    collect(idl, &field.ty, names)?;
+                }
+            }
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+pub fn emit(idl: &Idl, type_def: &TypeDef) -> Result<String, String> {
+    match &type_def.kind {
+        TypeKind::Struct(fields) => match idl.accounts.iter().find(|account| account.name == type_def.name) {
+            Some(account) => emit_account(idl, type_def, fields, &account.discriminator),
+            None => emit_struct(idl, type_def, fields),
+        },
+        TypeKind::Enum(variants) => {
+            let name = &type_def.name;
+            let mut out = String::from("use pinocchio::program_error::ProgramError;\n\n");
+            docs(&mut out, "", &type_def.docs);
+            writeln!(out, "#[repr(u8)]").unwrap();
+            writeln!(out, "#[derive(Clone, Copy, Debug, PartialEq, Eq)]").unwrap();
+            writeln!(out, "pub enum {name} {{").unwrap();
+            for variant in variants {
+                docs(&mut out, "    ", &variant.docs);
+                writeln!(out, "    {},", variant.name).unwrap();
+            }
+            writeln!(out, "}}\n").unwrap();
+            writeln!(out, "impl TryFrom<u8> for {name} {{").unwrap();
+            writeln!(out, "    type Error = ProgramError;\n").unwrap();
+            writeln!(out, "    fn try_from(value: u8) -> Result<Self, Self::Error> {{").unwrap();
+            writeln!(out, "        match value {{").unwrap();
+            for (i, variant) in variants.iter().enumerate() {
+                writeln!(out, "            {i} => Ok({name}::{}),", variant.name).unwrap();
+            }
+            writeln!(out, "            _ => Err(ProgramError::InvalidAccountData),").unwrap();
+            writeln!(out, "        }}").unwrap();
+            writeln!(out, "    }}").unwrap();
+            writeln!(out, "}}").unwrap();
+            Ok(out)
+        }
+    }
+}
+
+/// Rust type of a zero-copy field, enums are held as their `u8` index
+fn rust_type(idl: &Idl, ty: &Type) -> Result<String, String> {
+    Ok(match ty {
+        Type::Primitive(name) => name.clone(),
+        Type::Pubkey => "Pubkey".to_string(),
+        Type::Array(element, len) => format!("[{}; {len}]", rust_type(idl, element)?),
+        Type::Defined(name) => match idl.type_def(name)?.kind {
+            TypeKind::Struct(_) => name.clone(),
+            TypeKind::Enum(_) => "u8".to_string(),
+        },
+    })
+}
+
+/// Structs embedded by `fields`, imported from the sibling modules
+fn embedded(idl: &Idl, fields: &[Field]) -> Result<Vec<String>, String> {
+    fn embedded_type(idl: &Idl, ty: &Type) -> Result<Option<String>, String> {
+        match ty {
+            Type::Array(element, _) => embedded_type(idl, element),
+            Type::Defined(name) if matches!(idl.type_def(name)?.kind, TypeKind::Struct(_)) => Ok(Some(name.clone())),
+            _ => Ok(None),
+        }
+    }
+    let mut names = Vec::new();
+    for field in fields {
+        if let Some(name) = embedded_type(idl, &field.ty)? {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+    }
+    names.sort_unstable();
+    Ok(names)
+}
+
+fn emit_fields(idl: &Idl, out: &mut String, type_def: &TypeDef, fields: &[Field]) -> Result<(), String> {
+    docs(out, "", &type_def.docs);
+    writeln!(out, "#[repr(C, packed)]").unwrap();
+    writeln!(out, "#[derive(Clone, Copy, Debug, PartialEq)]").unwrap();
+    writeln!(out, "pub struct {} {{", type_def.name).unwrap();
+    for field in fields {
+        docs(out, "    ", &field.docs);
+        writeln!(out, "    pub {}: {},", field.name, rust_type(idl, &field.ty)?).unwrap();
+    }
+    writeln!(out, "}}\n").unwrap();
+    Ok(())
+}
+
+fn emit_struct(idl: &Idl, type_def: &TypeDef, fields: &[Field]) -> Result<String, String> {
+    let name = &type_def.name;
+    let mut out = String::new();
+    if fields.iter().any(|field| matches!(field.ty, Type::Pubkey)) {
+        writeln!(out, "use pinocchio::pubkey::Pubkey;\n").unwrap();
+    }
+    let embedded = embedded(idl, fields)?;
+    match embedded.as_slice() {
+        [] => {}
+        [one] => writeln!(out, "use super::{one};\n").unwrap(),
+        many => writeln!(out, "use super::{{{}}};\n", many.join(", ")).unwrap(),
+    }
+    emit_fields(idl, &mut out, type_def, fields)?;
+    writeln!(out, "impl {name} {{").unwrap();
+    writeln!(out, "    /// The length of the `{name}` data.").unwrap();
+    writeln!(out, "    pub const LEN: usize = core::mem::size_of::<{name}>();").unwrap();
+    writeln!(out, "}}").unwrap();
+    Ok(out)
+}
+
+fn emit_account(idl: &Idl, type_def: &TypeDef, fields: &[Field], discriminator: &[u8]) -> Result<String, String> {
+    let name = &type_def.name;
+    let mut out = String::new();
+    writeln!(out, "use pinocchio::{{").unwrap();
+    writeln!(out, "    account_info::{{AccountInfo, Ref}},").unwrap();
+    writeln!(out, "    program_error::ProgramError,").unwrap();
+    writeln!(out, "    pubkey::Pubkey,").unwrap();
+    writeln!(out, "}};\n").unwrap();
+    writeln!(out, "use crate::program_id;").unwrap();
+    let embedded = embedded(idl, fields)?;
+    if embedded.is_empty() {
+        writeln!(out, "use super::account::{{self, ProgramAccount}};\n").unwrap();
+    } else {
+        writeln!(out, "use super::{{account::{{self, ProgramAccount}}, {}}};\n", embedded.join(", ")).unwrap();
+    }
+    emit_fields(idl, &mut out, type_def, fields)?;
+    let discriminator: Vec<String> = discriminator.iter().map(u8::to_string).collect();
+    write!(
+        out,
+        r#"impl {name} {{
+    /// The length of the `{name}` account data.
+    pub const LEN: usize = core::mem::size_of::<{name}>();
+
+    /// Anchor-compatible discriminator
+    pub const DISCRIMINATOR: &'static [u8] = &[{discriminator}];
+
+    /// Return a `{name}` from the given account info.
+    ///
+    /// This method performs owner, length and discriminator validation on `AccountInfo`,
+    /// safe borrowing the account data.
+    #[inline]
+    pub fn from_account_info(account_info: &AccountInfo) -> Result<Ref<'_, {name}>, ProgramError> {{
+        account::load(account_info)
+    }}
+
+    /// Return a `{name}` from the given account info.
+    ///
+    /// This method performs owner, length and discriminator validation on `AccountInfo`,
+    /// but does not perform the borrow check.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that it is safe to borrow the account data – e.g., there are
+    /// no mutable borrows of the account data.
+    #[inline]
+    pub unsafe fn from_account_info_unchecked(
+        account_info: &AccountInfo,
+    ) -> Result<&Self, ProgramError> {{
+        account::load_unchecked(account_info)
+    }}
+
+    /// Return a `{name}` from the given bytes.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that `bytes` contains a valid representation of `{name}`.
+    #[inline(always)]
+    pub unsafe fn from_bytes(bytes: &[u8]) -> &Self {{
+        &*(bytes.as_ptr() as *const {name})
+    }}
+
+    /// Return a mutable `{name}` from the given bytes.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that `bytes` contains a valid representation of `{name}`.
+    #[inline(always)]
+    pub unsafe fn from_bytes_mut(bytes: &mut [u8]) -> &mut Self {{
+        &mut *(bytes.as_mut_ptr() as *mut {name})
+    }}
+
+    pub fn try_deserialize(buf: &mut &[u8]) -> Result<Self, ProgramError> {{
+        account::deserialize(buf)
+    }}
+
+    pub fn try_deserialize_unchecked(buf: &mut &[u8]) -> Result<Self, ProgramError> {{
+        account::deserialize_unchecked(buf)
+    }}
+
+    pub fn discriminator() -> &'static [u8] {{
+        Self::DISCRIMINATOR
+    }}
+
+    pub fn owner() -> Pubkey {{
+        *program_id()
+    }}
+
+    pub fn size() -> usize {{
+        Self::LEN + 8
+    }}
+}}
+
+unsafe impl ProgramAccount for {name} {{
+    const LEN: usize = {name}::LEN;
+    const DISCRIMINATOR: &'static [u8] = {name}::DISCRIMINATOR;
+}}
+"#,
+        discriminator = discriminator.join(", "),
+    )
+    .unwrap();
+    Ok(out)
+}
+
+/// Emits `src/states/mod.rs` for the generated modules
+pub fn emit_mod(types: &[&TypeDef]) -> String {
+    let mut out = String::from("pub mod account;\n");
+    for type_def in types {
+        writeln!(out, "pub mod {};", snake_case(&type_def.name)).unwrap();
+    }
+    writeln!(out, "\npub use account::ProgramAccount;").unwrap();
+    for type_def in types {
+        writeln!(out, "pub use {}::*;", snake_case(&type_def.name)).unwrap();
+    }
+    out
+}
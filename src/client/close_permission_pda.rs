@@ -0,0 +1,43 @@
+use solana_instruction::Instruction;
+use solana_pubkey::Pubkey;
+
+use crate::{instructions, UNINIT_BYTE};
+
+use super::build_instruction;
+
+/// Closes the permission account of an authority, refunding the rent to the owner
+///
+/// Only supported by CPMM releases with creator fees.
+///
+/// ### Accounts:
+///   0. `[WRITE, SIGNER]` owner - Admin authority, must be `crate::admin::ID`
+///   1. `[]` permission_authority - The authority the permission is granted to
+///   2. `[WRITE]` permission - Permission account to close (PDA)
+///   3. `[]` system_program - System program
+#[derive(Clone, Debug)]
+pub struct ClosePermissionPda {
+    /// Admin authority. Must be `crate::admin::ID`
+    pub owner: Pubkey,
+    /// CHECK: the authority the permission is granted to
+    pub permission_authority: Pubkey,
+    /// Permission account to close
+    pub permission: Pubkey,
+    /// System program
+    pub system_program: Pubkey,
+}
+
+impl ClosePermissionPda {
+    /// Build the instruction, encoded by `instructions::ClosePermissionPda`
+    pub fn instruction(&self) -> Instruction {
+        let mut data = [UNINIT_BYTE; 8];
+        instructions::ClosePermissionPda::write_data(&mut data);
+
+        build_instruction(
+            &instructions::ClosePermissionPda::ACCOUNTS,
+            [
+                &self.owner, &self.permission_authority, &self.permission, &self.system_program,
+            ],
+            data,
+        )
+    }
+}
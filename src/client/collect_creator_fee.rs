@@ -0,0 +1,76 @@
+use solana_instruction::Instruction;
+use solana_pubkey::Pubkey;
+
+use crate::{instructions, UNINIT_BYTE};
+
+use super::build_instruction;
+
+/// Collect the creator fee accrued to the pool
+///
+/// Only supported by CPMM releases with creator fees.
+///
+/// ### Accounts:
+///   0. `[WRITE, SIGNER]` creator - Pool creator, must match `PoolState::pool_creator`
+///   1. `[]` authority - Pool vault and lp mint authority (PDA)
+///   2. `[WRITE]` pool_state - Pool state stores accumulated creator fee amount
+///   3. `[]` amm_config - Amm config the pool belongs to
+///   4. `[WRITE]` token_0_vault - The address that holds pool tokens for token_0
+///   5. `[WRITE]` token_1_vault - The address that holds pool tokens for token_1
+///   6. `[]` vault_0_mint - The mint of token_0 vault
+///   7. `[]` vault_1_mint - The mint of token_1 vault
+///   8. `[WRITE]` creator_token_0 - Creator token0 account, created if needed
+///   9. `[WRITE]` creator_token_1 - Creator token1 account, created if needed
+///   10. `[]` token_0_program - Spl token program or token program 2022
+///   11. `[]` token_1_program - Spl token program or token program 2022
+///   12. `[]` associated_token_program - Program to create the creator token accounts
+///   13. `[]` system_program - To create a new program account
+#[derive(Clone, Debug)]
+pub struct CollectCreatorFee {
+    /// Pool creator, pays for the creator token accounts if needed
+    pub creator: Pubkey,
+    /// CHECK: pool vault and lp mint authority
+    pub authority: Pubkey,
+    /// Pool state stores accumulated creator fee amount
+    pub pool_state: Pubkey,
+    /// Amm config the pool belongs to
+    pub amm_config: Pubkey,
+    /// The address that holds pool tokens for token_0
+    pub token_0_vault: Pubkey,
+    /// The address that holds pool tokens for token_1
+    pub token_1_vault: Pubkey,
+    /// The mint of token_0 vault
+    pub vault_0_mint: Pubkey,
+    /// The mint of token_1 vault
+    pub vault_1_mint: Pubkey,
+    /// Creator token0 account
+    pub creator_token_0: Pubkey,
+    /// Creator token1 account
+    pub creator_token_1: Pubkey,
+    /// Spl token program or token program 2022
+    pub token_0_program: Pubkey,
+    /// Spl token program or token program 2022
+    pub token_1_program: Pubkey,
+    /// Program to create an ATA for the creator
+    pub associated_token_program: Pubkey,
+    /// To create a new program account
+    pub system_program: Pubkey,
+}
+
+impl CollectCreatorFee {
+    /// Build the instruction, encoded by `instructions::CollectCreatorFee`
+    pub fn instruction(&self) -> Instruction {
+        let mut data = [UNINIT_BYTE; 8];
+        instructions::CollectCreatorFee::write_data(&mut data);
+
+        build_instruction(
+            &instructions::CollectCreatorFee::ACCOUNTS,
+            [
+                &self.creator, &self.authority, &self.pool_state, &self.amm_config,
+                &self.token_0_vault, &self.token_1_vault, &self.vault_0_mint, &self.vault_1_mint,
+                &self.creator_token_0, &self.creator_token_1, &self.token_0_program, &self.token_1_program,
+                &self.associated_token_program, &self.system_program,
+            ],
+            data,
+        )
+    }
+}
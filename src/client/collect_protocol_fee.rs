@@ -0,0 +1,78 @@
+use solana_instruction::Instruction;
+use solana_pubkey::Pubkey;
+
+use crate::{instructions, UNINIT_BYTE};
+
+use super::build_instruction;
+
+/// Collect the protocol fee accrued to the pool
+///
+/// ### Accounts:
+///   0. `[SIGNER]` owner - Only the amm config protocol owner or admin can collect fee
+///   1. `[]` authority - Pool vault and lp mint authority (PDA)
+///   2. `[WRITE]` pool_state - Pool state stores accumulated protocol fee amount
+///   3. `[]` amm_config - Amm config account stores owner
+///   4. `[WRITE]` token_0_vault - The address that holds pool tokens for token_0
+///   5. `[WRITE]` token_1_vault - The address that holds pool tokens for token_1
+///   6. `[]` vault_0_mint - The mint of token_0 vault
+///   7. `[]` vault_1_mint - The mint of token_1 vault
+///   8. `[WRITE]` recipient_token_0_account - The address that receives the collected token_0 protocol fees
+///   9. `[WRITE]` recipient_token_1_account - The address that receives the collected token_1 protocol fees
+///   10. `[]` token_program - The SPL program to perform token transfers
+///   11. `[]` token_program_2022 - The SPL program 2022 to perform token transfers
+#[derive(Clone, Debug)]
+pub struct CollectProtocolFee {
+    /// Only the amm config protocol owner or admin can collect fee
+    pub owner: Pubkey,
+    /// CHECK: pool vault and lp mint authority
+    pub authority: Pubkey,
+    /// Pool state stores accumulated protocol fee amount
+    pub pool_state: Pubkey,
+    /// Amm config account stores owner
+    pub amm_config: Pubkey,
+    /// The address that holds pool tokens for token_0
+    pub token_0_vault: Pubkey,
+    /// The address that holds pool tokens for token_1
+    pub token_1_vault: Pubkey,
+    /// The mint of token_0 vault
+    pub vault_0_mint: Pubkey,
+    /// The mint of token_1 vault
+    pub vault_1_mint: Pubkey,
+    /// The address that receives the collected token_0 protocol fees
+    pub recipient_token_0_account: Pubkey,
+    /// The address that receives the collected token_1 protocol fees
+    pub recipient_token_1_account: Pubkey,
+    /// The SPL program to perform token transfers
+    pub token_program: Pubkey,
+    /// The SPL program 2022 to perform token transfers
+    pub token_program_2022: Pubkey,
+
+    /// Instruction parameters
+    ///
+    /// The maximum amount of token_0 to send, can be 0 to collect fees in only token_1
+    pub amount_0_requested: u64,
+    /// The maximum amount of token_1 to send, can be 0 to collect fees in only token_0
+    pub amount_1_requested: u64,
+}
+
+impl CollectProtocolFee {
+    /// Build the instruction, encoded by `instructions::CollectProtocolFee`
+    pub fn instruction(&self) -> Instruction {
+        let mut data = [UNINIT_BYTE; 24];
+        instructions::CollectProtocolFee::write_data(
+            &mut data,
+            self.amount_0_requested,
+            self.amount_1_requested,
+        );
+
+        build_instruction(
+            &instructions::CollectProtocolFee::ACCOUNTS,
+            [
+                &self.owner, &self.authority, &self.pool_state, &self.amm_config,
+                &self.token_0_vault, &self.token_1_vault, &self.vault_0_mint, &self.vault_1_mint,
+                &self.recipient_token_0_account, &self.recipient_token_1_account, &self.token_program, &self.token_program_2022,
+            ],
+            data,
+        )
+    }
+}
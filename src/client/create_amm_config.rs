@@ -0,0 +1,56 @@
+use solana_instruction::Instruction;
+use solana_pubkey::Pubkey;
+
+use crate::{instructions, UNINIT_BYTE};
+
+use super::build_instruction;
+
+/// Creates a new `AmmConfig` holding the fee rates pools can be created with
+///
+/// ### Accounts:
+///   0. `[WRITE, SIGNER]` owner - Address to be set as protocol owner, must be `crate::admin::ID`
+///   1. `[WRITE]` amm_config - Config state account to initialize (PDA)
+///   2. `[]` system_program - To create a new program account
+#[derive(Clone, Debug)]
+pub struct CreateAmmConfig {
+    /// Address to be set as protocol owner. Must be `crate::admin::ID`
+    pub owner: Pubkey,
+    /// Initialize config state account to store protocol owner address and fee rates.
+    pub amm_config: Pubkey,
+    /// To create a new program account
+    pub system_program: Pubkey,
+
+    /// Instruction parameters
+    pub index: u16,
+    pub trade_fee_rate: u64,
+    pub protocol_fee_rate: u64,
+    pub fund_fee_rate: u64,
+    pub create_pool_fee: u64,
+    /// The pool creator fee rate. Only read by releases supporting creator
+    /// fees, older releases ignore the trailing bytes.
+    pub creator_fee_rate: u64,
+}
+
+impl CreateAmmConfig {
+    /// Build the instruction, encoded by `instructions::CreateAmmConfig`
+    pub fn instruction(&self) -> Instruction {
+        let mut data = [UNINIT_BYTE; 50];
+        instructions::CreateAmmConfig::write_data(
+            &mut data,
+            self.index,
+            self.trade_fee_rate,
+            self.protocol_fee_rate,
+            self.fund_fee_rate,
+            self.create_pool_fee,
+            self.creator_fee_rate,
+        );
+
+        build_instruction(
+            &instructions::CreateAmmConfig::ACCOUNTS,
+            [
+                &self.owner, &self.amm_config, &self.system_program,
+            ],
+            data,
+        )
+    }
+}
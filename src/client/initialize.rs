@@ -0,0 +1,103 @@
+use solana_instruction::Instruction;
+use solana_pubkey::Pubkey;
+
+use crate::{instructions, UNINIT_BYTE};
+
+use super::build_instruction;
+
+/// Creates a pool for the given token pair and the initial price
+///
+/// ### Accounts:
+///   0. `[WRITE, SIGNER]` creator - Address paying to create the pool
+///   1. `[]` amm_config - Which config the pool belongs to
+///   2. `[]` authority - Pool vault and lp mint authority (PDA)
+///   3. `[WRITE]` pool_state - Initialize an account to store the pool state
+///   4. `[]` token_0_mint - Token_0 mint, key must be smaller than token_1 mint
+///   5. `[]` token_1_mint - Token_1 mint, key must be greater than token_0 mint
+///   6. `[WRITE]` lp_mint - Pool lp mint (PDA)
+///   7. `[WRITE]` creator_token_0 - Creator token0 account
+///   8. `[WRITE]` creator_token_1 - Creator token1 account
+///   9. `[WRITE]` creator_lp_token - Creator lp token account
+///   10. `[WRITE]` token_0_vault - Token_0 vault for the pool (PDA)
+///   11. `[WRITE]` token_1_vault - Token_1 vault for the pool (PDA)
+///   12. `[WRITE]` create_pool_fee - Create pool fee account
+///   13. `[WRITE]` observation_state - Account to store oracle observations (PDA)
+///   14. `[]` token_program - Program to create mint account and mint tokens
+///   15. `[]` token_0_program - Spl token program or token program 2022
+///   16. `[]` token_1_program - Spl token program or token program 2022
+///   17. `[]` associated_token_program - Program to create an ATA for receiving position NFT
+///   18. `[]` system_program - To create a new program account
+///   19. `[]` rent - Sysvar for program account
+#[derive(Clone, Debug)]
+pub struct Initialize {
+    /// Address paying to create the pool. Can be anyone
+    pub creator: Pubkey,
+    /// Which config the pool belongs to.
+    pub amm_config: Pubkey,
+    /// CHECK: pool vault and lp mint authority
+    pub authority: Pubkey,
+    /// CHECK: Initialize an account to store the pool state
+    pub pool_state: Pubkey,
+    /// Token_0 mint, the key must smaller then token_1 mint.
+    pub token_0_mint: Pubkey,
+    /// Token_1 mint, the key must grater then token_0 mint.
+    pub token_1_mint: Pubkey,
+    /// pool lp mint
+    pub lp_mint: Pubkey,
+    /// payer token0 account
+    pub creator_token_0: Pubkey,
+    /// creator token1 account
+    pub creator_token_1: Pubkey,
+    /// creator lp token account
+    pub creator_lp_token: Pubkey,
+    /// Token_0 vault for the pool
+    pub token_0_vault: Pubkey,
+    /// Token_1 vault for the pool
+    pub token_1_vault: Pubkey,
+    /// create pool fee account
+    pub create_pool_fee: Pubkey,
+    /// an account to store oracle observations
+    pub observation_state: Pubkey,
+    /// Program to create mint account and mint tokens
+    pub token_program: Pubkey,
+    /// Spl token program or token program 2022
+    pub token_0_program: Pubkey,
+    /// Spl token program or token program 2022
+    pub token_1_program: Pubkey,
+    /// Program to create an ATA for receiving position NFT
+    pub associated_token_program: Pubkey,
+    /// To create a new program account
+    pub system_program: Pubkey,
+    /// Sysvar for program account
+    pub rent: Pubkey,
+
+    /// Instruction parameters
+    pub init_amount_0: u64,
+    pub init_amount_1: u64,
+    pub open_time: u64,
+}
+
+impl Initialize {
+    /// Build the instruction, encoded by `instructions::Initialize`
+    pub fn instruction(&self) -> Instruction {
+        let mut data = [UNINIT_BYTE; 32];
+        instructions::Initialize::write_data(
+            &mut data,
+            self.init_amount_0,
+            self.init_amount_1,
+            self.open_time,
+        );
+
+        build_instruction(
+            &instructions::Initialize::ACCOUNTS,
+            [
+                &self.creator, &self.amm_config, &self.authority, &self.pool_state,
+                &self.token_0_mint, &self.token_1_mint, &self.lp_mint, &self.creator_token_0,
+                &self.creator_token_1, &self.creator_lp_token, &self.token_0_vault, &self.token_1_vault,
+                &self.create_pool_fee, &self.observation_state, &self.token_program, &self.token_0_program,
+                &self.token_1_program, &self.associated_token_program, &self.system_program, &self.rent,
+            ],
+            data,
+        )
+    }
+}
@@ -0,0 +1,111 @@
+use solana_instruction::Instruction;
+use solana_pubkey::Pubkey;
+
+use crate::{instructions, UNINIT_BYTE, states::CreatorFeeOn};
+
+use super::build_instruction;
+
+/// Creates a pool with permission, allowing the pool creator to collect creator fees
+///
+/// Only supported by CPMM releases with creator fees.
+///
+/// ### Accounts:
+///   0. `[WRITE, SIGNER]` payer - Address paying to create the pool, must hold a permission
+///   1. `[]` creator - Pool creator, receives the creator fees
+///   2. `[]` amm_config - Which config the pool belongs to
+///   3. `[]` authority - Pool vault and lp mint authority (PDA)
+///   4. `[WRITE]` pool_state - Initialize an account to store the pool state
+///   5. `[]` token_0_mint - Token_0 mint, key must be smaller than token_1 mint
+///   6. `[]` token_1_mint - Token_1 mint, key must be greater than token_0 mint
+///   7. `[WRITE]` lp_mint - Pool lp mint (PDA)
+///   8. `[WRITE]` payer_token_0 - Payer token0 account
+///   9. `[WRITE]` payer_token_1 - Payer token1 account
+///   10. `[WRITE]` payer_lp_token - Payer lp token account
+///   11. `[WRITE]` token_0_vault - Token_0 vault for the pool (PDA)
+///   12. `[WRITE]` token_1_vault - Token_1 vault for the pool (PDA)
+///   13. `[WRITE]` create_pool_fee - Create pool fee account
+///   14. `[WRITE]` observation_state - Account to store oracle observations (PDA)
+///   15. `[]` permission - Permission account of the payer (PDA)
+///   16. `[]` token_program - Program to create mint account and mint tokens
+///   17. `[]` token_0_program - Spl token program or token program 2022
+///   18. `[]` token_1_program - Spl token program or token program 2022
+///   19. `[]` associated_token_program - Program to create an ATA for receiving position NFT
+///   20. `[]` system_program - To create a new program account
+#[derive(Clone, Debug)]
+pub struct InitializeWithPermission {
+    /// Address paying to create the pool. Must hold a permission
+    pub payer: Pubkey,
+    /// CHECK: pool creator
+    pub creator: Pubkey,
+    /// Which config the pool belongs to.
+    pub amm_config: Pubkey,
+    /// CHECK: pool vault and lp mint authority
+    pub authority: Pubkey,
+    /// CHECK: Initialize an account to store the pool state
+    pub pool_state: Pubkey,
+    /// Token_0 mint, the key must smaller then token_1 mint.
+    pub token_0_mint: Pubkey,
+    /// Token_1 mint, the key must grater then token_0 mint.
+    pub token_1_mint: Pubkey,
+    /// pool lp mint
+    pub lp_mint: Pubkey,
+    /// payer token0 account
+    pub payer_token_0: Pubkey,
+    /// payer token1 account
+    pub payer_token_1: Pubkey,
+    /// payer lp token account
+    pub payer_lp_token: Pubkey,
+    /// Token_0 vault for the pool
+    pub token_0_vault: Pubkey,
+    /// Token_1 vault for the pool
+    pub token_1_vault: Pubkey,
+    /// create pool fee account
+    pub create_pool_fee: Pubkey,
+    /// an account to store oracle observations
+    pub observation_state: Pubkey,
+    /// Permission account of the payer
+    pub permission: Pubkey,
+    /// Program to create mint account and mint tokens
+    pub token_program: Pubkey,
+    /// Spl token program or token program 2022
+    pub token_0_program: Pubkey,
+    /// Spl token program or token program 2022
+    pub token_1_program: Pubkey,
+    /// Program to create an ATA for receiving position NFT
+    pub associated_token_program: Pubkey,
+    /// To create a new program account
+    pub system_program: Pubkey,
+
+    /// Instruction parameters
+    pub init_amount_0: u64,
+    pub init_amount_1: u64,
+    pub open_time: u64,
+    pub creator_fee_on: CreatorFeeOn,
+}
+
+impl InitializeWithPermission {
+    /// Build the instruction, encoded by `instructions::InitializeWithPermission`
+    pub fn instruction(&self) -> Instruction {
+        let mut data = [UNINIT_BYTE; 33];
+        instructions::InitializeWithPermission::write_data(
+            &mut data,
+            self.init_amount_0,
+            self.init_amount_1,
+            self.open_time,
+            self.creator_fee_on,
+        );
+
+        build_instruction(
+            &instructions::InitializeWithPermission::ACCOUNTS,
+            [
+                &self.payer, &self.creator, &self.amm_config, &self.authority,
+                &self.pool_state, &self.token_0_mint, &self.token_1_mint, &self.lp_mint,
+                &self.payer_token_0, &self.payer_token_1, &self.payer_lp_token, &self.token_0_vault,
+                &self.token_1_vault, &self.create_pool_fee, &self.observation_state, &self.permission,
+                &self.token_program, &self.token_0_program, &self.token_1_program, &self.associated_token_program,
+                &self.system_program,
+            ],
+            data,
+        )
+    }
+}
@@ -0,0 +1,81 @@
+//! Off-chain instruction builders
+//!
+//! Each builder mirrors the CPI struct of the same name in `crate::instructions`, with plain
+//! `Pubkey`s instead of `AccountInfo`s, and returns an owned `Instruction`. The discriminator,
+//! data layout and account roles are taken from the CPI struct, so both encode the same bytes.
+
+pub mod initialize;
+pub mod deposit;
+pub mod withdraw;
+pub mod swap_base_input;
+pub mod swap_base_output;
+pub mod create_amm_config;
+pub mod update_amm_config;
+pub mod update_pool_status;
+pub mod collect_protocol_fee;
+pub mod collect_fund_fee;
+pub mod initialize_with_permission;
+pub mod collect_creator_fee;
+pub mod create_permission_pda;
+pub mod close_permission_pda;
+pub mod resolve;
+
+pub use initialize::*;
+pub use deposit::*;
+pub use withdraw::*;
+pub use swap_base_input::*;
+pub use swap_base_output::*;
+pub use create_amm_config::*;
+pub use update_amm_config::*;
+pub use update_pool_status::*;
+pub use collect_protocol_fee::*;
+pub use collect_fund_fee::*;
+pub use initialize_with_permission::*;
+pub use collect_creator_fee::*;
+pub use create_permission_pda::*;
+pub use close_permission_pda::*;
+pub use resolve::*;
+
+use core::mem::MaybeUninit;
+
+use solana_instruction::{AccountMeta, Instruction};
+use solana_pubkey::Pubkey;
+
+use crate::instructions::AccountRole;
+
+/// The program id the instructions are sent to, `crate::program_id()`
+#[inline(always)]
+pub fn program_id() -> Pubkey {
+    Pubkey::new_from_array(*crate::program_id())
+}
+
+impl AccountRole {
+    /// Return the owned `AccountMeta` of `pubkey` with this role
+    #[inline(always)]
+    pub fn to_account_meta(self, pubkey: Pubkey) -> AccountMeta {
+        AccountMeta {
+            pubkey,
+            is_signer: self.is_signer,
+            is_writable: self.is_writable,
+        }
+    }
+}
+
+/// Build an instruction for `crate::program_id()` from the data written by a CPI struct's `write_data`
+#[inline(always)]
+fn build_instruction<const N: usize, const M: usize>(
+    roles: &[AccountRole; N],
+    accounts: [&Pubkey; N],
+    data: [MaybeUninit<u8>; M],
+) -> Instruction {
+    Instruction {
+        program_id: program_id(),
+        accounts: roles
+            .iter()
+            .zip(accounts)
+            .map(|(role, pubkey)| role.to_account_meta(*pubkey))
+            .collect(),
+        // SAFETY: `write_data` initializes every byte of the instruction data.
+        data: data.iter().map(|byte| unsafe { byte.assume_init() }).collect(),
+    }
+}
@@ -0,0 +1,389 @@
+//! Resolution of the instruction accounts from a decoded `PoolState`
+//!
+//! The pool account stores its vaults, mints, token programs and observation account, so a
+//! pool id, its already fetched `PoolState` and the user wallet are enough to build the swap,
+//! deposit and withdraw instructions without any RPC access. User token accounts are the
+//! associated token accounts of the wallet, under the token program of each mint.
+
+use solana_pubkey::Pubkey;
+
+use crate::{
+    associated_token_program, memo_program, pda, states::PoolState, token_program,
+    token_program_2022,
+};
+
+use super::{Deposit, SwapBaseInput, SwapBaseOutput, Withdraw};
+
+/// Return the pool vault and lp mint authority, see `pda::find_authority_address`
+#[inline]
+pub fn authority_address() -> Pubkey {
+    Pubkey::new_from_array(pda::find_authority_address().0)
+}
+
+/// Return the associated token account of `wallet` for `mint`, owned by `token_program`
+#[inline]
+pub fn associated_token_address(wallet: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    let seeds = [wallet.as_ref(), token_program.as_ref(), mint.as_ref()];
+    let (address, _) = pda::try_find_program_address(&seeds, &associated_token_program::ID)
+        .expect("Unable to find a viable program address bump seed");
+    Pubkey::new_from_array(address)
+}
+
+/// Accounts of `SwapBaseInput` and `SwapBaseOutput`
+///
+/// The input and output sides follow the mint being sold, see `SwapAccounts::resolve`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SwapAccounts {
+    pub payer: Pubkey,
+    pub authority: Pubkey,
+    pub amm_config: Pubkey,
+    pub pool_state: Pubkey,
+    pub input_token_account: Pubkey,
+    pub output_token_account: Pubkey,
+    pub input_vault: Pubkey,
+    pub output_vault: Pubkey,
+    pub input_token_program: Pubkey,
+    pub output_token_program: Pubkey,
+    pub input_token_mint: Pubkey,
+    pub output_token_mint: Pubkey,
+    pub observation_state: Pubkey,
+}
+
+impl SwapAccounts {
+    /// Resolve the accounts of `wallet` selling `input_mint` to the pool `pool_id`.
+    ///
+    /// Returns `None` if `input_mint` is not one of the pool mints.
+    pub fn resolve(
+        pool_id: &Pubkey,
+        pool: &PoolState,
+        wallet: &Pubkey,
+        input_mint: &Pubkey,
+    ) -> Option<Self> {
+        let token_0_mint = Pubkey::new_from_array(pool.token_0_mint);
+        let token_1_mint = Pubkey::new_from_array(pool.token_1_mint);
+        let token_0_vault = Pubkey::new_from_array(pool.token_0_vault);
+        let token_1_vault = Pubkey::new_from_array(pool.token_1_vault);
+        let token_0_program = Pubkey::new_from_array(pool.token_0_program);
+        let token_1_program = Pubkey::new_from_array(pool.token_1_program);
+
+        let (input, output) = if *input_mint == token_0_mint {
+            (
+                (token_0_mint, token_0_vault, token_0_program),
+                (token_1_mint, token_1_vault, token_1_program),
+            )
+        } else if *input_mint == token_1_mint {
+            (
+                (token_1_mint, token_1_vault, token_1_program),
+                (token_0_mint, token_0_vault, token_0_program),
+            )
+        } else {
+            return None;
+        };
+
+        Some(Self {
+            payer: *wallet,
+            authority: authority_address(),
+            amm_config: Pubkey::new_from_array(pool.amm_config),
+            pool_state: *pool_id,
+            input_token_account: associated_token_address(wallet, &input.0, &input.2),
+            output_token_account: associated_token_address(wallet, &output.0, &output.2),
+            input_vault: input.1,
+            output_vault: output.1,
+            input_token_program: input.2,
+            output_token_program: output.2,
+            input_token_mint: input.0,
+            output_token_mint: output.0,
+            observation_state: Pubkey::new_from_array(pool.observation_key),
+        })
+    }
+
+    /// Build a `SwapBaseInput` selling exactly `amount_in`
+    pub fn base_input(&self, amount_in: u64, minimum_amount_out: u64) -> SwapBaseInput {
+        SwapBaseInput {
+            payer: self.payer,
+            authority: self.authority,
+            amm_config: self.amm_config,
+            pool_state: self.pool_state,
+            input_token_account: self.input_token_account,
+            output_token_account: self.output_token_account,
+            input_vault: self.input_vault,
+            output_vault: self.output_vault,
+            input_token_program: self.input_token_program,
+            output_token_program: self.output_token_program,
+            input_token_mint: self.input_token_mint,
+            output_token_mint: self.output_token_mint,
+            observation_state: self.observation_state,
+            amount_in,
+            minimum_amount_out,
+        }
+    }
+
+    /// Build a `SwapBaseOutput` buying exactly `amount_out`
+    pub fn base_output(&self, max_amount_in: u64, amount_out: u64) -> SwapBaseOutput {
+        SwapBaseOutput {
+            payer: self.payer,
+            authority: self.authority,
+            amm_config: self.amm_config,
+            pool_state: self.pool_state,
+            input_token_account: self.input_token_account,
+            output_token_account: self.output_token_account,
+            input_vault: self.input_vault,
+            output_vault: self.output_vault,
+            input_token_program: self.input_token_program,
+            output_token_program: self.output_token_program,
+            input_token_mint: self.input_token_mint,
+            output_token_mint: self.output_token_mint,
+            observation_state: self.observation_state,
+            max_amount_in,
+            amount_out,
+        }
+    }
+}
+
+/// Accounts of `Deposit` and `Withdraw`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LiquidityAccounts {
+    pub owner: Pubkey,
+    pub authority: Pubkey,
+    pub pool_state: Pubkey,
+    pub owner_lp_token: Pubkey,
+    pub token_0_account: Pubkey,
+    pub token_1_account: Pubkey,
+    pub token_0_vault: Pubkey,
+    pub token_1_vault: Pubkey,
+    pub token_program: Pubkey,
+    pub token_program_2022: Pubkey,
+    pub vault_0_mint: Pubkey,
+    pub vault_1_mint: Pubkey,
+    pub lp_mint: Pubkey,
+}
+
+impl LiquidityAccounts {
+    /// Resolve the accounts of `wallet` providing liquidity to the pool `pool_id`.
+    ///
+    /// The lp mint is always an SPL Token mint.
+    pub fn resolve(pool_id: &Pubkey, pool: &PoolState, wallet: &Pubkey) -> Self {
+        let token_program = Pubkey::new_from_array(token_program::ID);
+        let vault_0_mint = Pubkey::new_from_array(pool.token_0_mint);
+        let vault_1_mint = Pubkey::new_from_array(pool.token_1_mint);
+        let lp_mint = Pubkey::new_from_array(pool.lp_mint);
+
+        Self {
+            owner: *wallet,
+            authority: authority_address(),
+            pool_state: *pool_id,
+            owner_lp_token: associated_token_address(wallet, &lp_mint, &token_program),
+            token_0_account: associated_token_address(
+                wallet,
+                &vault_0_mint,
+                &Pubkey::new_from_array(pool.token_0_program),
+            ),
+            token_1_account: associated_token_address(
+                wallet,
+                &vault_1_mint,
+                &Pubkey::new_from_array(pool.token_1_program),
+            ),
+            token_0_vault: Pubkey::new_from_array(pool.token_0_vault),
+            token_1_vault: Pubkey::new_from_array(pool.token_1_vault),
+            token_program,
+            token_program_2022: Pubkey::new_from_array(token_program_2022::ID),
+            vault_0_mint,
+            vault_1_mint,
+            lp_mint,
+        }
+    }
+
+    /// Build a `Deposit` minting `lp_token_amount`
+    pub fn deposit(
+        &self,
+        lp_token_amount: u64,
+        maximum_token_0_amount: u64,
+        maximum_token_1_amount: u64,
+    ) -> Deposit {
+        Deposit {
+            owner: self.owner,
+            authority: self.authority,
+            pool_state: self.pool_state,
+            owner_lp_token: self.owner_lp_token,
+            token_0_account: self.token_0_account,
+            token_1_account: self.token_1_account,
+            token_0_vault: self.token_0_vault,
+            token_1_vault: self.token_1_vault,
+            token_program: self.token_program,
+            token_program_2022: self.token_program_2022,
+            vault_0_mint: self.vault_0_mint,
+            vault_1_mint: self.vault_1_mint,
+            lp_mint: self.lp_mint,
+            lp_token_amount,
+            maximum_token_0_amount,
+            maximum_token_1_amount,
+        }
+    }
+
+    /// Build a `Withdraw` burning `lp_token_amount`
+    pub fn withdraw(
+        &self,
+        lp_token_amount: u64,
+        minimum_token_0_amount: u64,
+        minimum_token_1_amount: u64,
+    ) -> Withdraw {
+        Withdraw {
+            owner: self.owner,
+            authority: self.authority,
+            pool_state: self.pool_state,
+            owner_lp_token: self.owner_lp_token,
+            token_0_account: self.token_0_account,
+            token_1_account: self.token_1_account,
+            token_0_vault: self.token_0_vault,
+            token_1_vault: self.token_1_vault,
+            token_program: self.token_program,
+            token_program_2022: self.token_program_2022,
+            vault_0_mint: self.vault_0_mint,
+            vault_1_mint: self.vault_1_mint,
+            lp_mint: self.lp_mint,
+            memo_program: Pubkey::new_from_array(memo_program::ID),
+            lp_token_amount,
+            minimum_token_0_amount,
+            minimum_token_1_amount,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_utils::PoolStateBuilder, AUTH_SEED};
+
+    const POOL_ID: Pubkey = Pubkey::new_from_array([8; 32]);
+    const WALLET: Pubkey = Pubkey::new_from_array([9; 32]);
+    const TOKEN_0_MINT: [u8; 32] = [1; 32];
+    const TOKEN_1_MINT: [u8; 32] = [2; 32];
+
+    /// A pool of an SPL Token mint and a Token-2022 mint
+    fn pool_state() -> PoolState {
+        PoolStateBuilder::new()
+            .amm_config([7; 32])
+            .mints(TOKEN_0_MINT, TOKEN_1_MINT)
+            .vaults([3; 32], [4; 32])
+            .token_programs(token_program::ID, token_program_2022::ID)
+            .lp_mint([5; 32])
+            .observation_key([6; 32])
+            .build()
+    }
+
+    /// Derive the associated token account with `solana_pubkey`, not the `pda` derivation
+    /// used by `resolve`
+    fn ata(mint: [u8; 32], token_program: [u8; 32]) -> Pubkey {
+        let seeds: [&[u8]; 3] = [WALLET.as_ref(), &token_program, &mint];
+        Pubkey::find_program_address(&seeds, &associated_token_program::ID.into()).0
+    }
+
+    fn authority() -> Pubkey {
+        Pubkey::find_program_address(&[AUTH_SEED.as_bytes()], &crate::ID.into()).0
+    }
+
+    #[test]
+    fn derives_addresses_like_solana_pubkey() {
+        assert_eq!(authority_address(), authority());
+        let token_program = Pubkey::new_from_array(token_program::ID);
+        let mint = Pubkey::new_from_array(TOKEN_0_MINT);
+        assert_eq!(
+            associated_token_address(&WALLET, &mint, &token_program),
+            ata(TOKEN_0_MINT, token_program::ID)
+        );
+    }
+
+    #[test]
+    fn token_2022_accounts_are_derived_under_token_2022() {
+        let token_2022_ata = ata(TOKEN_1_MINT, token_program_2022::ID);
+        assert_ne!(token_2022_ata, ata(TOKEN_1_MINT, token_program::ID));
+
+        let pool_state = pool_state();
+        let swap = SwapAccounts::resolve(&POOL_ID, &pool_state, &WALLET, &TOKEN_1_MINT.into());
+        assert_eq!(swap.unwrap().input_token_account, token_2022_ata);
+        let accounts = LiquidityAccounts::resolve(&POOL_ID, &pool_state, &WALLET);
+        assert_eq!(accounts.token_1_account, token_2022_ata);
+    }
+
+    #[test]
+    fn swap_token_0_for_token_1() {
+        let swap = SwapAccounts::resolve(&POOL_ID, &pool_state(), &WALLET, &TOKEN_0_MINT.into());
+
+        assert_eq!(
+            swap,
+            Some(SwapAccounts {
+                payer: WALLET,
+                authority: authority(),
+                amm_config: Pubkey::new_from_array([7; 32]),
+                pool_state: POOL_ID,
+                input_token_account: ata(TOKEN_0_MINT, token_program::ID),
+                output_token_account: ata(TOKEN_1_MINT, token_program_2022::ID),
+                input_vault: Pubkey::new_from_array([3; 32]),
+                output_vault: Pubkey::new_from_array([4; 32]),
+                input_token_program: Pubkey::new_from_array(token_program::ID),
+                output_token_program: Pubkey::new_from_array(token_program_2022::ID),
+                input_token_mint: Pubkey::new_from_array(TOKEN_0_MINT),
+                output_token_mint: Pubkey::new_from_array(TOKEN_1_MINT),
+                observation_state: Pubkey::new_from_array([6; 32]),
+            })
+        );
+    }
+
+    #[test]
+    fn swap_token_1_for_token_0() {
+        let swap = SwapAccounts::resolve(&POOL_ID, &pool_state(), &WALLET, &TOKEN_1_MINT.into());
+
+        assert_eq!(
+            swap,
+            Some(SwapAccounts {
+                payer: WALLET,
+                authority: authority(),
+                amm_config: Pubkey::new_from_array([7; 32]),
+                pool_state: POOL_ID,
+                input_token_account: ata(TOKEN_1_MINT, token_program_2022::ID),
+                output_token_account: ata(TOKEN_0_MINT, token_program::ID),
+                input_vault: Pubkey::new_from_array([4; 32]),
+                output_vault: Pubkey::new_from_array([3; 32]),
+                input_token_program: Pubkey::new_from_array(token_program_2022::ID),
+                output_token_program: Pubkey::new_from_array(token_program::ID),
+                input_token_mint: Pubkey::new_from_array(TOKEN_1_MINT),
+                output_token_mint: Pubkey::new_from_array(TOKEN_0_MINT),
+                observation_state: Pubkey::new_from_array([6; 32]),
+            })
+        );
+    }
+
+    #[test]
+    fn swap_of_a_mint_not_in_the_pool() {
+        let pool_state = pool_state();
+        for mint in [[5; 32], [0; 32], WALLET.to_bytes()] {
+            let swap = SwapAccounts::resolve(&POOL_ID, &pool_state, &WALLET, &mint.into());
+            assert_eq!(swap, None);
+        }
+    }
+
+    #[test]
+    fn liquidity_accounts() {
+        let accounts = LiquidityAccounts::resolve(&POOL_ID, &pool_state(), &WALLET);
+
+        assert_eq!(
+            accounts,
+            LiquidityAccounts {
+                owner: WALLET,
+                authority: authority(),
+                pool_state: POOL_ID,
+                // the lp mint is always an SPL Token mint
+                owner_lp_token: ata([5; 32], token_program::ID),
+                token_0_account: ata(TOKEN_0_MINT, token_program::ID),
+                token_1_account: ata(TOKEN_1_MINT, token_program_2022::ID),
+                token_0_vault: Pubkey::new_from_array([3; 32]),
+                token_1_vault: Pubkey::new_from_array([4; 32]),
+                token_program: Pubkey::new_from_array(token_program::ID),
+                token_program_2022: Pubkey::new_from_array(token_program_2022::ID),
+                vault_0_mint: Pubkey::new_from_array(TOKEN_0_MINT),
+                vault_1_mint: Pubkey::new_from_array(TOKEN_1_MINT),
+                lp_mint: Pubkey::new_from_array([5; 32]),
+            }
+        );
+    }
+}
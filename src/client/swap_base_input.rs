@@ -0,0 +1,75 @@
+use solana_instruction::Instruction;
+use solana_pubkey::Pubkey;
+
+use crate::{instructions, UNINIT_BYTE};
+
+use super::build_instruction;
+
+/// Swap the tokens in the pool base input amount
+///
+/// ### Accounts:
+///   0. `[SIGNER]` payer - The user performing the swap
+///   1. `[]` authority - Pool vault and lp mint authority (PDA)
+///   2. `[]` amm_config - The factory state to read protocol fees
+///   3. `[WRITE]` pool_state - The program account of the pool in which the swap will be performed
+///   4. `[WRITE]` input_token_account - The user token account for input token
+///   5. `[WRITE]` output_token_account - The user token account for output token
+///   6. `[WRITE]` input_vault - The vault token account for input token
+///   7. `[WRITE]` output_vault - The vault token account for output token
+///   8. `[]` input_token_program - SPL program for input token transfers
+///   9. `[]` output_token_program - SPL program for output token transfers
+///   10. `[]` input_token_mint - The mint of input token
+///   11. `[]` output_token_mint - The mint of output token
+///   12. `[WRITE]` observation_state - The program account for the most recent oracle observation
+#[derive(Clone, Debug)]
+pub struct SwapBaseInput {
+    /// The user performing the swap
+    pub payer: Pubkey,
+    /// CHECK: pool vault and lp mint authority
+    pub authority: Pubkey,
+    /// The factory state to read protocol fees
+    pub amm_config: Pubkey,
+    /// The program account of the pool in which the swap will be performed
+    pub pool_state: Pubkey,
+    /// The user token account for input token
+    pub input_token_account: Pubkey,
+    /// The user token account for output token
+    pub output_token_account: Pubkey,
+    /// The vault token account for input token
+    pub input_vault: Pubkey,
+    /// The vault token account for output token
+    pub output_vault: Pubkey,
+    /// SPL program for input token transfers
+    pub input_token_program: Pubkey,
+    /// SPL program for output token transfers
+    pub output_token_program: Pubkey,
+    /// The mint of input token
+    pub input_token_mint: Pubkey,
+    /// The mint of output token
+    pub output_token_mint: Pubkey,
+    /// The program account for the most recent oracle observation
+    pub observation_state: Pubkey,
+
+    /// Instruction parameters
+    pub amount_in: u64,
+    pub minimum_amount_out: u64,
+}
+
+impl SwapBaseInput {
+    /// Build the instruction, encoded by `instructions::SwapBaseInput`
+    pub fn instruction(&self) -> Instruction {
+        let mut data = [UNINIT_BYTE; 24];
+        instructions::SwapBaseInput::write_data(&mut data, self.amount_in, self.minimum_amount_out);
+
+        build_instruction(
+            &instructions::SwapBaseInput::ACCOUNTS,
+            [
+                &self.payer, &self.authority, &self.amm_config, &self.pool_state,
+                &self.input_token_account, &self.output_token_account, &self.input_vault, &self.output_vault,
+                &self.input_token_program, &self.output_token_program, &self.input_token_mint, &self.output_token_mint,
+                &self.observation_state,
+            ],
+            data,
+        )
+    }
+}
@@ -0,0 +1,50 @@
+use solana_instruction::Instruction;
+use solana_pubkey::Pubkey;
+
+use crate::{instructions, UNINIT_BYTE};
+
+use super::build_instruction;
+
+/// The field of an `AmmConfig` changed by `UpdateAmmConfig`
+///
+/// The on-chain `instructions::UpdateAmmConfigField`, the owner variants carrying the
+/// address of the new owner, which is passed as the first remaining account of the
+/// instruction.
+pub type UpdateAmmConfigParam = instructions::UpdateAmmConfigField<Pubkey>;
+
+/// Updates a field of an `AmmConfig`
+///
+/// ### Accounts:
+///   0. `[SIGNER]` owner - The amm config owner or admin, must be `crate::admin::ID`
+///   1. `[WRITE]` amm_config - Amm config account to be changed
+///   2. `[]` new_owner - Only for `NewProtocolOwner` and `NewFundOwner`
+#[derive(Clone, Debug)]
+pub struct UpdateAmmConfig {
+    /// The amm config owner or admin. Must be `crate::admin::ID`
+    pub owner: Pubkey,
+    /// Amm config account to be changed
+    pub amm_config: Pubkey,
+
+    /// Instruction parameters
+    pub param: UpdateAmmConfigParam,
+}
+
+impl UpdateAmmConfig {
+    /// Build the instruction, encoded by `instructions::UpdateAmmConfig`
+    pub fn instruction(&self) -> Instruction {
+        let mut data = [UNINIT_BYTE; 17];
+        instructions::UpdateAmmConfig::write_data(&mut data, self.param.param(), self.param.value());
+
+        let mut instruction = build_instruction(
+            &instructions::UpdateAmmConfig::ACCOUNTS,
+            [&self.owner, &self.amm_config],
+            data,
+        );
+        if let Some(new_owner) = self.param.new_owner() {
+            instruction
+                .accounts
+                .push(instructions::UpdateAmmConfig::NEW_OWNER_ACCOUNT.to_account_meta(new_owner));
+        }
+        instruction
+    }
+}
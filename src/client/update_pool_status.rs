@@ -0,0 +1,40 @@
+use solana_instruction::Instruction;
+use solana_pubkey::Pubkey;
+
+use crate::{instructions, UNINIT_BYTE};
+
+use super::build_instruction;
+
+/// Update pool status for given value
+///
+/// ### Accounts:
+///   0. `[SIGNER]` authority - Admin authority, must be `crate::admin::ID`
+///   1. `[WRITE]` pool_state - Pool state account
+#[derive(Clone, Debug)]
+pub struct UpdatePoolStatus {
+    /// Admin authority. Must be `crate::admin::ID`
+    pub authority: Pubkey,
+    /// Pool state account
+    pub pool_state: Pubkey,
+
+    /// Instruction parameters
+    ///
+    /// Bitwise representation of the pool status, see `PoolState::status`
+    pub status: u8,
+}
+
+impl UpdatePoolStatus {
+    /// Build the instruction, encoded by `instructions::UpdatePoolStatus`
+    pub fn instruction(&self) -> Instruction {
+        let mut data = [UNINIT_BYTE; 9];
+        instructions::UpdatePoolStatus::write_data(&mut data, self.status);
+
+        build_instruction(
+            &instructions::UpdatePoolStatus::ACCOUNTS,
+            [
+                &self.authority, &self.pool_state,
+            ],
+            data,
+        )
+    }
+}
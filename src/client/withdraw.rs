@@ -0,0 +1,84 @@
+use solana_instruction::Instruction;
+use solana_pubkey::Pubkey;
+
+use crate::{instructions, UNINIT_BYTE};
+
+use super::build_instruction;
+
+/// Withdraw lp for token0 and token1
+///
+/// ### Accounts:
+///   0. `[SIGNER]` owner - Pays to mint the position
+///   1. `[]` authority - Pool vault and lp mint authority (PDA)
+///   2. `[WRITE]` pool_state - Pool state account
+///   3. `[WRITE]` owner_lp_token - Owner lp token account
+///   4. `[WRITE]` token_0_account - The token account for receive token_0
+///   5. `[WRITE]` token_1_account - The token account for receive token_1
+///   6. `[WRITE]` token_0_vault - The address that holds pool tokens for token_0
+///   7. `[WRITE]` token_1_vault - The address that holds pool tokens for token_1
+///   8. `[]` token_program - token Program
+///   9. `[]` token_program_2022 - Token program 2022
+///   10. `[]` vault_0_mint - The mint of token_0 vault
+///   11. `[]` vault_1_mint - The mint of token_1 vault
+///   12. `[WRITE]` lp_mint - Pool lp token mint
+///   13. `[]` memo_program - memo program
+#[derive(Clone, Debug)]
+pub struct Withdraw {
+    /// Pays to mint the position
+    pub owner: Pubkey,
+    /// CHECK: pool vault and lp mint authority
+    pub authority: Pubkey,
+    /// Pool state account
+    pub pool_state: Pubkey,
+    /// Owner lp token account
+    pub owner_lp_token: Pubkey,
+    /// The token account for receive token_0
+    pub token_0_account: Pubkey,
+    /// The token account for receive token_1
+    pub token_1_account: Pubkey,
+    /// The address that holds pool tokens for token_0
+    pub token_0_vault: Pubkey,
+    /// The address that holds pool tokens for token_1
+    pub token_1_vault: Pubkey,
+    /// token Program
+    pub token_program: Pubkey,
+    /// Token program 2022
+    pub token_program_2022: Pubkey,
+    /// The mint of token_0 vault
+    pub vault_0_mint: Pubkey,
+    /// The mint of token_1 vault
+    pub vault_1_mint: Pubkey,
+    /// Pool lp token mint
+    pub lp_mint: Pubkey,
+    /// memo program
+    pub memo_program: Pubkey,
+
+    /// Instruction parameters
+    pub lp_token_amount: u64,
+    pub minimum_token_0_amount: u64,
+    pub minimum_token_1_amount: u64,
+}
+
+impl Withdraw {
+    /// Build the instruction, encoded by `instructions::Withdraw`
+    pub fn instruction(&self) -> Instruction {
+        let mut data = [UNINIT_BYTE; 32];
+        instructions::Withdraw::write_data(
+            &mut data,
+            self.lp_token_amount,
+            self.minimum_token_0_amount,
+            self.minimum_token_1_amount,
+        );
+
+        build_instruction(
+            &instructions::Withdraw::ACCOUNTS,
+            [
+                &self.owner, &self.authority, &self.pool_state, &self.owner_lp_token,
+                &self.token_0_account, &self.token_1_account, &self.token_0_vault, &self.token_1_vault,
+                &self.token_program, &self.token_program_2022, &self.vault_0_mint, &self.vault_1_mint,
+                &self.lp_mint, &self.memo_program,
+            ],
+            data,
+        )
+    }
+}
@@ -0,0 +1,357 @@
+//! Constant product swap calculations, mirroring the program's `CurveCalculator`
+
+use super::fees::{self, FeeRates};
+
+/// The direction of a trade
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TradeDirection {
+    /// Input token 0, output token 1
+    ZeroForOne,
+    /// Input token 1, output token 0
+    OneForZero,
+}
+
+impl TradeDirection {
+    /// Given a trade direction, gives the opposite direction of the trade
+    #[inline]
+    pub fn opposite(&self) -> TradeDirection {
+        match self {
+            TradeDirection::ZeroForOne => TradeDirection::OneForZero,
+            TradeDirection::OneForZero => TradeDirection::ZeroForOne,
+        }
+    }
+}
+
+/// Encodes all results of swapping from an input token to an output token
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SwapResult {
+    /// Amount of input token paid by the user, fees included
+    pub input_amount: u128,
+    /// Amount of output token received by the user
+    pub output_amount: u128,
+    /// Amount of input tokens taken as trade fee
+    pub trade_fee: u128,
+    /// Part of the trade fee going to the protocol
+    pub protocol_fee: u128,
+    /// Part of the trade fee going to the fund
+    pub fund_fee: u128,
+    /// Amount of tokens going to the pool creator, in the input token when the
+    /// creator fee is charged on input, in the output token otherwise
+    pub creator_fee: u128,
+}
+
+/// Ceiling division used by the program for the base output swap.
+///
+/// Dividing a small number by a bigger one rounds to the nearest integer
+/// instead of always returning 1.
+#[inline]
+fn checked_ceil_div(numerator: u128, denominator: u128) -> Option<u128> {
+    let quotient = numerator.checked_div(denominator)?;
+    if quotient == 0 {
+        return if numerator.checked_mul(2)? >= denominator {
+            Some(1)
+        } else {
+            Some(0)
+        };
+    }
+    if numerator.checked_rem(denominator)? > 0 {
+        quotient.checked_add(1)
+    } else {
+        Some(quotient)
+    }
+}
+
+/// Output amount of a constant product swap of `input_amount`, rounded down.
+///
+/// (x + delta_x) * (y - delta_y) = x * y
+/// delta_y = (delta_x * y) / (x + delta_x)
+#[inline]
+pub fn swap_base_input_without_fees(
+    input_amount: u128,
+    input_vault_amount: u128,
+    output_vault_amount: u128,
+) -> Option<u128> {
+    let numerator = input_amount.checked_mul(output_vault_amount)?;
+    let denominator = input_vault_amount.checked_add(input_amount)?;
+    numerator.checked_div(denominator)
+}
+
+/// Input amount of a constant product swap returning `output_amount`, rounded up.
+///
+/// (x + delta_x) * (y - delta_y) = x * y
+/// delta_x = (x * delta_y) / (y - delta_y)
+#[inline]
+pub fn swap_base_output_without_fees(
+    output_amount: u128,
+    input_vault_amount: u128,
+    output_vault_amount: u128,
+) -> Option<u128> {
+    let numerator = input_vault_amount.checked_mul(output_amount)?;
+    let denominator = output_vault_amount.checked_sub(output_amount)?;
+    checked_ceil_div(numerator, denominator)
+}
+
+/// Subtract fees and calculate how much output token will be provided
+/// given an amount of input token.
+///
+/// Vault amounts must exclude the fees owed to the protocol, fund and creator,
+/// see `PoolState::vault_amount_without_fee`.
+pub fn swap_base_input(
+    input_amount: u128,
+    input_vault_amount: u128,
+    output_vault_amount: u128,
+    fee_rates: &FeeRates,
+    is_creator_fee_on_input: bool,
+) -> Option<SwapResult> {
+    let mut creator_fee = 0;
+
+    let trade_fee = fees::trading_fee(input_amount, fee_rates.trade_fee_rate)?;
+    let input_amount_less_fees = if is_creator_fee_on_input {
+        creator_fee = fees::creator_fee(input_amount, fee_rates.creator_fee_rate)?;
+        input_amount.checked_sub(trade_fee)?.checked_sub(creator_fee)?
+    } else {
+        input_amount.checked_sub(trade_fee)?
+    };
+    let protocol_fee = fees::protocol_fee(trade_fee, fee_rates.protocol_fee_rate)?;
+    let fund_fee = fees::fund_fee(trade_fee, fee_rates.fund_fee_rate)?;
+
+    let output_amount_swapped = swap_base_input_without_fees(
+        input_amount_less_fees,
+        input_vault_amount,
+        output_vault_amount,
+    )?;
+
+    let output_amount = if is_creator_fee_on_input {
+        output_amount_swapped
+    } else {
+        creator_fee = fees::creator_fee(output_amount_swapped, fee_rates.creator_fee_rate)?;
+        output_amount_swapped.checked_sub(creator_fee)?
+    };
+
+    Some(SwapResult {
+        input_amount,
+        output_amount,
+        trade_fee,
+        protocol_fee,
+        fund_fee,
+        creator_fee,
+    })
+}
+
+/// Calculate how much input token, fees included, is required to receive
+/// `output_amount` of output token.
+///
+/// Vault amounts must exclude the fees owed to the protocol, fund and creator,
+/// see `PoolState::vault_amount_without_fee`.
+pub fn swap_base_output(
+    output_amount: u128,
+    input_vault_amount: u128,
+    output_vault_amount: u128,
+    fee_rates: &FeeRates,
+    is_creator_fee_on_input: bool,
+) -> Option<SwapResult> {
+    let trade_fee: u128;
+    let mut creator_fee = 0;
+
+    let actual_output_amount = if is_creator_fee_on_input {
+        output_amount
+    } else {
+        let output_amount_with_creator_fee =
+            fees::calculate_pre_fee_amount(output_amount, fee_rates.creator_fee_rate)?;
+        creator_fee = output_amount_with_creator_fee.checked_sub(output_amount)?;
+        output_amount_with_creator_fee
+    };
+
+    let input_amount_swapped = swap_base_output_without_fees(
+        actual_output_amount,
+        input_vault_amount,
+        output_vault_amount,
+    )?;
+
+    let input_amount = if is_creator_fee_on_input {
+        let input_amount_with_fee = fees::calculate_pre_fee_amount(
+            input_amount_swapped,
+            fee_rates.trade_fee_rate.checked_add(fee_rates.creator_fee_rate)?,
+        )?;
+        let total_fee = input_amount_with_fee.checked_sub(input_amount_swapped)?;
+        creator_fee = fees::split_creator_fee(
+            total_fee,
+            fee_rates.trade_fee_rate,
+            fee_rates.creator_fee_rate,
+        )?;
+        trade_fee = total_fee.checked_sub(creator_fee)?;
+        input_amount_with_fee
+    } else {
+        let input_amount_with_fee =
+            fees::calculate_pre_fee_amount(input_amount_swapped, fee_rates.trade_fee_rate)?;
+        trade_fee = input_amount_with_fee.checked_sub(input_amount_swapped)?;
+        input_amount_with_fee
+    };
+    let protocol_fee = fees::protocol_fee(trade_fee, fee_rates.protocol_fee_rate)?;
+    let fund_fee = fees::fund_fee(trade_fee, fee_rates.fund_fee_rate)?;
+
+    Some(SwapResult {
+        input_amount,
+        output_amount,
+        trade_fee,
+        protocol_fee,
+        fund_fee,
+        creator_fee,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATES: FeeRates = FeeRates {
+        trade_fee_rate: 2_500,
+        creator_fee_rate: 500,
+        protocol_fee_rate: 120_000,
+        fund_fee_rate: 40_000,
+    };
+
+    #[test]
+    fn ceil_div_rounds_small_quotients_to_nearest() {
+        assert_eq!(checked_ceil_div(1, 3), Some(0));
+        assert_eq!(checked_ceil_div(2, 3), Some(1));
+        // exactly half rounds up
+        assert_eq!(checked_ceil_div(3, 6), Some(1));
+        assert_eq!(checked_ceil_div(0, 5), Some(0));
+        assert_eq!(checked_ceil_div(6, 3), Some(2));
+        assert_eq!(checked_ceil_div(7, 3), Some(3));
+        assert_eq!(checked_ceil_div(1, 0), None);
+        assert_eq!(checked_ceil_div(u128::MAX, u128::MAX), Some(1));
+    }
+
+    #[test]
+    fn swaps_without_fees() {
+        // 10_000 * 2_000_000 / 1_010_000 = 19_801.98
+        assert_eq!(swap_base_input_without_fees(10_000, 1_000_000, 2_000_000), Some(19_801));
+        // 2_000_000 * 10_000 / 990_000 = 20_202.02
+        assert_eq!(swap_base_output_without_fees(10_000, 2_000_000, 1_000_000), Some(20_203));
+    }
+
+    #[test]
+    fn swaps_against_empty_vaults() {
+        assert_eq!(swap_base_input_without_fees(0, 1_000_000, 2_000_000), Some(0));
+        assert_eq!(swap_base_input_without_fees(10_000, 1_000_000, 0), Some(0));
+        // an empty input vault gives the whole output vault away
+        assert_eq!(swap_base_input_without_fees(10, 0, 100), Some(100));
+        assert_eq!(swap_base_input_without_fees(0, 0, 100), None);
+
+        assert_eq!(swap_base_output_without_fees(0, 2_000_000, 1_000_000), Some(0));
+        assert_eq!(swap_base_output_without_fees(1_000_000, 2_000_000, 1_000_000), None);
+        assert_eq!(swap_base_output_without_fees(1, 2_000_000, 0), None);
+    }
+
+    #[test]
+    fn swap_base_input_without_creator_fee() {
+        let rates = FeeRates { creator_fee_rate: 0, ..RATES };
+        assert_eq!(
+            swap_base_input(10_000, 1_000_000, 2_000_000, &rates, true),
+            Some(SwapResult {
+                input_amount: 10_000,
+                output_amount: 19_752,
+                trade_fee: 25,
+                protocol_fee: 3,
+                fund_fee: 1,
+                creator_fee: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn swap_base_input_with_creator_fee_on_input() {
+        // 9_970 * 2_000_000 / 1_009_970 = 19_743.16
+        assert_eq!(
+            swap_base_input(10_000, 1_000_000, 2_000_000, &RATES, true),
+            Some(SwapResult {
+                input_amount: 10_000,
+                output_amount: 19_743,
+                trade_fee: 25,
+                protocol_fee: 3,
+                fund_fee: 1,
+                creator_fee: 5,
+            })
+        );
+    }
+
+    #[test]
+    fn swap_base_input_with_creator_fee_on_output() {
+        // 19_752 swapped, of which ceil(9.876) goes to the creator
+        assert_eq!(
+            swap_base_input(10_000, 1_000_000, 2_000_000, &RATES, false),
+            Some(SwapResult {
+                input_amount: 10_000,
+                output_amount: 19_742,
+                trade_fee: 25,
+                protocol_fee: 3,
+                fund_fee: 1,
+                creator_fee: 10,
+            })
+        );
+    }
+
+    #[test]
+    fn swap_base_input_fees_exceeding_the_input() {
+        let rates = FeeRates { trade_fee_rate: 999_999, creator_fee_rate: 1, ..RATES };
+        assert_eq!(swap_base_input(1, 1_000_000, 2_000_000, &rates, true), None);
+    }
+
+    #[test]
+    fn swap_base_output_without_creator_fee() {
+        // 20_203 swapped, charged at 2_500 is 20_253.6
+        let rates = FeeRates { creator_fee_rate: 0, ..RATES };
+        assert_eq!(
+            swap_base_output(10_000, 2_000_000, 1_000_000, &rates, true),
+            Some(SwapResult {
+                input_amount: 20_254,
+                output_amount: 10_000,
+                trade_fee: 51,
+                protocol_fee: 6,
+                fund_fee: 2,
+                creator_fee: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn swap_base_output_with_creator_fee_on_input() {
+        // 20_203 swapped, charged at 3_000 is 20_263.8, the creator part of 61 is 10.17
+        assert_eq!(
+            swap_base_output(10_000, 2_000_000, 1_000_000, &RATES, true),
+            Some(SwapResult {
+                input_amount: 20_264,
+                output_amount: 10_000,
+                trade_fee: 51,
+                protocol_fee: 6,
+                fund_fee: 2,
+                creator_fee: 10,
+            })
+        );
+    }
+
+    #[test]
+    fn swap_base_output_with_creator_fee_on_output() {
+        // 10_006 leave the vault, 20_215 swapped, charged at 2_500 is 20_265.7
+        assert_eq!(
+            swap_base_output(10_000, 2_000_000, 1_000_000, &RATES, false),
+            Some(SwapResult {
+                input_amount: 20_266,
+                output_amount: 10_000,
+                trade_fee: 51,
+                protocol_fee: 6,
+                fund_fee: 2,
+                creator_fee: 6,
+            })
+        );
+    }
+
+    #[test]
+    fn swap_base_output_draining_the_vault() {
+        assert_eq!(swap_base_output(1_000_000, 2_000_000, 1_000_000, &RATES, true), None);
+        // the creator fee on output pushes the amount leaving the vault over its balance
+        assert_eq!(swap_base_output(999_999, 2_000_000, 1_000_000, &RATES, false), None);
+    }
+}
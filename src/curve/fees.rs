@@ -0,0 +1,154 @@
+//! Fee calculations, mirroring the program's `Fees`
+
+/// Denominator of every fee rate, rates are expressed in hundredths of a bip (10^-6)
+pub const FEE_RATE_DENOMINATOR_VALUE: u64 = 1_000_000;
+
+/// The fee rates applied to a swap
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FeeRates {
+    /// The trade fee rate, see `AmmConfig::trade_fee_rate`
+    pub trade_fee_rate: u64,
+    /// The creator fee rate, `0` unless the pool has creator fees enabled
+    pub creator_fee_rate: u64,
+    /// The protocol fee rate, a share of the trade fee
+    pub protocol_fee_rate: u64,
+    /// The fund fee rate, a share of the trade fee
+    pub fund_fee_rate: u64,
+}
+
+#[inline]
+fn ceil_div(token_amount: u128, fee_numerator: u128, fee_denominator: u128) -> Option<u128> {
+    token_amount
+        .checked_mul(fee_numerator)?
+        .checked_add(fee_denominator)?
+        .checked_sub(1)?
+        .checked_div(fee_denominator)
+}
+
+#[inline]
+fn floor_div(token_amount: u128, fee_numerator: u128, fee_denominator: u128) -> Option<u128> {
+    token_amount
+        .checked_mul(fee_numerator)?
+        .checked_div(fee_denominator)
+}
+
+/// Calculate the trading fee in trading tokens, rounded up
+#[inline]
+pub fn trading_fee(amount: u128, trade_fee_rate: u64) -> Option<u128> {
+    ceil_div(
+        amount,
+        u128::from(trade_fee_rate),
+        u128::from(FEE_RATE_DENOMINATOR_VALUE),
+    )
+}
+
+/// Calculate the protocol share of a trading fee, rounded down
+#[inline]
+pub fn protocol_fee(amount: u128, protocol_fee_rate: u64) -> Option<u128> {
+    floor_div(
+        amount,
+        u128::from(protocol_fee_rate),
+        u128::from(FEE_RATE_DENOMINATOR_VALUE),
+    )
+}
+
+/// Calculate the fund share of a trading fee, rounded down
+#[inline]
+pub fn fund_fee(amount: u128, fund_fee_rate: u64) -> Option<u128> {
+    floor_div(
+        amount,
+        u128::from(fund_fee_rate),
+        u128::from(FEE_RATE_DENOMINATOR_VALUE),
+    )
+}
+
+/// Calculate the creator fee in trading tokens, rounded up
+#[inline]
+pub fn creator_fee(amount: u128, creator_fee_rate: u64) -> Option<u128> {
+    ceil_div(
+        amount,
+        u128::from(creator_fee_rate),
+        u128::from(FEE_RATE_DENOMINATOR_VALUE),
+    )
+}
+
+/// Split the creator part out of a fee charged at `trade_fee_rate + creator_fee_rate`, rounded down
+#[inline]
+pub fn split_creator_fee(total_fee: u128, trade_fee_rate: u64, creator_fee_rate: u64) -> Option<u128> {
+    floor_div(
+        total_fee,
+        u128::from(creator_fee_rate),
+        u128::from(trade_fee_rate.checked_add(creator_fee_rate)?),
+    )
+}
+
+/// Calculate the amount before a fee of `fee_rate` was taken, rounded up
+#[inline]
+pub fn calculate_pre_fee_amount(post_fee_amount: u128, fee_rate: u64) -> Option<u128> {
+    if fee_rate == 0 {
+        Some(post_fee_amount)
+    } else {
+        let numerator = post_fee_amount.checked_mul(u128::from(FEE_RATE_DENOMINATOR_VALUE))?;
+        let denominator =
+            u128::from(FEE_RATE_DENOMINATOR_VALUE).checked_sub(u128::from(fee_rate))?;
+        numerator
+            .checked_add(denominator)?
+            .checked_sub(1)?
+            .checked_div(denominator)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trading_and_creator_fees_round_up() {
+        assert_eq!(trading_fee(10_000, 2_500), Some(25));
+        // 25.0025
+        assert_eq!(trading_fee(10_001, 2_500), Some(26));
+        assert_eq!(trading_fee(1, 2_500), Some(1));
+        assert_eq!(trading_fee(0, 2_500), Some(0));
+        assert_eq!(trading_fee(10_000, 0), Some(0));
+        // 9.876
+        assert_eq!(creator_fee(19_752, 500), Some(10));
+        assert_eq!(creator_fee(0, 500), Some(0));
+    }
+
+    #[test]
+    fn protocol_and_fund_fees_round_down() {
+        // 3.12 and 1.04
+        assert_eq!(protocol_fee(26, 120_000), Some(3));
+        assert_eq!(fund_fee(26, 40_000), Some(1));
+        // 0.96
+        assert_eq!(protocol_fee(8, 120_000), Some(0));
+        assert_eq!(fund_fee(0, 40_000), Some(0));
+    }
+
+    #[test]
+    fn splits_the_creator_fee_rounded_down() {
+        // 51 * 500 / 3_000 = 8.5
+        assert_eq!(split_creator_fee(51, 2_500, 500), Some(8));
+        assert_eq!(split_creator_fee(51, 2_500, 0), Some(0));
+        assert_eq!(split_creator_fee(51, u64::MAX, 1), None);
+    }
+
+    #[test]
+    fn pre_fee_amount_rounds_up() {
+        // 20_203 * 10^6 / 997_500 = 20_253.6
+        assert_eq!(calculate_pre_fee_amount(20_203, 2_500), Some(20_254));
+        assert_eq!(calculate_pre_fee_amount(997_500, 2_500), Some(1_000_000));
+        assert_eq!(calculate_pre_fee_amount(20_203, 0), Some(20_203));
+        assert_eq!(calculate_pre_fee_amount(0, 2_500), Some(0));
+        // a 100% fee leaves nothing to divide by
+        assert_eq!(calculate_pre_fee_amount(1, FEE_RATE_DENOMINATOR_VALUE), None);
+    }
+
+    #[test]
+    fn overflows_are_none() {
+        assert_eq!(trading_fee(u128::MAX, 1), None);
+        assert_eq!(creator_fee(u128::MAX, 1), None);
+        assert_eq!(protocol_fee(u128::MAX, 2), None);
+        assert_eq!(calculate_pre_fee_amount(u128::MAX, 1), None);
+    }
+}
@@ -0,0 +1,255 @@
+//! Conversions between lp tokens and trading tokens, mirroring the program's `CurveCalculator`
+
+use crate::states::PoolState;
+
+/// The direction to round, used when converting lp tokens to trading tokens
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RoundDirection {
+    /// Round down, used by `Withdraw` for the amounts paid out
+    Floor,
+    /// Round up, used by `Deposit` for the amounts charged
+    Ceiling,
+}
+
+/// Encodes the amounts of trading tokens backing an amount of lp tokens
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TradingTokenResult {
+    /// Amount of token 0
+    pub token_0_amount: u128,
+    /// Amount of token 1
+    pub token_1_amount: u128,
+}
+
+/// Get the amounts of trading tokens backing `lp_token_amount` lp tokens.
+///
+/// With `RoundDirection::Ceiling`, an amount is only rounded up when it is non zero,
+/// as the program does.
+pub fn lp_tokens_to_trading_tokens(
+    lp_token_amount: u128,
+    lp_token_supply: u128,
+    swap_token_0_amount: u128,
+    swap_token_1_amount: u128,
+    round_direction: RoundDirection,
+) -> Option<TradingTokenResult> {
+    let token_0_numerator = lp_token_amount.checked_mul(swap_token_0_amount)?;
+    let token_1_numerator = lp_token_amount.checked_mul(swap_token_1_amount)?;
+    let mut token_0_amount = token_0_numerator.checked_div(lp_token_supply)?;
+    let mut token_1_amount = token_1_numerator.checked_div(lp_token_supply)?;
+
+    if round_direction == RoundDirection::Ceiling {
+        if token_0_numerator.checked_rem(lp_token_supply)? > 0 && token_0_amount > 0 {
+            token_0_amount = token_0_amount.checked_add(1)?;
+        }
+        if token_1_numerator.checked_rem(lp_token_supply)? > 0 && token_1_amount > 0 {
+            token_1_amount = token_1_amount.checked_add(1)?;
+        }
+    }
+
+    Some(TradingTokenResult {
+        token_0_amount,
+        token_1_amount,
+    })
+}
+
+/// Get the amounts of token 0 and token 1 backing `lp_token_amount` lp tokens of the pool.
+///
+/// `vault_0_amount` and `vault_1_amount` are the raw balances of the pool vaults, the fees
+/// owed by the pool are subtracted here. Use `RoundDirection::Ceiling` to get the amounts a
+/// `Deposit` transfers into the vaults, and `RoundDirection::Floor` for the amounts a
+/// `Withdraw` transfers out of them. Both are before any Token-2022 transfer fee.
+///
+/// Returns `None` on overflow, if the pool has no lp supply or if the vaults hold less than
+/// the fees they owe.
+pub fn lp_to_token_amounts(
+    pool_state: &PoolState,
+    vault_0_amount: u64,
+    vault_1_amount: u64,
+    lp_token_amount: u64,
+    round_direction: RoundDirection,
+) -> Option<(u64, u64)> {
+    let (vault_0, vault_1) = pool_state.vault_amount_without_fee(vault_0_amount, vault_1_amount)?;
+    let result = lp_tokens_to_trading_tokens(
+        u128::from(lp_token_amount),
+        u128::from(pool_state.lp_supply),
+        u128::from(vault_0),
+        u128::from(vault_1),
+        round_direction,
+    )?;
+    Some((
+        u64::try_from(result.token_0_amount).ok()?,
+        u64::try_from(result.token_1_amount).ok()?,
+    ))
+}
+
+/// Get the maximum amount of lp tokens a `Deposit` can mint without charging more than
+/// `maximum_token_0_amount` of token 0 and `maximum_token_1_amount` of token 1.
+///
+/// This is the inverse of `lp_to_token_amounts` with `RoundDirection::Ceiling`, the budgets
+/// are the amounts received by the vaults, i.e. net of any Token-2022 transfer fee.
+///
+/// Returns `None` on overflow, if the pool has no lp supply or an empty vault, if the
+/// vaults hold less than the fees they owe, or if the budgets cannot buy an amount of lp
+/// tokens costing a non zero amount of both tokens, which the program rejects with
+/// `ZeroTradingTokens`.
+pub fn max_lp_for_token_amounts(
+    pool_state: &PoolState,
+    vault_0_amount: u64,
+    vault_1_amount: u64,
+    maximum_token_0_amount: u64,
+    maximum_token_1_amount: u64,
+) -> Option<u64> {
+    let (vault_0, vault_1) = pool_state.vault_amount_without_fee(vault_0_amount, vault_1_amount)?;
+    let lp_supply = u128::from(pool_state.lp_supply);
+    if lp_supply == 0 {
+        return None;
+    }
+    let lp_from_token_0 = u128::from(maximum_token_0_amount)
+        .checked_mul(lp_supply)?
+        .checked_div(u128::from(vault_0))?;
+    let lp_from_token_1 = u128::from(maximum_token_1_amount)
+        .checked_mul(lp_supply)?
+        .checked_div(u128::from(vault_1))?;
+    let lp_token_amount = lp_from_token_0.min(lp_from_token_1);
+
+    // The cost only grows with the amount of lp tokens, when it rounds to zero on one side
+    // so does the cost of any smaller amount
+    let cost = lp_tokens_to_trading_tokens(
+        lp_token_amount,
+        lp_supply,
+        u128::from(vault_0),
+        u128::from(vault_1),
+        RoundDirection::Ceiling,
+    )?;
+    if cost.token_0_amount == 0 || cost.token_1_amount == 0 {
+        return None;
+    }
+    u64::try_from(lp_token_amount).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::PoolStateBuilder;
+
+    /// A pool of 1_000 lp tokens, owing 45 of token 0 and 3 of token 1 in fees on 12_390 and
+    /// 70 vault balances
+    fn pool_state() -> PoolState {
+        PoolStateBuilder::new().lp_supply(1_000).protocol_fees(45, 3).build()
+    }
+
+    const VAULT_0: u64 = 12_390;
+    const VAULT_1: u64 = 70;
+
+    #[test]
+    fn rounds_trading_tokens_up_or_down() {
+        // 123.45 and 0.67
+        let floor = lp_tokens_to_trading_tokens(10, 1_000, 12_345, 67, RoundDirection::Floor);
+        assert_eq!(floor, Some(TradingTokenResult { token_0_amount: 123, token_1_amount: 0 }));
+        // zero amounts are not rounded up
+        let ceiling = lp_tokens_to_trading_tokens(10, 1_000, 12_345, 67, RoundDirection::Ceiling);
+        assert_eq!(ceiling, Some(TradingTokenResult { token_0_amount: 124, token_1_amount: 0 }));
+    }
+
+    #[test]
+    fn exact_amounts_are_not_rounded() {
+        for round_direction in [RoundDirection::Floor, RoundDirection::Ceiling] {
+            assert_eq!(
+                lp_tokens_to_trading_tokens(100, 1_000, 5_000, 2_000, round_direction),
+                Some(TradingTokenResult { token_0_amount: 500, token_1_amount: 200 })
+            );
+        }
+    }
+
+    #[test]
+    fn zero_lp_supply_or_overflow() {
+        assert_eq!(lp_tokens_to_trading_tokens(10, 0, 12_345, 67, RoundDirection::Floor), None);
+        assert_eq!(lp_tokens_to_trading_tokens(10, 0, 0, 0, RoundDirection::Ceiling), None);
+        assert_eq!(lp_tokens_to_trading_tokens(u128::MAX, 1, 2, 1, RoundDirection::Floor), None);
+    }
+
+    #[test]
+    fn lp_to_token_amounts_of_the_pool() {
+        let pool_state = pool_state();
+        let amounts = |lp_token_amount, round_direction| {
+            lp_to_token_amounts(&pool_state, VAULT_0, VAULT_1, lp_token_amount, round_direction)
+        };
+
+        assert_eq!(amounts(10, RoundDirection::Ceiling), Some((124, 0)));
+        assert_eq!(amounts(10, RoundDirection::Floor), Some((123, 0)));
+        assert_eq!(amounts(0, RoundDirection::Ceiling), Some((0, 0)));
+    }
+
+    #[test]
+    fn lp_to_token_amounts_without_supply_or_fees() {
+        let empty = PoolStateBuilder::new().build();
+        assert_eq!(lp_to_token_amounts(&empty, VAULT_0, VAULT_1, 10, RoundDirection::Floor), None);
+
+        // vaults holding less than the fees they owe
+        let pool_state = pool_state();
+        assert_eq!(lp_to_token_amounts(&pool_state, 44, VAULT_1, 10, RoundDirection::Floor), None);
+        assert_eq!(lp_to_token_amounts(&pool_state, VAULT_0, 2, 10, RoundDirection::Floor), None);
+    }
+
+    #[test]
+    fn lp_to_token_amounts_overflowing_u64() {
+        let pool_state = PoolStateBuilder::new().lp_supply(1).build();
+        let amounts = lp_to_token_amounts(&pool_state, u64::MAX, 0, 2, RoundDirection::Floor);
+        assert_eq!(amounts, None);
+    }
+
+    #[test]
+    fn max_lp_for_token_amounts_of_the_pool() {
+        let pool_state = pool_state();
+        let max_lp = |maximum_token_0_amount, maximum_token_1_amount| {
+            max_lp_for_token_amounts(
+                &pool_state,
+                VAULT_0,
+                VAULT_1,
+                maximum_token_0_amount,
+                maximum_token_1_amount,
+            )
+        };
+
+        // 149.86 from token 0 and 59.70 from token 1, 59 lp tokens cost 729 and 4
+        assert_eq!(max_lp(1_850, 4), Some(59));
+        assert_eq!(
+            lp_to_token_amounts(&pool_state, VAULT_0, VAULT_1, 59, RoundDirection::Ceiling),
+            Some((729, 4))
+        );
+        // 101.26 from token 0 and 29.85 from token 1, 29 lp tokens cost 359 and 2
+        assert_eq!(max_lp(1_250, 2), Some(29));
+        assert_eq!(
+            lp_to_token_amounts(&pool_state, VAULT_0, VAULT_1, 29, RoundDirection::Ceiling),
+            Some((359, 2))
+        );
+    }
+
+    #[test]
+    fn max_lp_for_token_amounts_costing_zero_of_a_token() {
+        let pool_state = pool_state();
+        // 10.04 from token 0, 10 lp tokens would cost 124 of token 0 and none of token 1
+        assert_eq!(max_lp_for_token_amounts(&pool_state, VAULT_0, VAULT_1, 124, 1), None);
+        assert_eq!(
+            lp_to_token_amounts(&pool_state, VAULT_0, VAULT_1, 10, RoundDirection::Ceiling),
+            Some((124, 0))
+        );
+        // 14.93 from token 1, 14 lp tokens would cost 173 of token 0 and none of token 1
+        assert_eq!(max_lp_for_token_amounts(&pool_state, VAULT_0, VAULT_1, 200, 1), None);
+        assert_eq!(
+            lp_to_token_amounts(&pool_state, VAULT_0, VAULT_1, 14, RoundDirection::Ceiling),
+            Some((173, 0))
+        );
+        assert_eq!(max_lp_for_token_amounts(&pool_state, VAULT_0, VAULT_1, 0, 1), None);
+    }
+
+    #[test]
+    fn max_lp_for_token_amounts_without_supply_or_liquidity() {
+        let empty = PoolStateBuilder::new().build();
+        assert_eq!(max_lp_for_token_amounts(&empty, VAULT_0, VAULT_1, 124, 1), None);
+
+        let pool_state = pool_state();
+        // a vault holding only the fees it owes
+        assert_eq!(max_lp_for_token_amounts(&pool_state, VAULT_0, 3, 124, 1), None);
+        assert_eq!(max_lp_for_token_amounts(&pool_state, 44, VAULT_1, 124, 1), None);
+    }
+}
@@ -0,0 +1,14 @@
+//! Off-chain and on-chain replica of the CPMM constant product curve.
+//!
+//! Every function rounds exactly like the program does, so quotes can be used
+//! to build the slippage parameters of swap, deposit and withdraw CPIs.
+
+pub mod calculator;
+pub mod fees;
+pub mod liquidity;
+pub mod quote;
+
+pub use calculator::*;
+pub use fees::*;
+pub use liquidity::*;
+pub use quote::*;
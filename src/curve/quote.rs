@@ -0,0 +1,307 @@
+//! Swap quotes computed from the on-chain pool state
+
+use crate::states::{AmmConfig, PoolState};
+
+use super::{
+    calculator::{self, SwapResult, TradeDirection},
+    fees::FeeRates,
+};
+
+/// The amounts and fee split of a swap, as computed by the program
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SwapQuote {
+    /// Amount of input token paid by the user, fees included
+    pub amount_in: u64,
+    /// Amount of output token received by the user
+    pub amount_out: u64,
+    /// Trade fee, in the input token
+    pub trade_fee: u64,
+    /// Part of the trade fee going to the protocol
+    pub protocol_fee: u64,
+    /// Part of the trade fee going to the fund
+    pub fund_fee: u64,
+    /// Creator fee, in the input token when `creator_fee_on_input`, in the output token otherwise
+    pub creator_fee: u64,
+    /// Whether the creator fee is charged on the input token
+    pub creator_fee_on_input: bool,
+}
+
+impl SwapQuote {
+    #[inline]
+    fn from_result(result: SwapResult, creator_fee_on_input: bool) -> Option<Self> {
+        Some(SwapQuote {
+            amount_in: u64::try_from(result.input_amount).ok()?,
+            amount_out: u64::try_from(result.output_amount).ok()?,
+            trade_fee: u64::try_from(result.trade_fee).ok()?,
+            protocol_fee: u64::try_from(result.protocol_fee).ok()?,
+            fund_fee: u64::try_from(result.fund_fee).ok()?,
+            creator_fee: u64::try_from(result.creator_fee).ok()?,
+            creator_fee_on_input,
+        })
+    }
+}
+
+/// Resolve the fee rates, the fee side and the curve vault amounts ordered as (input, output)
+#[inline]
+fn prepare(
+    pool_state: &PoolState,
+    amm_config: &AmmConfig,
+    direction: TradeDirection,
+    vault_0_amount: u64,
+    vault_1_amount: u64,
+) -> Option<(FeeRates, bool, u128, u128)> {
+    let (vault_0, vault_1) = pool_state.vault_amount_without_fee(vault_0_amount, vault_1_amount)?;
+    let (input_vault, output_vault) = match direction {
+        TradeDirection::ZeroForOne => (vault_0, vault_1),
+        TradeDirection::OneForZero => (vault_1, vault_0),
+    };
+    let fee_rates = FeeRates {
+        trade_fee_rate: amm_config.trade_fee_rate,
+        creator_fee_rate: pool_state.adjust_creator_fee_rate(amm_config.creator_fee_rate),
+        protocol_fee_rate: amm_config.protocol_fee_rate,
+        fund_fee_rate: amm_config.fund_fee_rate,
+    };
+    let creator_fee_on_input = pool_state.is_creator_fee_on_input(direction).ok()?;
+    Some((
+        fee_rates,
+        creator_fee_on_input,
+        u128::from(input_vault),
+        u128::from(output_vault),
+    ))
+}
+
+/// Quote a `SwapBaseInput` of `amount_in` in `direction`.
+///
+/// `vault_0_amount` and `vault_1_amount` are the raw balances of the pool vaults, the fees
+/// owed by the pool are subtracted here. `amount_in` is the amount received by the input vault,
+/// i.e. net of any Token-2022 transfer fee, and `amount_out` is the amount sent by the output
+/// vault, before any Token-2022 transfer fee.
+///
+/// Returns `None` on overflow or if the vaults hold less than the fees they owe.
+pub fn swap_base_input_quote(
+    pool_state: &PoolState,
+    amm_config: &AmmConfig,
+    direction: TradeDirection,
+    vault_0_amount: u64,
+    vault_1_amount: u64,
+    amount_in: u64,
+) -> Option<SwapQuote> {
+    let (fee_rates, creator_fee_on_input, input_vault, output_vault) =
+        prepare(pool_state, amm_config, direction, vault_0_amount, vault_1_amount)?;
+    let result = calculator::swap_base_input(
+        u128::from(amount_in),
+        input_vault,
+        output_vault,
+        &fee_rates,
+        creator_fee_on_input,
+    )?;
+    SwapQuote::from_result(result, creator_fee_on_input)
+}
+
+/// Quote a `SwapBaseOutput` of `amount_out` in `direction`.
+///
+/// `vault_0_amount` and `vault_1_amount` are the raw balances of the pool vaults, the fees
+/// owed by the pool are subtracted here. `amount_out` is the amount sent by the output vault,
+/// before any Token-2022 transfer fee, and `amount_in` is the amount the input vault must
+/// receive, i.e. net of any Token-2022 transfer fee.
+///
+/// Returns `None` on overflow, if the vaults hold less than the fees they owe or if
+/// `amount_out` exceeds the output vault.
+pub fn swap_base_output_quote(
+    pool_state: &PoolState,
+    amm_config: &AmmConfig,
+    direction: TradeDirection,
+    vault_0_amount: u64,
+    vault_1_amount: u64,
+    amount_out: u64,
+) -> Option<SwapQuote> {
+    let (fee_rates, creator_fee_on_input, input_vault, output_vault) =
+        prepare(pool_state, amm_config, direction, vault_0_amount, vault_1_amount)?;
+    let result = calculator::swap_base_output(
+        u128::from(amount_out),
+        input_vault,
+        output_vault,
+        &fee_rates,
+        creator_fee_on_input,
+    )?;
+    SwapQuote::from_result(result, creator_fee_on_input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        states::CreatorFeeOn,
+        test_utils::{AmmConfigBuilder, PoolStateBuilder},
+    };
+
+    fn amm_config() -> AmmConfig {
+        AmmConfigBuilder::new()
+            .trade_fee_rate(2_500)
+            .protocol_fee_rate(120_000)
+            .fund_fee_rate(40_000)
+            .creator_fee_rate(500)
+            .build()
+    }
+
+    /// A pool owing 300 of token 0 and 700 of token 1 in fees, on 1_000_300 and 2_000_700
+    /// vault balances
+    fn pool_state(creator_fee_on: CreatorFeeOn) -> PoolState {
+        PoolStateBuilder::new()
+            .protocol_fees(200, 500)
+            .fund_fees(100, 100)
+            .creator_fees(0, 100)
+            .creator_fee_on(creator_fee_on)
+            .build()
+    }
+
+    const VAULT_0: u64 = 1_000_300;
+    const VAULT_1: u64 = 2_000_700;
+
+    #[test]
+    fn swap_base_input_with_creator_fee_on_output() {
+        // 9_975 of token 1 into 2_000_000 for 4_962.5 of the 1_000_000 token 0, ceil(2.48) of
+        // which goes to the creator
+        let pool_state = pool_state(CreatorFeeOn::OnlyToken0);
+        let quote = swap_base_input_quote(
+            &pool_state,
+            &amm_config(),
+            TradeDirection::OneForZero,
+            VAULT_0,
+            VAULT_1,
+            10_000,
+        );
+
+        assert_eq!(
+            quote,
+            Some(SwapQuote {
+                amount_in: 10_000,
+                amount_out: 4_959,
+                trade_fee: 25,
+                protocol_fee: 3,
+                fund_fee: 1,
+                creator_fee: 3,
+                creator_fee_on_input: false,
+            })
+        );
+    }
+
+    #[test]
+    fn swap_base_input_with_creator_fee_on_input() {
+        let pool_state = pool_state(CreatorFeeOn::OnlyToken1);
+        let quote = swap_base_input_quote(
+            &pool_state,
+            &amm_config(),
+            TradeDirection::OneForZero,
+            VAULT_0,
+            VAULT_1,
+            10_000,
+        );
+
+        assert_eq!(
+            quote,
+            Some(SwapQuote {
+                amount_in: 10_000,
+                amount_out: 4_960,
+                trade_fee: 25,
+                protocol_fee: 3,
+                fund_fee: 1,
+                creator_fee: 5,
+                creator_fee_on_input: true,
+            })
+        );
+    }
+
+    #[test]
+    fn swap_base_output_with_creator_fee_on_output() {
+        let pool_state = pool_state(CreatorFeeOn::OnlyToken0);
+        let quote = swap_base_output_quote(
+            &pool_state,
+            &amm_config(),
+            TradeDirection::OneForZero,
+            VAULT_0,
+            VAULT_1,
+            5_000,
+        );
+
+        assert_eq!(
+            quote,
+            Some(SwapQuote {
+                amount_in: 10_083,
+                amount_out: 5_000,
+                trade_fee: 26,
+                protocol_fee: 3,
+                fund_fee: 1,
+                creator_fee: 3,
+                creator_fee_on_input: false,
+            })
+        );
+    }
+
+    #[test]
+    fn creator_fee_rate_is_ignored_unless_enabled() {
+        let pool_state = PoolStateBuilder::new().build();
+        let quote = swap_base_input_quote(
+            &pool_state,
+            &amm_config(),
+            TradeDirection::ZeroForOne,
+            1_000_000,
+            2_000_000,
+            10_000,
+        )
+        .unwrap();
+
+        assert_eq!((quote.amount_out, quote.creator_fee), (19_752, 0));
+    }
+
+    #[test]
+    fn zero_amounts() {
+        let pool_state = pool_state(CreatorFeeOn::BothToken);
+        let direction = TradeDirection::ZeroForOne;
+        let quote =
+            swap_base_input_quote(&pool_state, &amm_config(), direction, VAULT_0, VAULT_1, 0);
+        assert_eq!(
+            quote,
+            Some(SwapQuote { creator_fee_on_input: true, ..SwapQuote::default() })
+        );
+
+        let quote =
+            swap_base_output_quote(&pool_state, &amm_config(), direction, VAULT_0, VAULT_1, 0);
+        assert_eq!(
+            quote,
+            Some(SwapQuote { creator_fee_on_input: true, ..SwapQuote::default() })
+        );
+    }
+
+    #[test]
+    fn vaults_holding_only_the_fees_they_owe() {
+        // Nothing is left to trade against once the fees are subtracted
+        let pool_state = pool_state(CreatorFeeOn::BothToken);
+        let (direction, config) = (TradeDirection::ZeroForOne, amm_config());
+        let quote = swap_base_input_quote(&pool_state, &config, direction, 300, 700, 10_000);
+        assert_eq!(quote.map(|quote| quote.amount_out), Some(0));
+
+        let quote = swap_base_output_quote(&pool_state, &config, direction, 300, 700, 1);
+        assert_eq!(quote, None);
+    }
+
+    #[test]
+    fn vaults_holding_less_than_the_fees_they_owe() {
+        let pool_state = pool_state(CreatorFeeOn::BothToken);
+        let (direction, config) = (TradeDirection::ZeroForOne, amm_config());
+
+        assert_eq!(swap_base_input_quote(&pool_state, &config, direction, 299, VAULT_1, 1), None);
+        assert_eq!(swap_base_output_quote(&pool_state, &config, direction, VAULT_0, 699, 1), None);
+    }
+
+    #[test]
+    fn empty_vaults() {
+        let pool_state = PoolStateBuilder::new().build();
+        let (direction, config) = (TradeDirection::ZeroForOne, amm_config());
+
+        let quote = swap_base_input_quote(&pool_state, &config, direction, 0, 0, 10_000);
+        assert_eq!(quote.map(|quote| quote.amount_out), Some(0));
+        assert_eq!(swap_base_input_quote(&pool_state, &config, direction, 0, 0, 0), None);
+        assert_eq!(swap_base_output_quote(&pool_state, &config, direction, 0, 0, 1), None);
+    }
+}
@@ -0,0 +1,333 @@
+use pinocchio::program_error::ProgramError;
+
+/// Errors returned by the `validate` methods of the instruction structs, when an account
+/// does not match what the `PoolState` or `AmmConfig` records.
+///
+/// Converted into `ProgramError::Custom`, with codes starting at 400. SPL Token and Token-2022
+/// use the codes below 100 for their own errors, and the CPMM program returns Anchor's codes
+/// from 100 to 103 and from 1000 up, so a failed validation cannot be mistaken for a failed
+/// CPI. The other errors of this crate follow in the 500, 600 and 700 ranges.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ValidationError {
+    /// The pool state is not a CPMM pool account
+    InvalidPoolState = 400,
+    /// The amm config is not a CPMM config account, or not the config of the pool
+    InvalidAmmConfig,
+    /// The authority is not the pool vault and lp mint authority
+    InvalidAuthority,
+    /// The signer is not allowed to perform the instruction
+    InvalidOwner,
+    /// The token_0 vault is not the vault recorded by the pool
+    InvalidToken0Vault,
+    /// The token_1 vault is not the vault recorded by the pool
+    InvalidToken1Vault,
+    /// The token_0 mint is not the mint recorded by the pool
+    InvalidToken0Mint,
+    /// The token_1 mint is not the mint recorded by the pool
+    InvalidToken1Mint,
+    /// The lp mint is not the mint recorded by the pool
+    InvalidLpMint,
+    /// The observation state is not the account recorded by the pool
+    InvalidObservationState,
+    /// The input and output vaults are not the two vaults of the pool
+    InvalidSwapVaults,
+    /// The input or output mint does not match its vault
+    InvalidSwapMint,
+    /// The input or output token program does not match its mint
+    InvalidSwapTokenProgram,
+    /// The token program of token_0 is not the program recorded by the pool
+    InvalidToken0Program,
+    /// The token program of token_1 is not the program recorded by the pool
+    InvalidToken1Program,
+    /// The token program is not the SPL token program
+    InvalidTokenProgram,
+    /// The token program 2022 is not the SPL token 2022 program
+    InvalidTokenProgram2022,
+    /// The memo program is not the SPL memo program
+    InvalidMemoProgram,
+    /// The associated token program is not the SPL associated token program
+    InvalidAssociatedTokenProgram,
+    /// The system program is not the system program
+    InvalidSystemProgram,
+    /// The rent account is not the rent sysvar
+    InvalidRentSysvar,
+    /// The token_0 mint is not smaller than the token_1 mint
+    InvalidMintOrder,
+    /// The amm config does not allow creating pools
+    PoolCreationDisabled,
+    /// The creator is not the creator of the pool
+    InvalidPoolCreator,
+    /// The permission is not a CPMM permission account, or not the permission of the payer
+    InvalidPermission,
+}
+
+impl ValidationError {
+    /// Return the name of the error
+    pub fn name(&self) -> &'static str {
+        match self {
+            ValidationError::InvalidPoolState => "InvalidPoolState",
+            ValidationError::InvalidAmmConfig => "InvalidAmmConfig",
+            ValidationError::InvalidAuthority => "InvalidAuthority",
+            ValidationError::InvalidOwner => "InvalidOwner",
+            ValidationError::InvalidToken0Vault => "InvalidToken0Vault",
+            ValidationError::InvalidToken1Vault => "InvalidToken1Vault",
+            ValidationError::InvalidToken0Mint => "InvalidToken0Mint",
+            ValidationError::InvalidToken1Mint => "InvalidToken1Mint",
+            ValidationError::InvalidLpMint => "InvalidLpMint",
+            ValidationError::InvalidObservationState => "InvalidObservationState",
+            ValidationError::InvalidSwapVaults => "InvalidSwapVaults",
+            ValidationError::InvalidSwapMint => "InvalidSwapMint",
+            ValidationError::InvalidSwapTokenProgram => "InvalidSwapTokenProgram",
+            ValidationError::InvalidToken0Program => "InvalidToken0Program",
+            ValidationError::InvalidToken1Program => "InvalidToken1Program",
+            ValidationError::InvalidTokenProgram => "InvalidTokenProgram",
+            ValidationError::InvalidTokenProgram2022 => "InvalidTokenProgram2022",
+            ValidationError::InvalidMemoProgram => "InvalidMemoProgram",
+            ValidationError::InvalidAssociatedTokenProgram => "InvalidAssociatedTokenProgram",
+            ValidationError::InvalidSystemProgram => "InvalidSystemProgram",
+            ValidationError::InvalidRentSysvar => "InvalidRentSysvar",
+            ValidationError::InvalidMintOrder => "InvalidMintOrder",
+            ValidationError::PoolCreationDisabled => "PoolCreationDisabled",
+            ValidationError::InvalidPoolCreator => "InvalidPoolCreator",
+            ValidationError::InvalidPermission => "InvalidPermission",
+        }
+    }
+}
+
+impl From<ValidationError> for ProgramError {
+    fn from(e: ValidationError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+/// Errors returned by the CPMM program, with their Anchor error codes.
+///
+/// A failed CPI surfaces them as `ProgramError::Custom(code)`, use `CpmmError::try_from`
+/// to recover the variant.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CpmmError {
+    /// Not approved
+    NotApproved = 6000,
+    /// Input account owner is not the program address
+    InvalidOwner = 6001,
+    /// Input token account empty
+    EmptySupply = 6002,
+    /// InvalidInput
+    InvalidInput = 6003,
+    /// Address of the provided lp token mint is incorrect
+    IncorrectLpMint = 6004,
+    /// Exceeds desired slippage limit
+    ExceededSlippage = 6005,
+    /// Given pool token amount results in zero trading tokens
+    ZeroTradingTokens = 6006,
+    /// Not support token_2022 mint extension
+    NotSupportMint = 6007,
+    /// invaild vault
+    InvalidVault = 6008,
+    /// Init lp amount is too less(Because 100 amount lp will be locked)
+    InitLpAmountTooLess = 6009,
+    /// TransferFee calculate not match
+    TransferFeeCalculateNotMatch = 6010,
+    /// Math overflow
+    MathOverflow = 6011,
+    /// Insufficient vault
+    InsufficientVault = 6012,
+    /// Invalid fee model
+    InvalidFeeModel = 6013,
+    /// Fee is zero
+    NoFeeCollect = 6014,
+}
+
+impl CpmmError {
+    /// Return the Anchor error code of the error
+    #[inline(always)]
+    pub fn code(&self) -> u32 {
+        *self as u32
+    }
+
+    /// Return the name of the error, as declared by the program
+    pub fn name(&self) -> &'static str {
+        match self {
+            CpmmError::NotApproved => "NotApproved",
+            CpmmError::InvalidOwner => "InvalidOwner",
+            CpmmError::EmptySupply => "EmptySupply",
+            CpmmError::InvalidInput => "InvalidInput",
+            CpmmError::IncorrectLpMint => "IncorrectLpMint",
+            CpmmError::ExceededSlippage => "ExceededSlippage",
+            CpmmError::ZeroTradingTokens => "ZeroTradingTokens",
+            CpmmError::NotSupportMint => "NotSupportMint",
+            CpmmError::InvalidVault => "InvalidVault",
+            CpmmError::InitLpAmountTooLess => "InitLpAmountTooLess",
+            CpmmError::TransferFeeCalculateNotMatch => "TransferFeeCalculateNotMatch",
+            CpmmError::MathOverflow => "MathOverflow",
+            CpmmError::InsufficientVault => "InsufficientVault",
+            CpmmError::InvalidFeeModel => "InvalidFeeModel",
+            CpmmError::NoFeeCollect => "NoFeeCollect",
+        }
+    }
+
+    /// Return the message of the error, as logged by the program
+    pub fn message(&self) -> &'static str {
+        match self {
+            CpmmError::NotApproved => "Not approved",
+            CpmmError::InvalidOwner => "Input account owner is not the program address",
+            CpmmError::EmptySupply => "Input token account empty",
+            CpmmError::InvalidInput => "InvalidInput",
+            CpmmError::IncorrectLpMint => "Address of the provided lp token mint is incorrect",
+            CpmmError::ExceededSlippage => "Exceeds desired slippage limit",
+            CpmmError::ZeroTradingTokens => "Given pool token amount results in zero trading tokens",
+            CpmmError::NotSupportMint => "Not support token_2022 mint extension",
+            CpmmError::InvalidVault => "invaild vault",
+            CpmmError::InitLpAmountTooLess => "Init lp amount is too less(Because 100 amount lp will be locked)",
+            CpmmError::TransferFeeCalculateNotMatch => "TransferFee calculate not match",
+            CpmmError::MathOverflow => "Math overflow",
+            CpmmError::InsufficientVault => "Insufficient vault",
+            CpmmError::InvalidFeeModel => "Invalid fee model",
+            CpmmError::NoFeeCollect => "Fee is zero",
+        }
+    }
+
+    /// Return the error of the given Anchor error code, if it is a CPMM error
+    pub fn from_code(code: u32) -> Option<Self> {
+        Some(match code {
+            6000 => CpmmError::NotApproved,
+            6001 => CpmmError::InvalidOwner,
+            6002 => CpmmError::EmptySupply,
+            6003 => CpmmError::InvalidInput,
+            6004 => CpmmError::IncorrectLpMint,
+            6005 => CpmmError::ExceededSlippage,
+            6006 => CpmmError::ZeroTradingTokens,
+            6007 => CpmmError::NotSupportMint,
+            6008 => CpmmError::InvalidVault,
+            6009 => CpmmError::InitLpAmountTooLess,
+            6010 => CpmmError::TransferFeeCalculateNotMatch,
+            6011 => CpmmError::MathOverflow,
+            6012 => CpmmError::InsufficientVault,
+            6013 => CpmmError::InvalidFeeModel,
+            6014 => CpmmError::NoFeeCollect,
+            _ => return None,
+        })
+    }
+}
+
+impl TryFrom<ProgramError> for CpmmError {
+    type Error = ProgramError;
+
+    /// Recover the CPMM error of a failed CPI, returning the original error otherwise
+    fn try_from(error: ProgramError) -> Result<Self, Self::Error> {
+        match error {
+            ProgramError::Custom(code) => CpmmError::from_code(code).ok_or(error),
+            _ => Err(error),
+        }
+    }
+}
+
+impl From<CpmmError> for ProgramError {
+    fn from(e: CpmmError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+/// Errors returned by the TWAP oracle reader
+///
+/// Converted into `ProgramError::Custom`, with codes starting at 500, see `ValidationError`.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OracleError {
+    /// The observation state has no observation yet
+    NotInitialized = 500,
+    /// The window is empty
+    InvalidWindow,
+    /// The observations do not go back far enough to cover the window
+    InsufficientHistory,
+    /// The latest observation is older than the accepted staleness
+    StaleObservation,
+    /// The average price overflows
+    MathOverflow,
+}
+
+impl OracleError {
+    /// Return the name of the error
+    pub fn name(&self) -> &'static str {
+        match self {
+            OracleError::NotInitialized => "NotInitialized",
+            OracleError::InvalidWindow => "InvalidWindow",
+            OracleError::InsufficientHistory => "InsufficientHistory",
+            OracleError::StaleObservation => "StaleObservation",
+            OracleError::MathOverflow => "MathOverflow",
+        }
+    }
+}
+
+impl From<OracleError> for ProgramError {
+    fn from(e: OracleError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+/// Errors returned by the event decoders
+///
+/// Converted into `ProgramError::Custom`, with codes starting at 600, see `ValidationError`.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EventError {
+    /// The `Program data:` payload is not valid base64
+    InvalidBase64 = 600,
+    /// The self-CPI payload does not start with the Anchor event tag
+    InvalidEventTag,
+    /// The discriminator is not one of a CPMM event
+    UnknownDiscriminator,
+    /// The event data does not match the layout of the event
+    InvalidEventData,
+}
+
+impl EventError {
+    /// Return the name of the error
+    pub fn name(&self) -> &'static str {
+        match self {
+            EventError::InvalidBase64 => "InvalidBase64",
+            EventError::InvalidEventTag => "InvalidEventTag",
+            EventError::UnknownDiscriminator => "UnknownDiscriminator",
+            EventError::InvalidEventData => "InvalidEventData",
+        }
+    }
+}
+
+impl From<EventError> for ProgramError {
+    fn from(e: EventError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+/// Errors returned by the instruction decoder
+///
+/// Converted into `ProgramError::Custom`, with codes starting at 700, see `ValidationError`.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The discriminator is not one of a CPMM instruction
+    UnknownDiscriminator = 700,
+    /// The instruction data is too short or holds an invalid value
+    InvalidInstructionData,
+    /// Fewer account keys than the instruction accounts
+    NotEnoughAccounts,
+}
+
+impl DecodeError {
+    /// Return the name of the error
+    pub fn name(&self) -> &'static str {
+        match self {
+            DecodeError::UnknownDiscriminator => "UnknownDiscriminator",
+            DecodeError::InvalidInstructionData => "InvalidInstructionData",
+            DecodeError::NotEnoughAccounts => "NotEnoughAccounts",
+        }
+    }
+}
+
+impl From<DecodeError> for ProgramError {
+    fn from(e: DecodeError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
@@ -0,0 +1,536 @@
+//! Decoders for the Anchor events emitted by the CPMM program.
+//!
+//! The program emits its events with `emit!`, as a base64 `Program data:` log line holding
+//! the event discriminator followed by the Borsh encoded event. Events emitted through a
+//! self-CPI carry the same bytes behind the Anchor event instruction tag, see `parse_cpi_event`.
+//!
+//! The decoders do not know which program wrote a log line, the caller must only pass the lines
+//! logged while the CPMM program was executing.
+
+use pinocchio::pubkey::Pubkey;
+
+use crate::error::EventError;
+
+/// Prefix of the log lines written by `emit!`
+pub const PROGRAM_DATA_PREFIX: &str = "Program data: ";
+
+/// Anchor tag of the self-CPI event instruction, `sha256("anchor:event")[..8]` as little-endian bytes
+pub const EVENT_IX_TAG_LE: [u8; 8] = [228, 69, 165, 46, 81, 203, 154, 29];
+
+/// Largest event payload decoded from a log line, longer payloads are not CPMM events
+const MAX_EVENT_LEN: usize = 256;
+
+/// Emitted by `swap_base_input` and `swap_base_output`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SwapEvent {
+    pub pool_id: Pubkey,
+    /// Input vault balance before the swap, without the fees
+    pub input_vault_before: u64,
+    /// Output vault balance before the swap, without the fees
+    pub output_vault_before: u64,
+    /// Amount of input token, excluding the transfer fee
+    pub input_amount: u64,
+    /// Amount of output token, excluding the transfer fee
+    pub output_amount: u64,
+    pub input_transfer_fee: u64,
+    pub output_transfer_fee: u64,
+    /// Whether the swap was `swap_base_input`
+    pub base_input: bool,
+    /// Mints and fees, only emitted by releases with creator fees
+    pub details: Option<SwapEventDetails>,
+}
+
+/// Trailing fields of `SwapEvent` added with creator fees
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SwapEventDetails {
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    pub trade_fee: u64,
+    pub creator_fee: u64,
+    pub creator_fee_on_input: bool,
+}
+
+impl SwapEvent {
+    pub const DISCRIMINATOR: [u8; 8] = [64, 198, 205, 232, 38, 8, 113, 226];
+
+    /// Length of the event data of releases without creator fees
+    pub const LEGACY_LEN: usize = 32 + 8 * 6 + 1;
+
+    /// Length of the event data of releases with creator fees
+    pub const LEN: usize = Self::LEGACY_LEN + 32 * 2 + 8 * 2 + 1;
+
+    /// Decode the Borsh encoded event data, without the discriminator
+    pub fn decode(data: &[u8]) -> Result<Self, EventError> {
+        let mut reader = Reader(data);
+        let event = SwapEvent {
+            pool_id: reader.pubkey()?,
+            input_vault_before: reader.u64()?,
+            output_vault_before: reader.u64()?,
+            input_amount: reader.u64()?,
+            output_amount: reader.u64()?,
+            input_transfer_fee: reader.u64()?,
+            output_transfer_fee: reader.u64()?,
+            base_input: reader.bool()?,
+            details: if reader.0.is_empty() {
+                None
+            } else {
+                Some(SwapEventDetails {
+                    input_mint: reader.pubkey()?,
+                    output_mint: reader.pubkey()?,
+                    trade_fee: reader.u64()?,
+                    creator_fee: reader.u64()?,
+                    creator_fee_on_input: reader.bool()?,
+                })
+            },
+        };
+        reader.finish(event)
+    }
+}
+
+/// Whether an `LpChangeEvent` was emitted by a deposit or a withdraw
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LpChangeType {
+    Deposit = 0,
+    Withdraw = 1,
+}
+
+/// Emitted by `deposit` and `withdraw`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LpChangeEvent {
+    pub pool_id: Pubkey,
+    pub lp_amount_before: u64,
+    /// Vault 0 balance before the change, without the fees
+    pub token_0_vault_before: u64,
+    /// Vault 1 balance before the change, without the fees
+    pub token_1_vault_before: u64,
+    /// Amount of token 0, excluding the transfer fee
+    pub token_0_amount: u64,
+    /// Amount of token 1, excluding the transfer fee
+    pub token_1_amount: u64,
+    pub token_0_transfer_fee: u64,
+    pub token_1_transfer_fee: u64,
+    pub change_type: LpChangeType,
+}
+
+impl LpChangeEvent {
+    pub const DISCRIMINATOR: [u8; 8] = [121, 163, 205, 201, 57, 218, 117, 60];
+
+    /// Length of the event data
+    pub const LEN: usize = 32 + 8 * 7 + 1;
+
+    /// Decode the Borsh encoded event data, without the discriminator
+    pub fn decode(data: &[u8]) -> Result<Self, EventError> {
+        let mut reader = Reader(data);
+        let event = LpChangeEvent {
+            pool_id: reader.pubkey()?,
+            lp_amount_before: reader.u64()?,
+            token_0_vault_before: reader.u64()?,
+            token_1_vault_before: reader.u64()?,
+            token_0_amount: reader.u64()?,
+            token_1_amount: reader.u64()?,
+            token_0_transfer_fee: reader.u64()?,
+            token_1_transfer_fee: reader.u64()?,
+            change_type: match reader.u8()? {
+                0 => LpChangeType::Deposit,
+                1 => LpChangeType::Withdraw,
+                _ => return Err(EventError::InvalidEventData),
+            },
+        };
+        reader.finish(event)
+    }
+}
+
+/// An event emitted by the CPMM program
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CpmmEvent {
+    Swap(SwapEvent),
+    LpChange(LpChangeEvent),
+}
+
+/// Decode an event from its discriminator followed by the Borsh encoded event data
+pub fn parse_event(data: &[u8]) -> Result<CpmmEvent, EventError> {
+    if data.len() < 8 {
+        return Err(EventError::UnknownDiscriminator);
+    }
+    let (discriminator, data) = data.split_at(8);
+    if discriminator == SwapEvent::DISCRIMINATOR {
+        SwapEvent::decode(data).map(CpmmEvent::Swap)
+    } else if discriminator == LpChangeEvent::DISCRIMINATOR {
+        LpChangeEvent::decode(data).map(CpmmEvent::LpChange)
+    } else {
+        Err(EventError::UnknownDiscriminator)
+    }
+}
+
+/// Decode an event from the instruction data of a self-CPI event instruction
+pub fn parse_cpi_event(instruction_data: &[u8]) -> Result<CpmmEvent, EventError> {
+    match instruction_data.strip_prefix(&EVENT_IX_TAG_LE) {
+        Some(data) => parse_event(data),
+        None => Err(EventError::InvalidEventTag),
+    }
+}
+
+/// Decode an event from a log line.
+///
+/// Returns `Ok(None)` if the line is not a `Program data:` line.
+pub fn parse_log(line: &str) -> Result<Option<CpmmEvent>, EventError> {
+    let Some(payload) = line.strip_prefix(PROGRAM_DATA_PREFIX) else {
+        return Ok(None);
+    };
+    let mut buffer = [0u8; MAX_EVENT_LEN];
+    let len = base64_decode(payload.trim_end().as_bytes(), &mut buffer)?;
+    if len > MAX_EVENT_LEN {
+        // Too long for a CPMM event, only malformed if the discriminator is one of ours
+        let discriminator = &buffer[..8];
+        if discriminator == SwapEvent::DISCRIMINATOR || discriminator == LpChangeEvent::DISCRIMINATOR {
+            return Err(EventError::InvalidEventData);
+        }
+        return Err(EventError::UnknownDiscriminator);
+    }
+    parse_event(&buffer[..len]).map(Some)
+}
+
+/// Decode standard padded base64 into `out`, returning the decoded length.
+///
+/// Bytes past the end of `out` are counted but dropped.
+fn base64_decode(input: &[u8], out: &mut [u8]) -> Result<usize, EventError> {
+    if !input.len().is_multiple_of(4) {
+        return Err(EventError::InvalidBase64);
+    }
+    let chunks = input.len() / 4;
+    let mut len = 0;
+    for (i, chunk) in input.chunks_exact(4).enumerate() {
+        let padding = if i + 1 == chunks {
+            chunk.iter().rev().take_while(|c| **c == b'=').count()
+        } else {
+            0
+        };
+        if padding > 2 {
+            return Err(EventError::InvalidBase64);
+        }
+        let mut bits: u32 = 0;
+        for c in &chunk[..4 - padding] {
+            bits = (bits << 6) | base64_value(*c)?;
+        }
+        bits <<= 6 * padding as u32;
+        let bytes = [(bits >> 16) as u8, (bits >> 8) as u8, bits as u8];
+        for byte in &bytes[..3 - padding] {
+            if let Some(slot) = out.get_mut(len) {
+                *slot = *byte;
+            }
+            len += 1;
+        }
+    }
+    Ok(len)
+}
+
+#[inline(always)]
+fn base64_value(c: u8) -> Result<u32, EventError> {
+    let value = match c {
+        b'A'..=b'Z' => c - b'A',
+        b'a'..=b'z' => c - b'a' + 26,
+        b'0'..=b'9' => c - b'0' + 52,
+        b'+' => 62,
+        b'/' => 63,
+        _ => return Err(EventError::InvalidBase64),
+    };
+    Ok(value as u32)
+}
+
+/// Borsh reader over the event data
+struct Reader<'a>(&'a [u8]);
+
+impl Reader<'_> {
+    #[inline(always)]
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], EventError> {
+        if self.0.len() < N {
+            return Err(EventError::InvalidEventData);
+        }
+        let (bytes, rest) = self.0.split_at(N);
+        self.0 = rest;
+        Ok(bytes.try_into().unwrap())
+    }
+
+    #[inline(always)]
+    fn pubkey(&mut self) -> Result<Pubkey, EventError> {
+        self.take::<32>()
+    }
+
+    #[inline(always)]
+    fn u64(&mut self) -> Result<u64, EventError> {
+        self.take::<8>().map(u64::from_le_bytes)
+    }
+
+    #[inline(always)]
+    fn u8(&mut self) -> Result<u8, EventError> {
+        self.take::<1>().map(|[byte]| byte)
+    }
+
+    #[inline(always)]
+    fn bool(&mut self) -> Result<bool, EventError> {
+        match self.u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(EventError::InvalidEventData),
+        }
+    }
+
+    /// Return `value` if all the data was read
+    #[inline(always)]
+    fn finish<T>(self, value: T) -> Result<T, EventError> {
+        if self.0.is_empty() {
+            Ok(value)
+        } else {
+            Err(EventError::InvalidEventData)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! No mainnet log line is available offline, the payloads are built from the event
+    //! layouts of the IDL and encoded with the test encoder below, itself checked against the
+    //! RFC 4648 vectors.
+
+    use std::{format, string::String, vec::Vec};
+
+    use super::*;
+
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    /// Encode `data` as standard padded base64
+    fn base64_encode(data: &[u8]) -> String {
+        let mut out = String::new();
+        for chunk in data.chunks(3) {
+            let bits = chunk
+                .iter()
+                .enumerate()
+                .fold(0u32, |bits, (i, byte)| bits | u32::from(*byte) << (16 - 8 * i));
+            for i in 0..4 {
+                if i <= chunk.len() {
+                    out.push(ALPHABET[(bits >> (18 - 6 * i) & 0x3f) as usize] as char);
+                } else {
+                    out.push('=');
+                }
+            }
+        }
+        out
+    }
+
+    fn log(payload: &[u8]) -> String {
+        format!("{PROGRAM_DATA_PREFIX}{}", base64_encode(payload))
+    }
+
+    fn decode(input: &str) -> Result<Vec<u8>, EventError> {
+        let mut out = [0u8; 16];
+        let len = base64_decode(input.as_bytes(), &mut out)?;
+        Ok(out[..len].to_vec())
+    }
+
+    /// The discriminator and fields of a `SwapEvent` without creator fees
+    fn legacy_swap_event() -> Vec<u8> {
+        let mut data = SwapEvent::DISCRIMINATOR.to_vec();
+        data.extend([1; 32]);
+        for value in [1_000_000u64, 2_000_000, 10_000, 19_752, 0, 7] {
+            data.extend(value.to_le_bytes());
+        }
+        data.push(1);
+        data
+    }
+
+    fn swap_event() -> Vec<u8> {
+        let mut data = legacy_swap_event();
+        data.extend([2; 32]);
+        data.extend([3; 32]);
+        data.extend(25u64.to_le_bytes());
+        data.extend(10u64.to_le_bytes());
+        data.push(0);
+        data
+    }
+
+    fn lp_change_event(change_type: u8) -> Vec<u8> {
+        let mut data = LpChangeEvent::DISCRIMINATOR.to_vec();
+        data.extend([4; 32]);
+        for value in [1_000_000u64, 1_000_000, 2_000_000, 100_000, 200_000, 3, 0] {
+            data.extend(value.to_le_bytes());
+        }
+        data.push(change_type);
+        data
+    }
+
+    const LEGACY: SwapEvent = SwapEvent {
+        pool_id: [1; 32],
+        input_vault_before: 1_000_000,
+        output_vault_before: 2_000_000,
+        input_amount: 10_000,
+        output_amount: 19_752,
+        input_transfer_fee: 0,
+        output_transfer_fee: 7,
+        base_input: true,
+        details: None,
+    };
+
+    #[test]
+    fn base64_vectors() {
+        for (decoded, encoded) in [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy"),
+        ] {
+            assert_eq!(base64_encode(decoded.as_bytes()), encoded);
+            assert_eq!(decode(encoded), Ok(decoded.as_bytes().to_vec()), "{encoded}");
+        }
+        assert_eq!(decode("+/+/"), Ok([0xfb, 0xff, 0xbf].to_vec()));
+    }
+
+    #[test]
+    fn base64_bad_padding_or_characters() {
+        for encoded in ["Zg=", "Zg", "Z===", "====", "Zg==Zm9v", "Zm=v", "Zm9*", "Zm9v\n"] {
+            assert_eq!(decode(encoded), Err(EventError::InvalidBase64), "{encoded:?}");
+        }
+    }
+
+    #[test]
+    fn base64_counts_bytes_past_the_buffer() {
+        let mut out = [0u8; 2];
+        assert_eq!(base64_decode(b"Zm9vYmFy", &mut out), Ok(6));
+        assert_eq!(&out, b"fo");
+    }
+
+    #[test]
+    fn legacy_swap_event_log() {
+        let data = legacy_swap_event();
+        assert_eq!(data.len(), 8 + SwapEvent::LEGACY_LEN);
+
+        assert_eq!(parse_log(&log(&data)), Ok(Some(CpmmEvent::Swap(LEGACY))));
+    }
+
+    #[test]
+    fn creator_fee_swap_event_log() {
+        let data = swap_event();
+        assert_eq!(data.len(), 8 + SwapEvent::LEN);
+
+        let expected = SwapEvent {
+            details: Some(SwapEventDetails {
+                input_mint: [2; 32],
+                output_mint: [3; 32],
+                trade_fee: 25,
+                creator_fee: 10,
+                creator_fee_on_input: false,
+            }),
+            ..LEGACY
+        };
+        assert_eq!(parse_log(&log(&data)), Ok(Some(CpmmEvent::Swap(expected))));
+        // as logged, with the trailing newline
+        let line = log(&data) + "\n";
+        assert_eq!(parse_log(&line), Ok(Some(CpmmEvent::Swap(expected))));
+    }
+
+    #[test]
+    fn malformed_swap_events() {
+        // truncated in the creator fee fields
+        let data = swap_event();
+        assert_eq!(parse_event(&data[..data.len() - 1]), Err(EventError::InvalidEventData));
+        let partial = &data[..8 + SwapEvent::LEGACY_LEN + 1];
+        assert_eq!(parse_event(partial), Err(EventError::InvalidEventData));
+        // trailing data
+        let mut data = swap_event();
+        data.push(0);
+        assert_eq!(parse_event(&data), Err(EventError::InvalidEventData));
+        // base_input is not a bool
+        let mut data = legacy_swap_event();
+        *data.last_mut().unwrap() = 2;
+        assert_eq!(parse_event(&data), Err(EventError::InvalidEventData));
+    }
+
+    #[test]
+    fn lp_change_event_log() {
+        let data = lp_change_event(1);
+        assert_eq!(data.len(), 8 + LpChangeEvent::LEN);
+
+        let expected = LpChangeEvent {
+            pool_id: [4; 32],
+            lp_amount_before: 1_000_000,
+            token_0_vault_before: 1_000_000,
+            token_1_vault_before: 2_000_000,
+            token_0_amount: 100_000,
+            token_1_amount: 200_000,
+            token_0_transfer_fee: 3,
+            token_1_transfer_fee: 0,
+            change_type: LpChangeType::Withdraw,
+        };
+        assert_eq!(parse_log(&log(&data)), Ok(Some(CpmmEvent::LpChange(expected))));
+        let deposit = LpChangeEvent { change_type: LpChangeType::Deposit, ..expected };
+        assert_eq!(parse_event(&lp_change_event(0)), Ok(CpmmEvent::LpChange(deposit)));
+        assert_eq!(parse_event(&lp_change_event(2)), Err(EventError::InvalidEventData));
+    }
+
+    #[test]
+    fn self_cpi_event() {
+        let mut instruction_data = EVENT_IX_TAG_LE.to_vec();
+        instruction_data.extend(legacy_swap_event());
+        assert_eq!(parse_cpi_event(&instruction_data), Ok(CpmmEvent::Swap(LEGACY)));
+
+        assert_eq!(parse_cpi_event(&legacy_swap_event()), Err(EventError::InvalidEventTag));
+        assert_eq!(parse_cpi_event(&EVENT_IX_TAG_LE[..7]), Err(EventError::InvalidEventTag));
+        assert_eq!(parse_cpi_event(&EVENT_IX_TAG_LE), Err(EventError::UnknownDiscriminator));
+    }
+
+    #[test]
+    fn event_ix_tag_is_the_anchor_tag() {
+        use sha2::{Digest, Sha256};
+
+        // Anchor reads the hash prefix as a big-endian u64 and writes it little-endian
+        let tag = u64::from_be_bytes(Sha256::digest(b"anchor:event")[..8].try_into().unwrap());
+        assert_eq!(tag, 0x1d9a_cb51_2ea5_45e4);
+        assert_eq!(tag.to_le_bytes(), EVENT_IX_TAG_LE);
+    }
+
+    #[test]
+    fn unknown_discriminators() {
+        let mut data = legacy_swap_event();
+        data[0] ^= 1;
+        assert_eq!(parse_log(&log(&data)), Err(EventError::UnknownDiscriminator));
+        assert_eq!(parse_log(&log(&data[..7])), Err(EventError::UnknownDiscriminator));
+        assert_eq!(parse_log(PROGRAM_DATA_PREFIX), Err(EventError::UnknownDiscriminator));
+    }
+
+    #[test]
+    fn other_log_lines() {
+        assert_eq!(parse_log("Program log: Instruction: SwapBaseInput"), Ok(None));
+        assert_eq!(parse_log(""), Ok(None));
+    }
+
+    #[test]
+    fn payloads_over_max_event_len() {
+        let mut data = legacy_swap_event();
+        data.resize(MAX_EVENT_LEN + 1, 0);
+        assert_eq!(parse_log(&log(&data)), Err(EventError::InvalidEventData));
+
+        let mut data = lp_change_event(0);
+        data.resize(MAX_EVENT_LEN + 1, 0);
+        assert_eq!(parse_log(&log(&data)), Err(EventError::InvalidEventData));
+
+        // the event of another program
+        let mut data = [9u8; MAX_EVENT_LEN + 1].to_vec();
+        assert_eq!(parse_log(&log(&data)), Err(EventError::UnknownDiscriminator));
+        data.truncate(MAX_EVENT_LEN);
+        assert_eq!(parse_log(&log(&data)), Err(EventError::UnknownDiscriminator));
+    }
+
+    #[test]
+    fn error_codes_start_at_600() {
+        use pinocchio::program_error::ProgramError;
+
+        assert_eq!(ProgramError::from(EventError::InvalidBase64), ProgramError::Custom(600));
+        assert_eq!(
+            ProgramError::from(EventError::InvalidEventData),
+            ProgramError::Custom(603)
+        );
+    }
+}
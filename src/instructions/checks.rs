@@ -0,0 +1,123 @@
+use pinocchio::{
+    account_info::{AccountInfo, Ref},
+    pubkey::Pubkey,
+};
+
+use crate::{
+    associated_token_program, error::ValidationError, memo_program, pda, rent_sysvar,
+    states::{AmmConfig, PoolState},
+    system_program, token_program, token_program_2022,
+};
+
+#[inline(always)]
+pub(crate) fn check_key(
+    account_info: &AccountInfo,
+    expected: &Pubkey,
+    error: ValidationError,
+) -> Result<(), ValidationError> {
+    if account_info.key() != expected {
+        return Err(error);
+    }
+    Ok(())
+}
+
+#[inline(always)]
+pub(crate) fn load_pool_state(account_info: &AccountInfo) -> Result<Ref<'_, PoolState>, ValidationError> {
+    PoolState::from_account_info(account_info).map_err(|_| ValidationError::InvalidPoolState)
+}
+
+#[inline(always)]
+pub(crate) fn load_amm_config(account_info: &AccountInfo) -> Result<Ref<'_, AmmConfig>, ValidationError> {
+    AmmConfig::from_account_info(account_info).map_err(|_| ValidationError::InvalidAmmConfig)
+}
+
+/// Check the authority against `PoolState::auth_bump`
+#[inline(always)]
+pub(crate) fn check_authority(
+    account_info: &AccountInfo,
+    pool_state: &PoolState,
+) -> Result<(), ValidationError> {
+    pda::verify_authority(account_info.key(), pool_state).map_err(|_| ValidationError::InvalidAuthority)
+}
+
+/// Check the vaults and their mints against the pool
+#[inline(always)]
+pub(crate) fn check_vaults(
+    pool_state: &PoolState,
+    token_0_vault: &AccountInfo,
+    token_1_vault: &AccountInfo,
+    vault_0_mint: &AccountInfo,
+    vault_1_mint: &AccountInfo,
+) -> Result<(), ValidationError> {
+    check_key(token_0_vault, &pool_state.token_0_vault, ValidationError::InvalidToken0Vault)?;
+    check_key(token_1_vault, &pool_state.token_1_vault, ValidationError::InvalidToken1Vault)?;
+    check_key(vault_0_mint, &pool_state.token_0_mint, ValidationError::InvalidToken0Mint)?;
+    check_key(vault_1_mint, &pool_state.token_1_mint, ValidationError::InvalidToken1Mint)
+}
+
+/// Check the token programs of the instructions taking both SPL token and token 2022
+#[inline(always)]
+pub(crate) fn check_token_programs(
+    token_program: &AccountInfo,
+    token_program_2022: &AccountInfo,
+) -> Result<(), ValidationError> {
+    check_key(token_program, &token_program::ID, ValidationError::InvalidTokenProgram)?;
+    check_key(token_program_2022, &token_program_2022::ID, ValidationError::InvalidTokenProgram2022)
+}
+
+/// Check the token programs of the instructions taking one program per token
+#[inline(always)]
+pub(crate) fn check_pool_token_programs(
+    pool_state: &PoolState,
+    token_0_program: &AccountInfo,
+    token_1_program: &AccountInfo,
+) -> Result<(), ValidationError> {
+    check_key(token_0_program, &pool_state.token_0_program, ValidationError::InvalidToken0Program)?;
+    check_key(token_1_program, &pool_state.token_1_program, ValidationError::InvalidToken1Program)
+}
+
+/// Check a token program is either SPL token or token 2022
+#[inline(always)]
+pub(crate) fn check_any_token_program(
+    account_info: &AccountInfo,
+    error: ValidationError,
+) -> Result<(), ValidationError> {
+    if account_info.key() != &token_program::ID && account_info.key() != &token_program_2022::ID {
+        return Err(error);
+    }
+    Ok(())
+}
+
+/// Check a token program is either SPL token or token 2022, and owns the mint
+#[inline(always)]
+pub(crate) fn check_mint_program(
+    mint: &AccountInfo,
+    token_program: &AccountInfo,
+    error: ValidationError,
+) -> Result<(), ValidationError> {
+    check_any_token_program(token_program, error)?;
+    if !mint.is_owned_by(token_program.key()) {
+        return Err(error);
+    }
+    Ok(())
+}
+
+#[inline(always)]
+pub(crate) fn check_memo_program(account_info: &AccountInfo) -> Result<(), ValidationError> {
+    check_key(account_info, &memo_program::ID, ValidationError::InvalidMemoProgram)
+}
+
+#[inline(always)]
+pub(crate) fn check_associated_token_program(account_info: &AccountInfo) -> Result<(), ValidationError> {
+    check_key(account_info, &associated_token_program::ID, ValidationError::InvalidAssociatedTokenProgram)
+}
+
+#[inline(always)]
+pub(crate) fn check_system_program(account_info: &AccountInfo) -> Result<(), ValidationError> {
+    check_key(account_info, &system_program::ID, ValidationError::InvalidSystemProgram)
+}
+
+#[inline(always)]
+pub(crate) fn check_rent_sysvar(account_info: &AccountInfo) -> Result<(), ValidationError> {
+    check_key(account_info, &rent_sysvar::ID, ValidationError::InvalidRentSysvar)
+}
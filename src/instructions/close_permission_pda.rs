@@ -0,0 +1,122 @@
+use core::{mem::MaybeUninit, slice::from_raw_parts};
+
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Instruction, Signer},
+    program::invoke_signed,
+    program_error::ProgramError,
+    ProgramResult,
+};
+
+use crate::{
+    admin, error::ValidationError, states::Permission, write_bytes, UNINIT_BYTE,
+};
+
+use super::checks::{check_key, check_system_program};
+use super::encoding::{account_metas, exact_accounts, AccountRole};
+
+/// Closes the permission account of an authority, refunding the rent to the owner
+///
+/// Only supported by CPMM releases with creator fees.
+///
+/// ### Accounts:
+///   0. `[WRITE, SIGNER]` owner - Admin authority, must be `crate::admin::ID`
+///   1. `[]` permission_authority - The authority the permission is granted to
+///   2. `[WRITE]` permission - Permission account to close (PDA)
+///   3. `[]` system_program - System program
+pub struct ClosePermissionPda<'a> {
+    /// Admin authority. Must be `crate::admin::ID`
+    pub owner: &'a AccountInfo,
+    /// CHECK: the authority the permission is granted to
+    pub permission_authority: &'a AccountInfo,
+    /// Permission account to close
+    pub permission: &'a AccountInfo,
+    /// System program
+    pub system_program: &'a AccountInfo,
+}
+
+impl<'a> ClosePermissionPda<'a> {
+    /// Bind `accounts` in the documented order.
+    ///
+    /// `accounts` must hold exactly the accounts of the instruction, fewer fail with
+    /// `NotEnoughAccountKeys` and more with `InvalidArgument`.
+    pub fn from_accounts(accounts: &'a [AccountInfo]) -> Result<Self, ProgramError> {
+        let [owner, permission_authority, permission, system_program] = exact_accounts(accounts)?;
+        Ok(Self { owner, permission_authority, permission, system_program })
+    }
+}
+
+impl ClosePermissionPda<'_> {
+    /// Anchor discriminator of the instruction
+    pub const DISCRIMINATOR: [u8; 8] = [156, 84, 32, 118, 69, 135, 70, 123];
+
+    /// Length of the instruction data
+    pub const DATA_LEN: usize = 8;
+
+    /// Roles of the accounts, in order
+    pub const ACCOUNTS: [AccountRole; 4] = [
+        AccountRole::WRITABLE_SIGNER,
+        AccountRole::READONLY,
+        AccountRole::WRITABLE,
+        AccountRole::READONLY,
+    ];
+
+    /// Names of the accounts, in order
+    pub const ACCOUNT_NAMES: [&'static str; 4] = [
+        "owner", "permission_authority", "permission", "system_program",
+    ];
+
+    /// Write the instruction data, initializing every byte of `data`
+    #[inline(always)]
+    pub fn write_data(data: &mut [MaybeUninit<u8>; 8]) {
+        // Instruction data layout:
+        // -  [0..8]: instruction discriminator (8 bytes)
+
+        // Set discriminator (8 bytes)
+        write_bytes(&mut data[0..8], &Self::DISCRIMINATOR);
+    }
+
+    /// Check the accounts against the admin and `Permission` before invoking.
+    ///
+    /// This is opt-in, the program performs the same checks but fails with less
+    /// descriptive errors after the CPI compute has been spent.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        check_key(self.owner, &admin::ID, ValidationError::InvalidOwner)?;
+        let permission = Permission::from_account_info(self.permission)
+            .map_err(|_| ValidationError::InvalidPermission)?;
+        if &permission.authority != self.permission_authority.key() {
+            return Err(ValidationError::InvalidPermission);
+        }
+        check_system_program(self.system_program)
+    }
+
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        self.with_instruction(|instruction, accounts| invoke_signed(instruction, accounts, signers))
+    }
+
+    /// Build the instruction and call `f` with it and the account infos, in instruction order.
+    ///
+    /// `invoke_signed` passes them to `pinocchio::program::invoke_signed`, other executors such
+    /// as `test_utils::svm::Svm` can run the same instruction.
+    #[inline(always)]
+    pub fn with_instruction<R>(&self, f: impl FnOnce(&Instruction, &[&AccountInfo; 4]) -> R) -> R {
+        let accounts = [self.owner, self.permission_authority, self.permission, self.system_program];
+        let account_metas = account_metas(&Self::ACCOUNTS, &accounts);
+
+        let mut instruction_data = [UNINIT_BYTE; 8];
+        Self::write_data(&mut instruction_data);
+
+        let instruction = Instruction {
+            program_id: crate::program_id(),
+            accounts: &account_metas,
+            data: unsafe { from_raw_parts(instruction_data.as_ptr() as _, 8) },
+        };
+
+        f(&instruction, &accounts)
+    }
+}
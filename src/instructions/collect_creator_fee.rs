@@ -0,0 +1,185 @@
+use core::{mem::MaybeUninit, slice::from_raw_parts};
+
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Instruction, Signer},
+    program::invoke_signed,
+    program_error::ProgramError,
+    ProgramResult,
+};
+
+use crate::{error::ValidationError, write_bytes, UNINIT_BYTE};
+
+use super::checks::{
+    check_associated_token_program, check_authority, check_key, check_pool_token_programs,
+    check_system_program, check_vaults, load_pool_state,
+};
+use super::encoding::{account_metas, exact_accounts, AccountRole};
+
+/// Collect the creator fee accrued to the pool
+///
+/// Only supported by CPMM releases with creator fees.
+///
+/// ### Accounts:
+///   0. `[WRITE, SIGNER]` creator - Pool creator, must match `PoolState::pool_creator`
+///   1. `[]` authority - Pool vault and lp mint authority (PDA)
+///   2. `[WRITE]` pool_state - Pool state stores accumulated creator fee amount
+///   3. `[]` amm_config - Amm config the pool belongs to
+///   4. `[WRITE]` token_0_vault - The address that holds pool tokens for token_0
+///   5. `[WRITE]` token_1_vault - The address that holds pool tokens for token_1
+///   6. `[]` vault_0_mint - The mint of token_0 vault
+///   7. `[]` vault_1_mint - The mint of token_1 vault
+///   8. `[WRITE]` creator_token_0 - Creator token0 account, created if needed
+///   9. `[WRITE]` creator_token_1 - Creator token1 account, created if needed
+///   10. `[]` token_0_program - Spl token program or token program 2022
+///   11. `[]` token_1_program - Spl token program or token program 2022
+///   12. `[]` associated_token_program - Program to create the creator token accounts
+///   13. `[]` system_program - To create a new program account
+pub struct CollectCreatorFee<'a> {
+    /// Pool creator, pays for the creator token accounts if needed
+    pub creator: &'a AccountInfo,
+    /// CHECK: pool vault and lp mint authority
+    pub authority: &'a AccountInfo,
+    /// Pool state stores accumulated creator fee amount
+    pub pool_state: &'a AccountInfo,
+    /// Amm config the pool belongs to
+    pub amm_config: &'a AccountInfo,
+    /// The address that holds pool tokens for token_0
+    pub token_0_vault: &'a AccountInfo,
+    /// The address that holds pool tokens for token_1
+    pub token_1_vault: &'a AccountInfo,
+    /// The mint of token_0 vault
+    pub vault_0_mint: &'a AccountInfo,
+    /// The mint of token_1 vault
+    pub vault_1_mint: &'a AccountInfo,
+    /// Creator token0 account
+    pub creator_token_0: &'a AccountInfo,
+    /// Creator token1 account
+    pub creator_token_1: &'a AccountInfo,
+    /// Spl token program or token program 2022
+    pub token_0_program: &'a AccountInfo,
+    /// Spl token program or token program 2022
+    pub token_1_program: &'a AccountInfo,
+    /// Program to create an ATA for the creator
+    pub associated_token_program: &'a AccountInfo,
+    /// To create a new program account
+    pub system_program: &'a AccountInfo,
+}
+
+impl<'a> CollectCreatorFee<'a> {
+    /// Bind `accounts` in the documented order.
+    ///
+    /// `accounts` must hold exactly the accounts of the instruction, fewer fail with
+    /// `NotEnoughAccountKeys` and more with `InvalidArgument`.
+    pub fn from_accounts(accounts: &'a [AccountInfo]) -> Result<Self, ProgramError> {
+        let [
+            creator, authority, pool_state, amm_config, token_0_vault, token_1_vault, vault_0_mint,
+            vault_1_mint, creator_token_0, creator_token_1, token_0_program, token_1_program,
+            associated_token_program, system_program,
+        ] = exact_accounts(accounts)?;
+        Ok(Self {
+            creator, authority, pool_state, amm_config, token_0_vault, token_1_vault, vault_0_mint,
+            vault_1_mint, creator_token_0, creator_token_1, token_0_program, token_1_program,
+            associated_token_program, system_program,
+        })
+    }
+}
+
+impl CollectCreatorFee<'_> {
+    /// Anchor discriminator of the instruction
+    pub const DISCRIMINATOR: [u8; 8] = [20, 22, 86, 123, 198, 28, 219, 132];
+
+    /// Length of the instruction data
+    pub const DATA_LEN: usize = 8;
+
+    /// Roles of the accounts, in order
+    pub const ACCOUNTS: [AccountRole; 14] = [
+        AccountRole::WRITABLE_SIGNER,
+        AccountRole::READONLY,
+        AccountRole::WRITABLE,
+        AccountRole::READONLY,
+        AccountRole::WRITABLE,
+        AccountRole::WRITABLE,
+        AccountRole::READONLY,
+        AccountRole::READONLY,
+        AccountRole::WRITABLE,
+        AccountRole::WRITABLE,
+        AccountRole::READONLY,
+        AccountRole::READONLY,
+        AccountRole::READONLY,
+        AccountRole::READONLY,
+    ];
+
+    /// Names of the accounts, in order
+    pub const ACCOUNT_NAMES: [&'static str; 14] = [
+        "creator", "authority", "pool_state", "amm_config", "token_0_vault", "token_1_vault",
+        "vault_0_mint", "vault_1_mint", "creator_token_0", "creator_token_1", "token_0_program",
+        "token_1_program", "associated_token_program", "system_program",
+    ];
+
+    /// Write the instruction data, initializing every byte of `data`
+    #[inline(always)]
+    pub fn write_data(data: &mut [MaybeUninit<u8>; 8]) {
+        // Instruction data layout:
+        // -  [0..8]: instruction discriminator (8 bytes)
+
+        // Set discriminator (8 bytes)
+        write_bytes(&mut data[0..8], &Self::DISCRIMINATOR);
+    }
+
+    /// Check the accounts against the `PoolState` before invoking.
+    ///
+    /// This is opt-in, the program performs the same checks but fails with less
+    /// descriptive errors after the CPI compute has been spent.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let pool_state = load_pool_state(self.pool_state)?;
+        check_key(self.creator, &pool_state.pool_creator, ValidationError::InvalidPoolCreator)?;
+        check_key(self.amm_config, &pool_state.amm_config, ValidationError::InvalidAmmConfig)?;
+        check_authority(self.authority, &pool_state)?;
+        check_vaults(
+            &pool_state,
+            self.token_0_vault,
+            self.token_1_vault,
+            self.vault_0_mint,
+            self.vault_1_mint,
+        )?;
+        check_pool_token_programs(&pool_state, self.token_0_program, self.token_1_program)?;
+        check_associated_token_program(self.associated_token_program)?;
+        check_system_program(self.system_program)
+    }
+
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        self.with_instruction(|instruction, accounts| invoke_signed(instruction, accounts, signers))
+    }
+
+    /// Build the instruction and call `f` with it and the account infos, in instruction order.
+    ///
+    /// `invoke_signed` passes them to `pinocchio::program::invoke_signed`, other executors such
+    /// as `test_utils::svm::Svm` can run the same instruction.
+    #[inline(always)]
+    pub fn with_instruction<R>(&self, f: impl FnOnce(&Instruction, &[&AccountInfo; 14]) -> R) -> R {
+        let accounts = [
+            self.creator, self.authority, self.pool_state, self.amm_config,
+            self.token_0_vault, self.token_1_vault, self.vault_0_mint, self.vault_1_mint,
+            self.creator_token_0, self.creator_token_1, self.token_0_program, self.token_1_program,
+            self.associated_token_program, self.system_program,
+        ];
+        let account_metas = account_metas(&Self::ACCOUNTS, &accounts);
+
+        let mut instruction_data = [UNINIT_BYTE; 8];
+        Self::write_data(&mut instruction_data);
+
+        let instruction = Instruction {
+            program_id: crate::program_id(),
+            accounts: &account_metas,
+            data: unsafe { from_raw_parts(instruction_data.as_ptr() as _, 8) },
+        };
+
+        f(&instruction, &accounts)
+    }
+}
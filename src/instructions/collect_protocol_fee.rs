@@ -0,0 +1,198 @@
+use core::{mem::MaybeUninit, slice::from_raw_parts};
+
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Instruction, Signer},
+    program::invoke_signed,
+    program_error::ProgramError,
+    ProgramResult,
+};
+
+use crate::{admin, error::ValidationError, write_bytes, UNINIT_BYTE};
+
+use super::checks::{
+    check_authority, check_key, check_token_programs, check_vaults, load_amm_config,
+    load_pool_state,
+};
+use super::encoding::{account_metas, exact_accounts, AccountRole};
+
+/// Collect the protocol fee accrued to the pool
+///
+/// ### Accounts:
+///   0. `[SIGNER]` owner - Only the amm config protocol owner or admin can collect fee
+///   1. `[]` authority - Pool vault and lp mint authority (PDA)
+///   2. `[WRITE]` pool_state - Pool state stores accumulated protocol fee amount
+///   3. `[]` amm_config - Amm config account stores owner
+///   4. `[WRITE]` token_0_vault - The address that holds pool tokens for token_0
+///   5. `[WRITE]` token_1_vault - The address that holds pool tokens for token_1
+///   6. `[]` vault_0_mint - The mint of token_0 vault
+///   7. `[]` vault_1_mint - The mint of token_1 vault
+///   8. `[WRITE]` recipient_token_0_account - The address that receives the collected token_0 protocol fees
+///   9. `[WRITE]` recipient_token_1_account - The address that receives the collected token_1 protocol fees
+///   10. `[]` token_program - The SPL program to perform token transfers
+///   11. `[]` token_program_2022 - The SPL program 2022 to perform token transfers
+pub struct CollectProtocolFee<'a> {
+    /// Only the amm config protocol owner or admin can collect fee
+    pub owner: &'a AccountInfo,
+    /// CHECK: pool vault and lp mint authority
+    pub authority: &'a AccountInfo,
+    /// Pool state stores accumulated protocol fee amount
+    pub pool_state: &'a AccountInfo,
+    /// Amm config account stores owner
+    pub amm_config: &'a AccountInfo,
+    /// The address that holds pool tokens for token_0
+    pub token_0_vault: &'a AccountInfo,
+    /// The address that holds pool tokens for token_1
+    pub token_1_vault: &'a AccountInfo,
+    /// The mint of token_0 vault
+    pub vault_0_mint: &'a AccountInfo,
+    /// The mint of token_1 vault
+    pub vault_1_mint: &'a AccountInfo,
+    /// The address that receives the collected token_0 protocol fees
+    pub recipient_token_0_account: &'a AccountInfo,
+    /// The address that receives the collected token_1 protocol fees
+    pub recipient_token_1_account: &'a AccountInfo,
+    /// The SPL program to perform token transfers
+    pub token_program: &'a AccountInfo,
+    /// The SPL program 2022 to perform token transfers
+    pub token_program_2022: &'a AccountInfo,
+
+    /// Instruction parameters
+    ///
+    /// The maximum amount of token_0 to send, can be 0 to collect fees in only token_1
+    pub amount_0_requested: u64,
+    /// The maximum amount of token_1 to send, can be 0 to collect fees in only token_0
+    pub amount_1_requested: u64,
+}
+
+impl<'a> CollectProtocolFee<'a> {
+    /// Bind `accounts` in the documented order.
+    ///
+    /// `accounts` must hold exactly the accounts of the instruction, fewer fail with
+    /// `NotEnoughAccountKeys` and more with `InvalidArgument`.
+    pub fn from_accounts(
+        accounts: &'a [AccountInfo],
+        amount_0_requested: u64,
+        amount_1_requested: u64,
+    ) -> Result<Self, ProgramError> {
+        let [
+            owner, authority, pool_state, amm_config, token_0_vault, token_1_vault, vault_0_mint,
+            vault_1_mint, recipient_token_0_account, recipient_token_1_account, token_program,
+            token_program_2022,
+        ] = exact_accounts(accounts)?;
+        Ok(Self {
+            owner, authority, pool_state, amm_config, token_0_vault, token_1_vault, vault_0_mint,
+            vault_1_mint, recipient_token_0_account, recipient_token_1_account, token_program,
+            token_program_2022, amount_0_requested, amount_1_requested,
+        })
+    }
+}
+
+impl CollectProtocolFee<'_> {
+    /// Anchor discriminator of the instruction
+    pub const DISCRIMINATOR: [u8; 8] = [136, 136, 252, 221, 194, 66, 126, 89];
+
+    /// Length of the instruction data
+    pub const DATA_LEN: usize = 24;
+
+    /// Roles of the accounts, in order
+    pub const ACCOUNTS: [AccountRole; 12] = [
+        AccountRole::READONLY_SIGNER,
+        AccountRole::READONLY,
+        AccountRole::WRITABLE,
+        AccountRole::READONLY,
+        AccountRole::WRITABLE,
+        AccountRole::WRITABLE,
+        AccountRole::READONLY,
+        AccountRole::READONLY,
+        AccountRole::WRITABLE,
+        AccountRole::WRITABLE,
+        AccountRole::READONLY,
+        AccountRole::READONLY,
+    ];
+
+    /// Names of the accounts, in order
+    pub const ACCOUNT_NAMES: [&'static str; 12] = [
+        "owner", "authority", "pool_state", "amm_config", "token_0_vault", "token_1_vault",
+        "vault_0_mint", "vault_1_mint", "recipient_token_0_account", "recipient_token_1_account",
+        "token_program", "token_program_2022",
+    ];
+
+    /// Write the instruction data, initializing every byte of `data`
+    #[inline(always)]
+    pub fn write_data(
+        data: &mut [MaybeUninit<u8>; 24],
+        amount_0_requested: u64,
+        amount_1_requested: u64,
+    ) {
+        // Instruction data layout:
+        // -  [0..8]: instruction discriminator (8 bytes)
+        // -  [8..16]: amount_0_requested (8 bytes, u64)
+        // -  [16..24]: amount_1_requested (8 bytes, u64)
+
+        // Set discriminator (8 bytes)
+        write_bytes(&mut data[0..8], &Self::DISCRIMINATOR);
+
+        // Set amount_0_requested (8 bytes)
+        write_bytes(&mut data[8..16], &amount_0_requested.to_le_bytes());
+
+        // Set amount_1_requested (8 bytes)
+        write_bytes(&mut data[16..24], &amount_1_requested.to_le_bytes());
+    }
+
+    /// Check the accounts against the `PoolState` and `AmmConfig` before invoking.
+    ///
+    /// This is opt-in, the program performs the same checks but fails with less
+    /// descriptive errors after the CPI compute has been spent.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let pool_state = load_pool_state(self.pool_state)?;
+        check_key(self.amm_config, &pool_state.amm_config, ValidationError::InvalidAmmConfig)?;
+        let amm_config = load_amm_config(self.amm_config)?;
+        if self.owner.key() != &amm_config.protocol_owner && self.owner.key() != &admin::ID {
+            return Err(ValidationError::InvalidOwner);
+        }
+        check_authority(self.authority, &pool_state)?;
+        check_vaults(
+            &pool_state,
+            self.token_0_vault,
+            self.token_1_vault,
+            self.vault_0_mint,
+            self.vault_1_mint,
+        )?;
+        check_token_programs(self.token_program, self.token_program_2022)
+    }
+
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        self.with_instruction(|instruction, accounts| invoke_signed(instruction, accounts, signers))
+    }
+
+    /// Build the instruction and call `f` with it and the account infos, in instruction order.
+    ///
+    /// `invoke_signed` passes them to `pinocchio::program::invoke_signed`, other executors such
+    /// as `test_utils::svm::Svm` can run the same instruction.
+    #[inline(always)]
+    pub fn with_instruction<R>(&self, f: impl FnOnce(&Instruction, &[&AccountInfo; 12]) -> R) -> R {
+        let accounts = [
+            self.owner, self.authority, self.pool_state, self.amm_config,
+            self.token_0_vault, self.token_1_vault, self.vault_0_mint, self.vault_1_mint,
+            self.recipient_token_0_account, self.recipient_token_1_account, self.token_program, self.token_program_2022,
+        ];
+        let account_metas = account_metas(&Self::ACCOUNTS, &accounts);
+
+        let mut instruction_data = [UNINIT_BYTE; 24];
+        Self::write_data(&mut instruction_data, self.amount_0_requested, self.amount_1_requested);
+
+        let instruction = Instruction {
+            program_id: crate::program_id(),
+            accounts: &account_metas,
+            data: unsafe { from_raw_parts(instruction_data.as_ptr() as _, 24) },
+        };
+
+        f(&instruction, &accounts)
+    }
+}
@@ -0,0 +1,170 @@
+use core::{mem::MaybeUninit, slice::from_raw_parts};
+
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Instruction, Signer},
+    program::invoke_signed,
+    program_error::ProgramError,
+    ProgramResult,
+};
+
+use crate::{admin, error::ValidationError, write_bytes, UNINIT_BYTE};
+
+use super::checks::{check_key, check_system_program};
+use super::encoding::{account_metas, exact_accounts, AccountRole};
+
+/// Creates a new `AmmConfig` holding the fee rates pools can be created with
+///
+/// ### Accounts:
+///   0. `[WRITE, SIGNER]` owner - Address to be set as protocol owner, must be `crate::admin::ID`
+///   1. `[WRITE]` amm_config - Config state account to initialize (PDA)
+///   2. `[]` system_program - To create a new program account
+pub struct CreateAmmConfig<'a> {
+    /// Address to be set as protocol owner. Must be `crate::admin::ID`
+    pub owner: &'a AccountInfo,
+    /// Initialize config state account to store protocol owner address and fee rates.
+    pub amm_config: &'a AccountInfo,
+    /// To create a new program account
+    pub system_program: &'a AccountInfo,
+
+    /// Instruction parameters
+    pub index: u16,
+    pub trade_fee_rate: u64,
+    pub protocol_fee_rate: u64,
+    pub fund_fee_rate: u64,
+    pub create_pool_fee: u64,
+    /// The pool creator fee rate. Only read by releases supporting creator
+    /// fees, older releases ignore the trailing bytes.
+    pub creator_fee_rate: u64,
+}
+
+impl<'a> CreateAmmConfig<'a> {
+    /// Bind `accounts` in the documented order.
+    ///
+    /// `accounts` must hold exactly the accounts of the instruction, fewer fail with
+    /// `NotEnoughAccountKeys` and more with `InvalidArgument`.
+    pub fn from_accounts(
+        accounts: &'a [AccountInfo],
+        index: u16,
+        trade_fee_rate: u64,
+        protocol_fee_rate: u64,
+        fund_fee_rate: u64,
+        create_pool_fee: u64,
+        creator_fee_rate: u64,
+    ) -> Result<Self, ProgramError> {
+        let [owner, amm_config, system_program] = exact_accounts(accounts)?;
+        Ok(Self {
+            owner, amm_config, system_program, index, trade_fee_rate, protocol_fee_rate,
+            fund_fee_rate, create_pool_fee, creator_fee_rate,
+        })
+    }
+}
+
+impl CreateAmmConfig<'_> {
+    /// Anchor discriminator of the instruction
+    pub const DISCRIMINATOR: [u8; 8] = [137, 52, 237, 212, 215, 117, 108, 104];
+
+    /// Length of the instruction data
+    pub const DATA_LEN: usize = 50;
+
+    /// Roles of the accounts, in order
+    pub const ACCOUNTS: [AccountRole; 3] = [
+        AccountRole::WRITABLE_SIGNER,
+        AccountRole::WRITABLE,
+        AccountRole::READONLY,
+    ];
+
+    /// Names of the accounts, in order
+    pub const ACCOUNT_NAMES: [&'static str; 3] = [
+        "owner", "amm_config", "system_program",
+    ];
+
+    /// Write the instruction data, initializing every byte of `data`
+    #[inline(always)]
+    pub fn write_data(
+        data: &mut [MaybeUninit<u8>; 50],
+        index: u16,
+        trade_fee_rate: u64,
+        protocol_fee_rate: u64,
+        fund_fee_rate: u64,
+        create_pool_fee: u64,
+        creator_fee_rate: u64,
+    ) {
+        // Instruction data layout:
+        // -  [0..8]: instruction discriminator (8 bytes)
+        // -  [8..10]: index (2 bytes, u16)
+        // -  [10..18]: trade_fee_rate (8 bytes, u64)
+        // -  [18..26]: protocol_fee_rate (8 bytes, u64)
+        // -  [26..34]: fund_fee_rate (8 bytes, u64)
+        // -  [34..42]: create_pool_fee (8 bytes, u64)
+        // -  [42..50]: creator_fee_rate (8 bytes, u64)
+
+        // Set discriminator (8 bytes)
+        write_bytes(&mut data[0..8], &Self::DISCRIMINATOR);
+
+        // Set index (2 bytes)
+        write_bytes(&mut data[8..10], &index.to_le_bytes());
+
+        // Set trade_fee_rate (8 bytes)
+        write_bytes(&mut data[10..18], &trade_fee_rate.to_le_bytes());
+
+        // Set protocol_fee_rate (8 bytes)
+        write_bytes(&mut data[18..26], &protocol_fee_rate.to_le_bytes());
+
+        // Set fund_fee_rate (8 bytes)
+        write_bytes(&mut data[26..34], &fund_fee_rate.to_le_bytes());
+
+        // Set create_pool_fee (8 bytes)
+        write_bytes(&mut data[34..42], &create_pool_fee.to_le_bytes());
+
+        // Set creator_fee_rate (8 bytes)
+        write_bytes(&mut data[42..50], &creator_fee_rate.to_le_bytes());
+    }
+
+    /// Check the accounts against the admin before invoking.
+    ///
+    /// This is opt-in, the program performs the same checks but fails with less
+    /// descriptive errors after the CPI compute has been spent.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        check_key(self.owner, &admin::ID, ValidationError::InvalidOwner)?;
+        check_system_program(self.system_program)
+    }
+
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        self.with_instruction(|instruction, accounts| invoke_signed(instruction, accounts, signers))
+    }
+
+    /// Build the instruction and call `f` with it and the account infos, in instruction order.
+    ///
+    /// `invoke_signed` passes them to `pinocchio::program::invoke_signed`, other executors such
+    /// as `test_utils::svm::Svm` can run the same instruction.
+    #[inline(always)]
+    pub fn with_instruction<R>(&self, f: impl FnOnce(&Instruction, &[&AccountInfo; 3]) -> R) -> R {
+        let accounts = [self.owner, self.amm_config, self.system_program];
+        let account_metas = account_metas(&Self::ACCOUNTS, &accounts);
+
+        let mut instruction_data = [UNINIT_BYTE; 50];
+        Self::write_data(
+            &mut instruction_data,
+            self.index,
+            self.trade_fee_rate,
+            self.protocol_fee_rate,
+            self.fund_fee_rate,
+            self.create_pool_fee,
+            self.creator_fee_rate,
+        );
+
+        let instruction = Instruction {
+            program_id: crate::program_id(),
+            accounts: &account_metas,
+            data: unsafe { from_raw_parts(instruction_data.as_ptr() as _, 50) },
+        };
+
+        f(&instruction, &accounts)
+    }
+}
@@ -0,0 +1,501 @@
+use crate::{error::DecodeError, states::CreatorFeeOn};
+
+use super::{
+    AccountRole, ClosePermissionPda, CollectCreatorFee, CollectFundFee, CollectProtocolFee,
+    CreateAmmConfig, CreatePermissionPda, Deposit, Initialize, InitializeWithPermission,
+    SwapBaseInput, SwapBaseOutput, UpdateAmmConfig, UpdateAmmConfigParam, UpdatePoolStatus,
+    Withdraw,
+};
+
+/// Roles of the `UpdateAmmConfig` accounts when the new owner is passed
+const UPDATE_AMM_CONFIG_OWNER_ACCOUNTS: [AccountRole; 3] = [
+    UpdateAmmConfig::ACCOUNTS[0],
+    UpdateAmmConfig::ACCOUNTS[1],
+    UpdateAmmConfig::NEW_OWNER_ACCOUNT,
+];
+
+/// Names of the `UpdateAmmConfig` accounts when the new owner is passed
+const UPDATE_AMM_CONFIG_OWNER_ACCOUNT_NAMES: [&str; 3] = [
+    UpdateAmmConfig::ACCOUNT_NAMES[0],
+    UpdateAmmConfig::ACCOUNT_NAMES[1],
+    UpdateAmmConfig::NEW_OWNER_ACCOUNT_NAME,
+];
+
+/// A CPMM instruction decoded from its instruction data
+///
+/// The reverse of the CPI structs of the same name: `decode` identifies the instruction
+/// by its discriminator and reads the arguments from the layout written by `write_data`.
+/// Like the program, bytes after the arguments are ignored.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CpmmInstruction {
+    Initialize {
+        init_amount_0: u64,
+        init_amount_1: u64,
+        open_time: u64,
+    },
+    Deposit {
+        lp_token_amount: u64,
+        maximum_token_0_amount: u64,
+        maximum_token_1_amount: u64,
+    },
+    Withdraw {
+        lp_token_amount: u64,
+        minimum_token_0_amount: u64,
+        minimum_token_1_amount: u64,
+    },
+    SwapBaseInput {
+        amount_in: u64,
+        minimum_amount_out: u64,
+    },
+    SwapBaseOutput {
+        max_amount_in: u64,
+        amount_out: u64,
+    },
+    CreateAmmConfig {
+        index: u16,
+        trade_fee_rate: u64,
+        protocol_fee_rate: u64,
+        fund_fee_rate: u64,
+        create_pool_fee: u64,
+        creator_fee_rate: u64,
+    },
+    UpdateAmmConfig {
+        param: u8,
+        value: u64,
+    },
+    UpdatePoolStatus {
+        status: u8,
+    },
+    CollectProtocolFee {
+        amount_0_requested: u64,
+        amount_1_requested: u64,
+    },
+    CollectFundFee {
+        amount_0_requested: u64,
+        amount_1_requested: u64,
+    },
+    InitializeWithPermission {
+        init_amount_0: u64,
+        init_amount_1: u64,
+        open_time: u64,
+        creator_fee_on: CreatorFeeOn,
+    },
+    CollectCreatorFee,
+    CreatePermissionPda,
+    ClosePermissionPda,
+}
+
+impl CpmmInstruction {
+    /// Decode the instruction data of a CPMM instruction
+    pub fn decode(data: &[u8]) -> Result<Self, DecodeError> {
+        if data.len() < 8 {
+            return Err(DecodeError::UnknownDiscriminator);
+        }
+        let discriminator = &data[..8];
+
+        let instruction = if discriminator == Initialize::DISCRIMINATOR {
+            check_len(data, Initialize::DATA_LEN)?;
+            Self::Initialize {
+                init_amount_0: u64_at(data, 8),
+                init_amount_1: u64_at(data, 16),
+                open_time: u64_at(data, 24),
+            }
+        } else if discriminator == Deposit::DISCRIMINATOR {
+            check_len(data, Deposit::DATA_LEN)?;
+            Self::Deposit {
+                lp_token_amount: u64_at(data, 8),
+                maximum_token_0_amount: u64_at(data, 16),
+                maximum_token_1_amount: u64_at(data, 24),
+            }
+        } else if discriminator == Withdraw::DISCRIMINATOR {
+            check_len(data, Withdraw::DATA_LEN)?;
+            Self::Withdraw {
+                lp_token_amount: u64_at(data, 8),
+                minimum_token_0_amount: u64_at(data, 16),
+                minimum_token_1_amount: u64_at(data, 24),
+            }
+        } else if discriminator == SwapBaseInput::DISCRIMINATOR {
+            check_len(data, SwapBaseInput::DATA_LEN)?;
+            Self::SwapBaseInput {
+                amount_in: u64_at(data, 8),
+                minimum_amount_out: u64_at(data, 16),
+            }
+        } else if discriminator == SwapBaseOutput::DISCRIMINATOR {
+            check_len(data, SwapBaseOutput::DATA_LEN)?;
+            Self::SwapBaseOutput {
+                max_amount_in: u64_at(data, 8),
+                amount_out: u64_at(data, 16),
+            }
+        } else if discriminator == CreateAmmConfig::DISCRIMINATOR {
+            check_len(data, CreateAmmConfig::DATA_LEN)?;
+            Self::CreateAmmConfig {
+                index: u16::from_le_bytes([data[8], data[9]]),
+                trade_fee_rate: u64_at(data, 10),
+                protocol_fee_rate: u64_at(data, 18),
+                fund_fee_rate: u64_at(data, 26),
+                create_pool_fee: u64_at(data, 34),
+                creator_fee_rate: u64_at(data, 42),
+            }
+        } else if discriminator == UpdateAmmConfig::DISCRIMINATOR {
+            check_len(data, UpdateAmmConfig::DATA_LEN)?;
+            Self::UpdateAmmConfig {
+                param: data[8],
+                value: u64_at(data, 9),
+            }
+        } else if discriminator == UpdatePoolStatus::DISCRIMINATOR {
+            check_len(data, UpdatePoolStatus::DATA_LEN)?;
+            Self::UpdatePoolStatus { status: data[8] }
+        } else if discriminator == CollectProtocolFee::DISCRIMINATOR {
+            check_len(data, CollectProtocolFee::DATA_LEN)?;
+            Self::CollectProtocolFee {
+                amount_0_requested: u64_at(data, 8),
+                amount_1_requested: u64_at(data, 16),
+            }
+        } else if discriminator == CollectFundFee::DISCRIMINATOR {
+            check_len(data, CollectFundFee::DATA_LEN)?;
+            Self::CollectFundFee {
+                amount_0_requested: u64_at(data, 8),
+                amount_1_requested: u64_at(data, 16),
+            }
+        } else if discriminator == InitializeWithPermission::DISCRIMINATOR {
+            check_len(data, InitializeWithPermission::DATA_LEN)?;
+            Self::InitializeWithPermission {
+                init_amount_0: u64_at(data, 8),
+                init_amount_1: u64_at(data, 16),
+                open_time: u64_at(data, 24),
+                creator_fee_on: CreatorFeeOn::try_from(data[32])
+                    .map_err(|_| DecodeError::InvalidInstructionData)?,
+            }
+        } else if discriminator == CollectCreatorFee::DISCRIMINATOR {
+            Self::CollectCreatorFee
+        } else if discriminator == CreatePermissionPda::DISCRIMINATOR {
+            Self::CreatePermissionPda
+        } else if discriminator == ClosePermissionPda::DISCRIMINATOR {
+            Self::ClosePermissionPda
+        } else {
+            return Err(DecodeError::UnknownDiscriminator);
+        };
+
+        Ok(instruction)
+    }
+
+    /// Return the name of the instruction, as in the program
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Initialize { .. } => "initialize",
+            Self::Deposit { .. } => "deposit",
+            Self::Withdraw { .. } => "withdraw",
+            Self::SwapBaseInput { .. } => "swap_base_input",
+            Self::SwapBaseOutput { .. } => "swap_base_output",
+            Self::CreateAmmConfig { .. } => "create_amm_config",
+            Self::UpdateAmmConfig { .. } => "update_amm_config",
+            Self::UpdatePoolStatus { .. } => "update_pool_status",
+            Self::CollectProtocolFee { .. } => "collect_protocol_fee",
+            Self::CollectFundFee { .. } => "collect_fund_fee",
+            Self::InitializeWithPermission { .. } => "initialize_with_permission",
+            Self::CollectCreatorFee => "collect_creator_fee",
+            Self::CreatePermissionPda => "create_permission_pda",
+            Self::ClosePermissionPda => "close_permission_pda",
+        }
+    }
+
+    /// Return the names of the instruction accounts, in order
+    pub fn account_names(&self) -> &'static [&'static str] {
+        match self {
+            Self::Initialize { .. } => &Initialize::ACCOUNT_NAMES,
+            Self::Deposit { .. } => &Deposit::ACCOUNT_NAMES,
+            Self::Withdraw { .. } => &Withdraw::ACCOUNT_NAMES,
+            Self::SwapBaseInput { .. } => &SwapBaseInput::ACCOUNT_NAMES,
+            Self::SwapBaseOutput { .. } => &SwapBaseOutput::ACCOUNT_NAMES,
+            Self::CreateAmmConfig { .. } => &CreateAmmConfig::ACCOUNT_NAMES,
+            Self::UpdateAmmConfig { param, .. } if has_new_owner(*param) => {
+                &UPDATE_AMM_CONFIG_OWNER_ACCOUNT_NAMES
+            }
+            Self::UpdateAmmConfig { .. } => &UpdateAmmConfig::ACCOUNT_NAMES,
+            Self::UpdatePoolStatus { .. } => &UpdatePoolStatus::ACCOUNT_NAMES,
+            Self::CollectProtocolFee { .. } => &CollectProtocolFee::ACCOUNT_NAMES,
+            Self::CollectFundFee { .. } => &CollectFundFee::ACCOUNT_NAMES,
+            Self::InitializeWithPermission { .. } => &InitializeWithPermission::ACCOUNT_NAMES,
+            Self::CollectCreatorFee => &CollectCreatorFee::ACCOUNT_NAMES,
+            Self::CreatePermissionPda => &CreatePermissionPda::ACCOUNT_NAMES,
+            Self::ClosePermissionPda => &ClosePermissionPda::ACCOUNT_NAMES,
+        }
+    }
+
+    /// Return the roles of the instruction accounts, in order
+    pub fn account_roles(&self) -> &'static [AccountRole] {
+        match self {
+            Self::Initialize { .. } => &Initialize::ACCOUNTS,
+            Self::Deposit { .. } => &Deposit::ACCOUNTS,
+            Self::Withdraw { .. } => &Withdraw::ACCOUNTS,
+            Self::SwapBaseInput { .. } => &SwapBaseInput::ACCOUNTS,
+            Self::SwapBaseOutput { .. } => &SwapBaseOutput::ACCOUNTS,
+            Self::CreateAmmConfig { .. } => &CreateAmmConfig::ACCOUNTS,
+            Self::UpdateAmmConfig { param, .. } if has_new_owner(*param) => {
+                &UPDATE_AMM_CONFIG_OWNER_ACCOUNTS
+            }
+            Self::UpdateAmmConfig { .. } => &UpdateAmmConfig::ACCOUNTS,
+            Self::UpdatePoolStatus { .. } => &UpdatePoolStatus::ACCOUNTS,
+            Self::CollectProtocolFee { .. } => &CollectProtocolFee::ACCOUNTS,
+            Self::CollectFundFee { .. } => &CollectFundFee::ACCOUNTS,
+            Self::InitializeWithPermission { .. } => &InitializeWithPermission::ACCOUNTS,
+            Self::CollectCreatorFee => &CollectCreatorFee::ACCOUNTS,
+            Self::CreatePermissionPda => &CreatePermissionPda::ACCOUNTS,
+            Self::ClosePermissionPda => &ClosePermissionPda::ACCOUNTS,
+        }
+    }
+
+    /// Pair the account `keys` of the instruction with their names.
+    ///
+    /// `keys` can be pubkeys, `AccountMeta`s or any per account value, in instruction order.
+    /// Keys past the instruction accounts, the remaining accounts, are not returned.
+    pub fn named_accounts<'k, K>(
+        &self,
+        keys: &'k [K],
+    ) -> Result<impl Iterator<Item = (&'static str, &'k K)>, DecodeError> {
+        let names = self.account_names();
+        if keys.len() < names.len() {
+            return Err(DecodeError::NotEnoughAccounts);
+        }
+        Ok(names.iter().copied().zip(keys))
+    }
+
+    /// Return the key of the account named `name`, `None` if the instruction has no such
+    /// account or `keys` is too short.
+    pub fn account<'k, K>(&self, keys: &'k [K], name: &str) -> Option<&'k K> {
+        let position = self.account_names().iter().position(|n| *n == name)?;
+        keys.get(position)
+    }
+}
+
+/// Whether an `UpdateAmmConfig` of `param` passes the new owner account
+#[inline(always)]
+fn has_new_owner(param: u8) -> bool {
+    param == UpdateAmmConfigParam::NEW_PROTOCOL_OWNER || param == UpdateAmmConfigParam::NEW_FUND_OWNER
+}
+
+#[inline(always)]
+fn check_len(data: &[u8], len: usize) -> Result<(), DecodeError> {
+    if data.len() < len {
+        return Err(DecodeError::InvalidInstructionData);
+    }
+    Ok(())
+}
+
+/// Read the `u64` at `offset`, the length must have been checked
+#[inline(always)]
+fn u64_at(data: &[u8], offset: usize) -> u64 {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&data[offset..offset + 8]);
+    u64::from_le_bytes(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use core::mem::MaybeUninit;
+
+    use super::*;
+
+    /// Return the instruction data written by `write`
+    fn written<const N: usize>(write: impl FnOnce(&mut [MaybeUninit<u8>; N])) -> [u8; N] {
+        let mut data = [MaybeUninit::<u8>::uninit(); N];
+        write(&mut data);
+        // SAFETY: `write_data` initializes every byte of `data`.
+        data.map(|byte| unsafe { byte.assume_init() })
+    }
+
+    /// Decode `data`, and check that any shorter data is rejected and trailing bytes ignored
+    fn round_trip(data: &[u8]) -> CpmmInstruction {
+        let instruction = CpmmInstruction::decode(data).unwrap();
+        for len in 8..data.len() {
+            let error = Err(DecodeError::InvalidInstructionData);
+            assert_eq!(CpmmInstruction::decode(&data[..len]), error, "{}", instruction.name());
+        }
+        assert_eq!(
+            CpmmInstruction::decode(&[data, &[0xff]].concat()),
+            Ok(instruction),
+            "{}",
+            instruction.name()
+        );
+        instruction
+    }
+
+    #[test]
+    fn pool_instructions() {
+        let data = written(|data| Initialize::write_data(data, 1, u64::MAX, 3));
+        assert_eq!(
+            round_trip(&data),
+            CpmmInstruction::Initialize { init_amount_0: 1, init_amount_1: u64::MAX, open_time: 3 }
+        );
+        let data = written(|data| Deposit::write_data(data, 4, 5, 6));
+        assert_eq!(
+            round_trip(&data),
+            CpmmInstruction::Deposit {
+                lp_token_amount: 4,
+                maximum_token_0_amount: 5,
+                maximum_token_1_amount: 6,
+            }
+        );
+        let data = written(|data| Withdraw::write_data(data, 7, 8, 9));
+        assert_eq!(
+            round_trip(&data),
+            CpmmInstruction::Withdraw {
+                lp_token_amount: 7,
+                minimum_token_0_amount: 8,
+                minimum_token_1_amount: 9,
+            }
+        );
+        let data = written(|data| SwapBaseInput::write_data(data, 10, 11));
+        assert_eq!(
+            round_trip(&data),
+            CpmmInstruction::SwapBaseInput { amount_in: 10, minimum_amount_out: 11 }
+        );
+        let data = written(|data| SwapBaseOutput::write_data(data, 12, 13));
+        assert_eq!(
+            round_trip(&data),
+            CpmmInstruction::SwapBaseOutput { max_amount_in: 12, amount_out: 13 }
+        );
+    }
+
+    #[test]
+    fn admin_instructions() {
+        let data = written(|data| CreateAmmConfig::write_data(data, 0x0102, 14, 15, 16, 17, 18));
+        assert_eq!(
+            round_trip(&data),
+            CpmmInstruction::CreateAmmConfig {
+                index: 0x0102,
+                trade_fee_rate: 14,
+                protocol_fee_rate: 15,
+                fund_fee_rate: 16,
+                create_pool_fee: 17,
+                creator_fee_rate: 18,
+            }
+        );
+        let data = written(|data| UpdatePoolStatus::write_data(data, 4));
+        assert_eq!(round_trip(&data), CpmmInstruction::UpdatePoolStatus { status: 4 });
+        let data = written(|data| CollectProtocolFee::write_data(data, 19, 20));
+        assert_eq!(
+            round_trip(&data),
+            CpmmInstruction::CollectProtocolFee { amount_0_requested: 19, amount_1_requested: 20 }
+        );
+        let data = written(|data| CollectFundFee::write_data(data, 21, 22));
+        assert_eq!(
+            round_trip(&data),
+            CpmmInstruction::CollectFundFee { amount_0_requested: 21, amount_1_requested: 22 }
+        );
+        let data = written(CreatePermissionPda::write_data);
+        assert_eq!(round_trip(&data), CpmmInstruction::CreatePermissionPda);
+        let data = written(ClosePermissionPda::write_data);
+        assert_eq!(round_trip(&data), CpmmInstruction::ClosePermissionPda);
+    }
+
+    #[test]
+    fn creator_fee_instructions() {
+        let data = written(|data| {
+            InitializeWithPermission::write_data(data, 23, 24, 25, CreatorFeeOn::OnlyToken1)
+        });
+        assert_eq!(
+            round_trip(&data),
+            CpmmInstruction::InitializeWithPermission {
+                init_amount_0: 23,
+                init_amount_1: 24,
+                open_time: 25,
+                creator_fee_on: CreatorFeeOn::OnlyToken1,
+            }
+        );
+        let data = written(CollectCreatorFee::write_data);
+        assert_eq!(round_trip(&data), CpmmInstruction::CollectCreatorFee);
+    }
+
+    #[test]
+    fn invalid_creator_fee_on() {
+        let mut data = written(|data| {
+            InitializeWithPermission::write_data(data, 23, 24, 25, CreatorFeeOn::BothToken)
+        });
+        data[32] = 3;
+        assert_eq!(CpmmInstruction::decode(&data), Err(DecodeError::InvalidInstructionData));
+    }
+
+    #[test]
+    fn update_amm_config() {
+        for param in [
+            UpdateAmmConfigParam::TRADE_FEE_RATE,
+            UpdateAmmConfigParam::CREATE_POOL_FEE,
+            UpdateAmmConfigParam::DISABLE_CREATE_POOL,
+            UpdateAmmConfigParam::CREATOR_FEE_RATE,
+        ] {
+            let data = written(|data| UpdateAmmConfig::write_data(data, param, 26));
+            let instruction = round_trip(&data);
+
+            assert_eq!(instruction, CpmmInstruction::UpdateAmmConfig { param, value: 26 });
+            assert_eq!(instruction.account_names(), ["owner", "amm_config"]);
+            assert_eq!(instruction.account_roles(), UpdateAmmConfig::ACCOUNTS);
+            assert_eq!(instruction.account(&[1, 2, 3], "new_owner"), None);
+        }
+    }
+
+    #[test]
+    fn update_amm_config_new_owner() {
+        for param in [
+            UpdateAmmConfigParam::NEW_PROTOCOL_OWNER,
+            UpdateAmmConfigParam::NEW_FUND_OWNER,
+        ] {
+            let data = written(|data| UpdateAmmConfig::write_data(data, param, 0));
+            let instruction = round_trip(&data);
+
+            assert_eq!(instruction, CpmmInstruction::UpdateAmmConfig { param, value: 0 });
+            assert_eq!(instruction.account_names(), ["owner", "amm_config", "new_owner"]);
+            assert_eq!(
+                instruction.account_roles(),
+                [AccountRole::READONLY_SIGNER, AccountRole::WRITABLE, AccountRole::READONLY]
+            );
+            assert_eq!(instruction.account(&[1, 2, 3], "new_owner"), Some(&3));
+            assert!(matches!(
+                instruction.named_accounts(&[1, 2]),
+                Err(DecodeError::NotEnoughAccounts)
+            ));
+        }
+    }
+
+    #[test]
+    fn unknown_or_short_discriminators() {
+        let mut data = written(|data| SwapBaseInput::write_data(data, 10, 11));
+        data[7] ^= 1;
+        assert_eq!(CpmmInstruction::decode(&data), Err(DecodeError::UnknownDiscriminator));
+
+        let data = written(CollectCreatorFee::write_data);
+        assert_eq!(CpmmInstruction::decode(&data[..7]), Err(DecodeError::UnknownDiscriminator));
+        assert_eq!(CpmmInstruction::decode(&[]), Err(DecodeError::UnknownDiscriminator));
+    }
+
+    #[test]
+    fn named_accounts() {
+        let instruction = CpmmInstruction::SwapBaseInput { amount_in: 10, minimum_amount_out: 11 };
+        let keys: [u8; 14] = core::array::from_fn(|i| i as u8);
+
+        assert!(matches!(
+            instruction.named_accounts(&keys[..12]),
+            Err(DecodeError::NotEnoughAccounts)
+        ));
+        // the remaining accounts are not named
+        let named = instruction.named_accounts(&keys).unwrap().map(|(name, key)| (name, *key));
+        assert!(named.eq(SwapBaseInput::ACCOUNT_NAMES.into_iter().zip(0..13)));
+        assert_eq!(instruction.account(&keys, "observation_state"), Some(&12));
+        assert_eq!(instruction.account(&keys[..12], "observation_state"), None);
+        assert_eq!(instruction.account(&keys, "lp_mint"), None);
+    }
+
+    #[test]
+    fn error_codes_start_at_700() {
+        use pinocchio::program_error::ProgramError;
+
+        assert_eq!(
+            ProgramError::from(DecodeError::UnknownDiscriminator),
+            ProgramError::Custom(700)
+        );
+        assert_eq!(
+            ProgramError::from(DecodeError::NotEnoughAccounts),
+            ProgramError::Custom(702)
+        );
+    }
+}
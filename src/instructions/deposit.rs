@@ -1,13 +1,19 @@
-use core::slice::from_raw_parts;
+use core::{mem::MaybeUninit, slice::from_raw_parts};
 
 use pinocchio::{
     account_info::AccountInfo,
-    instruction::{AccountMeta, Instruction, Signer},
+    instruction::{Instruction, Signer},
     program::invoke_signed,
+    program_error::ProgramError,
     ProgramResult,
 };
 
-use crate::{write_bytes, UNINIT_BYTE};
+use crate::{error::ValidationError, write_bytes, UNINIT_BYTE};
+
+use super::checks::{
+    check_authority, check_key, check_token_programs, check_vaults, load_pool_state,
+};
+use super::encoding::{account_metas, exact_accounts, AccountRole};
 
 /// Deposits liquidity into the pool
 ///
@@ -59,63 +65,143 @@ pub struct Deposit<'a> {
     pub maximum_token_1_amount: u64,
 }
 
-impl Deposit<'_> {
-    #[inline(always)]
-    pub fn invoke(&self) -> ProgramResult {
-        self.invoke_signed(&[])
+impl<'a> Deposit<'a> {
+    /// Bind `accounts` in the documented order.
+    ///
+    /// `accounts` must hold exactly the accounts of the instruction, fewer fail with
+    /// `NotEnoughAccountKeys` and more with `InvalidArgument`.
+    pub fn from_accounts(
+        accounts: &'a [AccountInfo],
+        lp_token_amount: u64,
+        maximum_token_0_amount: u64,
+        maximum_token_1_amount: u64,
+    ) -> Result<Self, ProgramError> {
+        let [
+            owner, authority, pool_state, owner_lp_token, token_0_account, token_1_account,
+            token_0_vault, token_1_vault, token_program, token_program_2022, vault_0_mint,
+            vault_1_mint, lp_mint,
+        ] = exact_accounts(accounts)?;
+        Ok(Self {
+            owner, authority, pool_state, owner_lp_token, token_0_account, token_1_account,
+            token_0_vault, token_1_vault, token_program, token_program_2022, vault_0_mint,
+            vault_1_mint, lp_mint, lp_token_amount, maximum_token_0_amount, maximum_token_1_amount,
+        })
     }
+}
 
-    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
-        // Account metadata - 13 accounts total
-        let account_metas: [AccountMeta; 13] = [
-            AccountMeta::readonly_signer(self.owner.key()),
-            AccountMeta::readonly(self.authority.key()),
-            AccountMeta::writable(self.pool_state.key()),
-            AccountMeta::writable(self.owner_lp_token.key()),
-            AccountMeta::writable(self.token_0_account.key()),
-            AccountMeta::writable(self.token_1_account.key()),
-            AccountMeta::writable(self.token_0_vault.key()),
-            AccountMeta::writable(self.token_1_vault.key()),
-            AccountMeta::readonly(self.token_program.key()),
-            AccountMeta::readonly(self.token_program_2022.key()),
-            AccountMeta::readonly(self.vault_0_mint.key()),
-            AccountMeta::readonly(self.vault_1_mint.key()),
-            AccountMeta::writable(self.lp_mint.key()),
-        ];
+impl Deposit<'_> {
+    /// Anchor discriminator of the instruction
+    pub const DISCRIMINATOR: [u8; 8] = [242, 35, 198, 137, 82, 225, 242, 182];
 
+    /// Length of the instruction data
+    pub const DATA_LEN: usize = 32;
+
+    /// Roles of the accounts, in order
+    pub const ACCOUNTS: [AccountRole; 13] = [
+        AccountRole::READONLY_SIGNER,
+        AccountRole::READONLY,
+        AccountRole::WRITABLE,
+        AccountRole::WRITABLE,
+        AccountRole::WRITABLE,
+        AccountRole::WRITABLE,
+        AccountRole::WRITABLE,
+        AccountRole::WRITABLE,
+        AccountRole::READONLY,
+        AccountRole::READONLY,
+        AccountRole::READONLY,
+        AccountRole::READONLY,
+        AccountRole::WRITABLE,
+    ];
+
+    /// Names of the accounts, in order
+    pub const ACCOUNT_NAMES: [&'static str; 13] = [
+        "owner", "authority", "pool_state", "owner_lp_token", "token_0_account", "token_1_account",
+        "token_0_vault", "token_1_vault", "token_program", "token_program_2022", "vault_0_mint",
+        "vault_1_mint", "lp_mint",
+    ];
+
+    /// Write the instruction data, initializing every byte of `data`
+    #[inline(always)]
+    pub fn write_data(
+        data: &mut [MaybeUninit<u8>; 32],
+        lp_token_amount: u64,
+        maximum_token_0_amount: u64,
+        maximum_token_1_amount: u64,
+    ) {
         // Instruction data layout:
         // -  [0..8]: instruction discriminator (8 bytes)
         // -  [8..16]: lp_token_amount (8 bytes, u64)
         // -  [16..24]: maximum_token_0_amount (8 bytes, u64)
         // -  [24..32]: maximum_token_1_amount (8 bytes, u64)
-        let mut instruction_data = [UNINIT_BYTE; 32];
 
         // Set discriminator (8 bytes)
-        let discriminator: [u8; 8] = [242, 35, 198, 137, 82, 225, 242, 182];
-        write_bytes(&mut instruction_data[0..8], &discriminator);
-        
+        write_bytes(&mut data[0..8], &Self::DISCRIMINATOR);
+
         // Set lp_token_amount (8 bytes)
-        write_bytes(&mut instruction_data[8..16], &self.lp_token_amount.to_le_bytes());
-        
+        write_bytes(&mut data[8..16], &lp_token_amount.to_le_bytes());
+
         // Set maximum_token_0_amount (8 bytes)
-        write_bytes(&mut instruction_data[16..24], &self.maximum_token_0_amount.to_le_bytes());
-        
+        write_bytes(&mut data[16..24], &maximum_token_0_amount.to_le_bytes());
+
         // Set maximum_token_1_amount (8 bytes)
-        write_bytes(&mut instruction_data[24..32], &self.maximum_token_1_amount.to_le_bytes());
+        write_bytes(&mut data[24..32], &maximum_token_1_amount.to_le_bytes());
+    }
 
-        let instruction = Instruction {
-            program_id: &crate::ID,
-            accounts: &account_metas,
-            data: unsafe { from_raw_parts(instruction_data.as_ptr() as _, 32) },
-        };
+    /// Check the accounts against the `PoolState` before invoking.
+    ///
+    /// This is opt-in, the program performs the same checks but fails with less
+    /// descriptive errors after the CPI compute has been spent.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let pool_state = load_pool_state(self.pool_state)?;
+        check_authority(self.authority, &pool_state)?;
+        check_vaults(
+            &pool_state,
+            self.token_0_vault,
+            self.token_1_vault,
+            self.vault_0_mint,
+            self.vault_1_mint,
+        )?;
+        check_key(self.lp_mint, &pool_state.lp_mint, ValidationError::InvalidLpMint)?;
+        check_token_programs(self.token_program, self.token_program_2022)
+    }
+
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        self.with_instruction(|instruction, accounts| invoke_signed(instruction, accounts, signers))
+    }
 
+    /// Build the instruction and call `f` with it and the account infos, in instruction order.
+    ///
+    /// `invoke_signed` passes them to `pinocchio::program::invoke_signed`, other executors such
+    /// as `test_utils::svm::Svm` can run the same instruction.
+    #[inline(always)]
+    pub fn with_instruction<R>(&self, f: impl FnOnce(&Instruction, &[&AccountInfo; 13]) -> R) -> R {
         let accounts = [
             self.owner, self.authority, self.pool_state, self.owner_lp_token,
             self.token_0_account, self.token_1_account, self.token_0_vault, self.token_1_vault,
             self.token_program, self.token_program_2022, self.vault_0_mint, self.vault_1_mint,
             self.lp_mint,
         ];
+        let account_metas = account_metas(&Self::ACCOUNTS, &accounts);
+
+        let mut instruction_data = [UNINIT_BYTE; 32];
+        Self::write_data(
+            &mut instruction_data,
+            self.lp_token_amount,
+            self.maximum_token_0_amount,
+            self.maximum_token_1_amount,
+        );
+
+        let instruction = Instruction {
+            program_id: crate::program_id(),
+            accounts: &account_metas,
+            data: unsafe { from_raw_parts(instruction_data.as_ptr() as _, 32) },
+        };
 
-        invoke_signed(&instruction, &accounts, signers)
+        f(&instruction, &accounts)
     }
 } 
\ No newline at end of file
@@ -0,0 +1,51 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::AccountMeta,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+/// Signer and writable flags of an instruction account
+///
+/// Each instruction declares the roles of its accounts once, in `ACCOUNTS`, for both the
+/// CPI structs and the off-chain instruction builders.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AccountRole {
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+impl AccountRole {
+    pub const READONLY: AccountRole = AccountRole { is_signer: false, is_writable: false };
+    pub const WRITABLE: AccountRole = AccountRole { is_signer: false, is_writable: true };
+    pub const READONLY_SIGNER: AccountRole = AccountRole { is_signer: true, is_writable: false };
+    pub const WRITABLE_SIGNER: AccountRole = AccountRole { is_signer: true, is_writable: true };
+
+    /// Return the `AccountMeta` of `pubkey` with this role
+    #[inline(always)]
+    pub fn meta(self, pubkey: &Pubkey) -> AccountMeta<'_> {
+        AccountMeta::new(pubkey, self.is_writable, self.is_signer)
+    }
+}
+
+/// Return the `AccountMeta`s of `accounts`, with the roles declared by the instruction
+#[inline(always)]
+pub(crate) fn account_metas<'a, const N: usize>(
+    roles: &[AccountRole; N],
+    accounts: &[&'a AccountInfo; N],
+) -> [AccountMeta<'a>; N] {
+    core::array::from_fn(|i| roles[i].meta(accounts[i].key()))
+}
+
+/// Return `accounts` as the `N` accounts of an instruction, failing with
+/// `NotEnoughAccountKeys` when there are fewer and `InvalidArgument` when there are more
+#[inline(always)]
+pub(crate) fn exact_accounts<const N: usize>(
+    accounts: &[AccountInfo],
+) -> Result<&[AccountInfo; N], ProgramError> {
+    match accounts.try_into() {
+        Ok(accounts) => Ok(accounts),
+        Err(_) if accounts.len() < N => Err(ProgramError::NotEnoughAccountKeys),
+        Err(_) => Err(ProgramError::InvalidArgument),
+    }
+}
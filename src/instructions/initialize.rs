@@ -1,13 +1,20 @@
-use core::slice::from_raw_parts;
+use core::{mem::MaybeUninit, slice::from_raw_parts};
 
 use pinocchio::{
     account_info::AccountInfo,
-    instruction::{AccountMeta, Instruction, Signer},
+    instruction::{Instruction, Signer},
     program::invoke_signed,
+    program_error::ProgramError,
     ProgramResult,
 };
 
-use crate::{write_bytes, UNINIT_BYTE};
+use crate::{error::ValidationError, token_program, write_bytes, UNINIT_BYTE};
+
+use super::checks::{
+    check_associated_token_program, check_key, check_mint_program, check_rent_sysvar,
+    check_system_program, load_amm_config,
+};
+use super::encoding::{account_metas, exact_accounts, AccountRole};
 
 /// Creates a pool for the given token pair and the initial price
 ///
@@ -80,63 +87,134 @@ pub struct Initialize<'a> {
     pub open_time: u64,
 }
 
-impl Initialize<'_> {
-    #[inline(always)]
-    pub fn invoke(&self) -> ProgramResult {
-        self.invoke_signed(&[])
+impl<'a> Initialize<'a> {
+    /// Bind `accounts` in the documented order.
+    ///
+    /// `accounts` must hold exactly the accounts of the instruction, fewer fail with
+    /// `NotEnoughAccountKeys` and more with `InvalidArgument`.
+    pub fn from_accounts(
+        accounts: &'a [AccountInfo],
+        init_amount_0: u64,
+        init_amount_1: u64,
+        open_time: u64,
+    ) -> Result<Self, ProgramError> {
+        let [
+            creator, amm_config, authority, pool_state, token_0_mint, token_1_mint, lp_mint,
+            creator_token_0, creator_token_1, creator_lp_token, token_0_vault, token_1_vault,
+            create_pool_fee, observation_state, token_program, token_0_program, token_1_program,
+            associated_token_program, system_program, rent,
+        ] = exact_accounts(accounts)?;
+        Ok(Self {
+            creator, amm_config, authority, pool_state, token_0_mint, token_1_mint, lp_mint,
+            creator_token_0, creator_token_1, creator_lp_token, token_0_vault, token_1_vault,
+            create_pool_fee, observation_state, token_program, token_0_program, token_1_program,
+            associated_token_program, system_program, rent, init_amount_0, init_amount_1,
+            open_time,
+        })
     }
+}
 
-    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
-        // Account metadata - 20 accounts total
-        let account_metas: [AccountMeta; 20] = [
-            AccountMeta::writable_signer(self.creator.key()),
-            AccountMeta::readonly(self.amm_config.key()),
-            AccountMeta::readonly(self.authority.key()),
-            AccountMeta::writable(self.pool_state.key()),
-            AccountMeta::readonly(self.token_0_mint.key()),
-            AccountMeta::readonly(self.token_1_mint.key()),
-            AccountMeta::writable(self.lp_mint.key()),
-            AccountMeta::writable(self.creator_token_0.key()),
-            AccountMeta::writable(self.creator_token_1.key()),
-            AccountMeta::writable(self.creator_lp_token.key()),
-            AccountMeta::writable(self.token_0_vault.key()),
-            AccountMeta::writable(self.token_1_vault.key()),
-            AccountMeta::writable(self.create_pool_fee.key()),
-            AccountMeta::writable(self.observation_state.key()),
-            AccountMeta::readonly(self.token_program.key()),
-            AccountMeta::readonly(self.token_0_program.key()),
-            AccountMeta::readonly(self.token_1_program.key()),
-            AccountMeta::readonly(self.associated_token_program.key()),
-            AccountMeta::readonly(self.system_program.key()),
-            AccountMeta::readonly(self.rent.key()),
-        ];
+impl Initialize<'_> {
+    /// Anchor discriminator of the instruction
+    pub const DISCRIMINATOR: [u8; 8] = [175, 175, 109, 31, 13, 152, 155, 237];
 
+    /// Length of the instruction data
+    pub const DATA_LEN: usize = 32;
+
+    /// Roles of the accounts, in order
+    pub const ACCOUNTS: [AccountRole; 20] = [
+        AccountRole::WRITABLE_SIGNER,
+        AccountRole::READONLY,
+        AccountRole::READONLY,
+        AccountRole::WRITABLE,
+        AccountRole::READONLY,
+        AccountRole::READONLY,
+        AccountRole::WRITABLE,
+        AccountRole::WRITABLE,
+        AccountRole::WRITABLE,
+        AccountRole::WRITABLE,
+        AccountRole::WRITABLE,
+        AccountRole::WRITABLE,
+        AccountRole::WRITABLE,
+        AccountRole::WRITABLE,
+        AccountRole::READONLY,
+        AccountRole::READONLY,
+        AccountRole::READONLY,
+        AccountRole::READONLY,
+        AccountRole::READONLY,
+        AccountRole::READONLY,
+    ];
+
+    /// Names of the accounts, in order
+    pub const ACCOUNT_NAMES: [&'static str; 20] = [
+        "creator", "amm_config", "authority", "pool_state", "token_0_mint", "token_1_mint",
+        "lp_mint", "creator_token_0", "creator_token_1", "creator_lp_token", "token_0_vault",
+        "token_1_vault", "create_pool_fee", "observation_state", "token_program", "token_0_program",
+        "token_1_program", "associated_token_program", "system_program", "rent",
+    ];
+
+    /// Write the instruction data, initializing every byte of `data`
+    #[inline(always)]
+    pub fn write_data(
+        data: &mut [MaybeUninit<u8>; 32],
+        init_amount_0: u64,
+        init_amount_1: u64,
+        open_time: u64,
+    ) {
         // Instruction data layout:
         // -  [0..8]: instruction discriminator (8 bytes)
         // -  [8..16]: init_amount_0 (8 bytes, u64)
         // -  [16..24]: init_amount_1 (8 bytes, u64)
         // -  [24..32]: open_time (8 bytes, u64)
-        let mut instruction_data = [UNINIT_BYTE; 32];
 
         // Set discriminator (8 bytes)
-        let discriminator: [u8; 8] = [175, 175, 109, 31, 13, 152, 155, 237];
-        write_bytes(&mut instruction_data[0..8], &discriminator);
-        
+        write_bytes(&mut data[0..8], &Self::DISCRIMINATOR);
+
         // Set init_amount_0 (8 bytes)
-        write_bytes(&mut instruction_data[8..16], &self.init_amount_0.to_le_bytes());
-        
+        write_bytes(&mut data[8..16], &init_amount_0.to_le_bytes());
+
         // Set init_amount_1 (8 bytes)
-        write_bytes(&mut instruction_data[16..24], &self.init_amount_1.to_le_bytes());
-        
+        write_bytes(&mut data[16..24], &init_amount_1.to_le_bytes());
+
         // Set open_time (8 bytes)
-        write_bytes(&mut instruction_data[24..32], &self.open_time.to_le_bytes());
+        write_bytes(&mut data[24..32], &open_time.to_le_bytes());
+    }
 
-        let instruction = Instruction {
-            program_id: &crate::ID,
-            accounts: &account_metas,
-            data: unsafe { from_raw_parts(instruction_data.as_ptr() as _, 32) },
-        };
+    /// Check the accounts against the `AmmConfig` before invoking.
+    ///
+    /// This is opt-in, the program performs the same checks but fails with less
+    /// descriptive errors after the CPI compute has been spent.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let amm_config = load_amm_config(self.amm_config)?;
+        if amm_config.disable_create_pool {
+            return Err(ValidationError::PoolCreationDisabled);
+        }
+        if self.token_0_mint.key() >= self.token_1_mint.key() {
+            return Err(ValidationError::InvalidMintOrder);
+        }
+        check_key(self.token_program, &token_program::ID, ValidationError::InvalidTokenProgram)?;
+        check_mint_program(self.token_0_mint, self.token_0_program, ValidationError::InvalidToken0Program)?;
+        check_mint_program(self.token_1_mint, self.token_1_program, ValidationError::InvalidToken1Program)?;
+        check_associated_token_program(self.associated_token_program)?;
+        check_system_program(self.system_program)?;
+        check_rent_sysvar(self.rent)
+    }
+
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        self.with_instruction(|instruction, accounts| invoke_signed(instruction, accounts, signers))
+    }
 
+    /// Build the instruction and call `f` with it and the account infos, in instruction order.
+    ///
+    /// `invoke_signed` passes them to `pinocchio::program::invoke_signed`, other executors such
+    /// as `test_utils::svm::Svm` can run the same instruction.
+    #[inline(always)]
+    pub fn with_instruction<R>(&self, f: impl FnOnce(&Instruction, &[&AccountInfo; 20]) -> R) -> R {
         let accounts = [
             self.creator, self.amm_config, self.authority, self.pool_state,
             self.token_0_mint, self.token_1_mint, self.lp_mint, self.creator_token_0,
@@ -144,7 +222,22 @@ impl Initialize<'_> {
             self.create_pool_fee, self.observation_state, self.token_program, self.token_0_program,
             self.token_1_program, self.associated_token_program, self.system_program, self.rent,
         ];
+        let account_metas = account_metas(&Self::ACCOUNTS, &accounts);
+
+        let mut instruction_data = [UNINIT_BYTE; 32];
+        Self::write_data(
+            &mut instruction_data,
+            self.init_amount_0,
+            self.init_amount_1,
+            self.open_time,
+        );
+
+        let instruction = Instruction {
+            program_id: crate::program_id(),
+            accounts: &account_metas,
+            data: unsafe { from_raw_parts(instruction_data.as_ptr() as _, 32) },
+        };
 
-        invoke_signed(&instruction, &accounts, signers)
+        f(&instruction, &accounts)
     }
 } 
\ No newline at end of file
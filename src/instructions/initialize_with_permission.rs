@@ -0,0 +1,266 @@
+use core::{mem::MaybeUninit, slice::from_raw_parts};
+
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Instruction, Signer},
+    program::invoke_signed,
+    program_error::ProgramError,
+    ProgramResult,
+};
+
+use crate::{
+    error::ValidationError,
+    states::{CreatorFeeOn, Permission},
+    token_program, write_bytes, UNINIT_BYTE,
+};
+
+use super::checks::{
+    check_associated_token_program, check_key, check_mint_program, check_system_program,
+    load_amm_config,
+};
+use super::encoding::{account_metas, exact_accounts, AccountRole};
+
+/// Creates a pool with permission, allowing the pool creator to collect creator fees
+///
+/// Only supported by CPMM releases with creator fees.
+///
+/// ### Accounts:
+///   0. `[WRITE, SIGNER]` payer - Address paying to create the pool, must hold a permission
+///   1. `[]` creator - Pool creator, receives the creator fees
+///   2. `[]` amm_config - Which config the pool belongs to
+///   3. `[]` authority - Pool vault and lp mint authority (PDA)
+///   4. `[WRITE]` pool_state - Initialize an account to store the pool state
+///   5. `[]` token_0_mint - Token_0 mint, key must be smaller than token_1 mint
+///   6. `[]` token_1_mint - Token_1 mint, key must be greater than token_0 mint
+///   7. `[WRITE]` lp_mint - Pool lp mint (PDA)
+///   8. `[WRITE]` payer_token_0 - Payer token0 account
+///   9. `[WRITE]` payer_token_1 - Payer token1 account
+///   10. `[WRITE]` payer_lp_token - Payer lp token account
+///   11. `[WRITE]` token_0_vault - Token_0 vault for the pool (PDA)
+///   12. `[WRITE]` token_1_vault - Token_1 vault for the pool (PDA)
+///   13. `[WRITE]` create_pool_fee - Create pool fee account
+///   14. `[WRITE]` observation_state - Account to store oracle observations (PDA)
+///   15. `[]` permission - Permission account of the payer (PDA)
+///   16. `[]` token_program - Program to create mint account and mint tokens
+///   17. `[]` token_0_program - Spl token program or token program 2022
+///   18. `[]` token_1_program - Spl token program or token program 2022
+///   19. `[]` associated_token_program - Program to create an ATA for receiving position NFT
+///   20. `[]` system_program - To create a new program account
+pub struct InitializeWithPermission<'a> {
+    /// Address paying to create the pool. Must hold a permission
+    pub payer: &'a AccountInfo,
+    /// CHECK: pool creator
+    pub creator: &'a AccountInfo,
+    /// Which config the pool belongs to.
+    pub amm_config: &'a AccountInfo,
+    /// CHECK: pool vault and lp mint authority
+    pub authority: &'a AccountInfo,
+    /// CHECK: Initialize an account to store the pool state
+    pub pool_state: &'a AccountInfo,
+    /// Token_0 mint, the key must smaller then token_1 mint.
+    pub token_0_mint: &'a AccountInfo,
+    /// Token_1 mint, the key must grater then token_0 mint.
+    pub token_1_mint: &'a AccountInfo,
+    /// pool lp mint
+    pub lp_mint: &'a AccountInfo,
+    /// payer token0 account
+    pub payer_token_0: &'a AccountInfo,
+    /// payer token1 account
+    pub payer_token_1: &'a AccountInfo,
+    /// payer lp token account
+    pub payer_lp_token: &'a AccountInfo,
+    /// Token_0 vault for the pool
+    pub token_0_vault: &'a AccountInfo,
+    /// Token_1 vault for the pool
+    pub token_1_vault: &'a AccountInfo,
+    /// create pool fee account
+    pub create_pool_fee: &'a AccountInfo,
+    /// an account to store oracle observations
+    pub observation_state: &'a AccountInfo,
+    /// Permission account of the payer
+    pub permission: &'a AccountInfo,
+    /// Program to create mint account and mint tokens
+    pub token_program: &'a AccountInfo,
+    /// Spl token program or token program 2022
+    pub token_0_program: &'a AccountInfo,
+    /// Spl token program or token program 2022
+    pub token_1_program: &'a AccountInfo,
+    /// Program to create an ATA for receiving position NFT
+    pub associated_token_program: &'a AccountInfo,
+    /// To create a new program account
+    pub system_program: &'a AccountInfo,
+
+    /// Instruction parameters
+    pub init_amount_0: u64,
+    pub init_amount_1: u64,
+    pub open_time: u64,
+    pub creator_fee_on: CreatorFeeOn,
+}
+
+impl<'a> InitializeWithPermission<'a> {
+    /// Bind `accounts` in the documented order.
+    ///
+    /// `accounts` must hold exactly the accounts of the instruction, fewer fail with
+    /// `NotEnoughAccountKeys` and more with `InvalidArgument`.
+    pub fn from_accounts(
+        accounts: &'a [AccountInfo],
+        init_amount_0: u64,
+        init_amount_1: u64,
+        open_time: u64,
+        creator_fee_on: CreatorFeeOn,
+    ) -> Result<Self, ProgramError> {
+        let [
+            payer, creator, amm_config, authority, pool_state, token_0_mint, token_1_mint, lp_mint,
+            payer_token_0, payer_token_1, payer_lp_token, token_0_vault, token_1_vault,
+            create_pool_fee, observation_state, permission, token_program, token_0_program,
+            token_1_program, associated_token_program, system_program,
+        ] = exact_accounts(accounts)?;
+        Ok(Self {
+            payer, creator, amm_config, authority, pool_state, token_0_mint, token_1_mint, lp_mint,
+            payer_token_0, payer_token_1, payer_lp_token, token_0_vault, token_1_vault,
+            create_pool_fee, observation_state, permission, token_program, token_0_program,
+            token_1_program, associated_token_program, system_program, init_amount_0,
+            init_amount_1, open_time, creator_fee_on,
+        })
+    }
+}
+
+impl InitializeWithPermission<'_> {
+    /// Anchor discriminator of the instruction
+    pub const DISCRIMINATOR: [u8; 8] = [63, 55, 254, 65, 49, 178, 89, 121];
+
+    /// Length of the instruction data
+    pub const DATA_LEN: usize = 33;
+
+    /// Roles of the accounts, in order
+    pub const ACCOUNTS: [AccountRole; 21] = [
+        AccountRole::WRITABLE_SIGNER,
+        AccountRole::READONLY,
+        AccountRole::READONLY,
+        AccountRole::READONLY,
+        AccountRole::WRITABLE,
+        AccountRole::READONLY,
+        AccountRole::READONLY,
+        AccountRole::WRITABLE,
+        AccountRole::WRITABLE,
+        AccountRole::WRITABLE,
+        AccountRole::WRITABLE,
+        AccountRole::WRITABLE,
+        AccountRole::WRITABLE,
+        AccountRole::WRITABLE,
+        AccountRole::WRITABLE,
+        AccountRole::READONLY,
+        AccountRole::READONLY,
+        AccountRole::READONLY,
+        AccountRole::READONLY,
+        AccountRole::READONLY,
+        AccountRole::READONLY,
+    ];
+
+    /// Names of the accounts, in order
+    pub const ACCOUNT_NAMES: [&'static str; 21] = [
+        "payer", "creator", "amm_config", "authority", "pool_state", "token_0_mint", "token_1_mint",
+        "lp_mint", "payer_token_0", "payer_token_1", "payer_lp_token", "token_0_vault",
+        "token_1_vault", "create_pool_fee", "observation_state", "permission", "token_program",
+        "token_0_program", "token_1_program", "associated_token_program", "system_program",
+    ];
+
+    /// Write the instruction data, initializing every byte of `data`
+    #[inline(always)]
+    pub fn write_data(
+        data: &mut [MaybeUninit<u8>; 33],
+        init_amount_0: u64,
+        init_amount_1: u64,
+        open_time: u64,
+        creator_fee_on: CreatorFeeOn,
+    ) {
+        // Instruction data layout:
+        // -  [0..8]: instruction discriminator (8 bytes)
+        // -  [8..16]: init_amount_0 (8 bytes, u64)
+        // -  [16..24]: init_amount_1 (8 bytes, u64)
+        // -  [24..32]: open_time (8 bytes, u64)
+        // -  [32]: creator_fee_on (1 byte, u8)
+
+        // Set discriminator (8 bytes)
+        write_bytes(&mut data[0..8], &Self::DISCRIMINATOR);
+
+        // Set init_amount_0 (8 bytes)
+        write_bytes(&mut data[8..16], &init_amount_0.to_le_bytes());
+
+        // Set init_amount_1 (8 bytes)
+        write_bytes(&mut data[16..24], &init_amount_1.to_le_bytes());
+
+        // Set open_time (8 bytes)
+        write_bytes(&mut data[24..32], &open_time.to_le_bytes());
+
+        // Set creator_fee_on (1 byte)
+        write_bytes(&mut data[32..33], &[creator_fee_on as u8]);
+    }
+
+    /// Check the accounts against the `AmmConfig` and `Permission` before invoking.
+    ///
+    /// This is opt-in, the program performs the same checks but fails with less
+    /// descriptive errors after the CPI compute has been spent.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let amm_config = load_amm_config(self.amm_config)?;
+        if amm_config.disable_create_pool {
+            return Err(ValidationError::PoolCreationDisabled);
+        }
+        if self.token_0_mint.key() >= self.token_1_mint.key() {
+            return Err(ValidationError::InvalidMintOrder);
+        }
+        let permission = Permission::from_account_info(self.permission)
+            .map_err(|_| ValidationError::InvalidPermission)?;
+        if &permission.authority != self.payer.key() {
+            return Err(ValidationError::InvalidPermission);
+        }
+        check_key(self.token_program, &token_program::ID, ValidationError::InvalidTokenProgram)?;
+        check_mint_program(self.token_0_mint, self.token_0_program, ValidationError::InvalidToken0Program)?;
+        check_mint_program(self.token_1_mint, self.token_1_program, ValidationError::InvalidToken1Program)?;
+        check_associated_token_program(self.associated_token_program)?;
+        check_system_program(self.system_program)
+    }
+
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        self.with_instruction(|instruction, accounts| invoke_signed(instruction, accounts, signers))
+    }
+
+    /// Build the instruction and call `f` with it and the account infos, in instruction order.
+    ///
+    /// `invoke_signed` passes them to `pinocchio::program::invoke_signed`, other executors such
+    /// as `test_utils::svm::Svm` can run the same instruction.
+    #[inline(always)]
+    pub fn with_instruction<R>(&self, f: impl FnOnce(&Instruction, &[&AccountInfo; 21]) -> R) -> R {
+        let accounts = [
+            self.payer, self.creator, self.amm_config, self.authority,
+            self.pool_state, self.token_0_mint, self.token_1_mint, self.lp_mint,
+            self.payer_token_0, self.payer_token_1, self.payer_lp_token, self.token_0_vault,
+            self.token_1_vault, self.create_pool_fee, self.observation_state, self.permission,
+            self.token_program, self.token_0_program, self.token_1_program, self.associated_token_program,
+            self.system_program,
+        ];
+        let account_metas = account_metas(&Self::ACCOUNTS, &accounts);
+
+        let mut instruction_data = [UNINIT_BYTE; 33];
+        Self::write_data(
+            &mut instruction_data,
+            self.init_amount_0,
+            self.init_amount_1,
+            self.open_time,
+            self.creator_fee_on,
+        );
+
+        let instruction = Instruction {
+            program_id: crate::program_id(),
+            accounts: &account_metas,
+            data: unsafe { from_raw_parts(instruction_data.as_ptr() as _, 33) },
+        };
+
+        f(&instruction, &accounts)
+    }
+}
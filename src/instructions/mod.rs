@@ -1,11 +1,36 @@
+mod checks;
+pub mod encoding;
 pub mod initialize;
 pub mod deposit;
 pub mod withdraw;
 pub mod swap_base_input;
 pub mod swap_base_output;
+pub mod create_amm_config;
+pub mod update_amm_config;
+pub mod update_pool_status;
+pub mod collect_protocol_fee;
+pub mod collect_fund_fee;
+pub mod initialize_with_permission;
+pub mod collect_creator_fee;
+pub mod create_permission_pda;
+pub mod close_permission_pda;
+pub mod slippage;
+pub mod decode;
 
 pub use initialize::*;
 pub use deposit::*;
 pub use withdraw::*;
 pub use swap_base_input::*;
-pub use swap_base_output::*; 
\ No newline at end of file
+pub use swap_base_output::*;
+pub use create_amm_config::*;
+pub use update_amm_config::*;
+pub use update_pool_status::*;
+pub use collect_protocol_fee::*;
+pub use collect_fund_fee::*;
+pub use initialize_with_permission::*;
+pub use collect_creator_fee::*;
+pub use create_permission_pda::*;
+pub use close_permission_pda::*;
+pub use encoding::AccountRole;
+pub use slippage::*;
+pub use decode::*; 
\ No newline at end of file
@@ -0,0 +1,255 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError};
+
+use crate::{
+    curve::{self, SwapQuote, TradeDirection},
+    error::ValidationError,
+    states::{AmmConfig, PoolState},
+};
+
+use super::checks::{check_authority, check_key, load_pool_state};
+use super::encoding::exact_accounts;
+
+/// Denominator of slippage tolerances, expressed in basis points
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// The accounts of a `SwapBaseInput` or `SwapBaseOutput`
+///
+/// ### Accounts:
+///   0. `[SIGNER]` payer - The user performing the swap
+///   1. `[]` authority - Pool vault and lp mint authority (PDA)
+///   2. `[]` amm_config - The factory state to read protocol fees
+///   3. `[WRITE]` pool_state - The program account of the pool in which the swap will be performed
+///   4. `[WRITE]` input_token_account - The user token account for input token
+///   5. `[WRITE]` output_token_account - The user token account for output token
+///   6. `[WRITE]` input_vault - The vault token account for input token
+///   7. `[WRITE]` output_vault - The vault token account for output token
+///   8. `[]` input_token_program - SPL program for input token transfers
+///   9. `[]` output_token_program - SPL program for output token transfers
+///   10. `[]` input_token_mint - The mint of input token
+///   11. `[]` output_token_mint - The mint of output token
+///   12. `[WRITE]` observation_state - The program account for the most recent oracle observation
+#[derive(Clone, Copy)]
+pub struct SwapAccounts<'a> {
+    /// The user performing the swap
+    pub payer: &'a AccountInfo,
+    /// CHECK: pool vault and lp mint authority
+    pub authority: &'a AccountInfo,
+    /// The factory state to read protocol fees
+    pub amm_config: &'a AccountInfo,
+    /// The program account of the pool in which the swap will be performed
+    pub pool_state: &'a AccountInfo,
+    /// The user token account for input token
+    pub input_token_account: &'a AccountInfo,
+    /// The user token account for output token
+    pub output_token_account: &'a AccountInfo,
+    /// The vault token account for input token
+    pub input_vault: &'a AccountInfo,
+    /// The vault token account for output token
+    pub output_vault: &'a AccountInfo,
+    /// SPL program for input token transfers
+    pub input_token_program: &'a AccountInfo,
+    /// SPL program for output token transfers
+    pub output_token_program: &'a AccountInfo,
+    /// The mint of input token
+    pub input_token_mint: &'a AccountInfo,
+    /// The mint of output token
+    pub output_token_mint: &'a AccountInfo,
+    /// The program account for the most recent oracle observation
+    pub observation_state: &'a AccountInfo,
+}
+
+impl<'a> SwapAccounts<'a> {
+    /// Bind `accounts` in the order of the swap instructions.
+    ///
+    /// `accounts` must hold exactly the accounts of the swap, fewer fail with
+    /// `NotEnoughAccountKeys` and more with `InvalidArgument`.
+    pub fn from_accounts(accounts: &'a [AccountInfo]) -> Result<Self, ProgramError> {
+        let [
+            payer, authority, amm_config, pool_state, input_token_account, output_token_account,
+            input_vault, output_vault, input_token_program, output_token_program, input_token_mint,
+            output_token_mint, observation_state,
+        ] = exact_accounts(accounts)?;
+        Ok(Self {
+            payer, authority, amm_config, pool_state, input_token_account, output_token_account,
+            input_vault, output_vault, input_token_program, output_token_program, input_token_mint,
+            output_token_mint, observation_state,
+        })
+    }
+}
+
+impl SwapAccounts<'_> {
+    /// Check the accounts against the `PoolState` before invoking a swap.
+    ///
+    /// This is opt-in, the program performs the same checks but fails with less
+    /// descriptive errors after the CPI compute has been spent.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let pool_state = load_pool_state(self.pool_state)?;
+        check_key(self.amm_config, &pool_state.amm_config, ValidationError::InvalidAmmConfig)?;
+        check_authority(self.authority, &pool_state)?;
+        check_key(
+            self.observation_state,
+            &pool_state.observation_key,
+            ValidationError::InvalidObservationState,
+        )?;
+
+        let (input_mint, output_mint, input_program, output_program) = if pool_state.token_0_vault
+            == *self.input_vault.key()
+            && pool_state.token_1_vault == *self.output_vault.key()
+        {
+            (
+                pool_state.token_0_mint,
+                pool_state.token_1_mint,
+                pool_state.token_0_program,
+                pool_state.token_1_program,
+            )
+        } else if pool_state.token_1_vault == *self.input_vault.key()
+            && pool_state.token_0_vault == *self.output_vault.key()
+        {
+            (
+                pool_state.token_1_mint,
+                pool_state.token_0_mint,
+                pool_state.token_1_program,
+                pool_state.token_0_program,
+            )
+        } else {
+            return Err(ValidationError::InvalidSwapVaults);
+        };
+        check_key(self.input_token_mint, &input_mint, ValidationError::InvalidSwapMint)?;
+        check_key(self.output_token_mint, &output_mint, ValidationError::InvalidSwapMint)?;
+        check_key(self.input_token_program, &input_program, ValidationError::InvalidSwapTokenProgram)?;
+        check_key(self.output_token_program, &output_program, ValidationError::InvalidSwapTokenProgram)
+    }
+
+    /// Quote a `SwapBaseInput` of `amount_in` from the current pool, config and vault accounts.
+    ///
+    /// Token-2022 transfer fees are not taken into account, so the quote of a swap involving
+    /// a mint with a transfer fee is optimistic and the swap fails on slippage rather than
+    /// accepting a worse price.
+    pub fn quote_base_input(&self, amount_in: u64) -> Result<SwapQuote, ProgramError> {
+        self.quote(|pool_state, amm_config, direction, vault_0, vault_1| {
+            curve::swap_base_input_quote(pool_state, amm_config, direction, vault_0, vault_1, amount_in)
+        })
+    }
+
+    /// Quote a `SwapBaseOutput` of `amount_out` from the current pool, config and vault accounts.
+    ///
+    /// Token-2022 transfer fees are not taken into account, see `quote_base_input`.
+    pub fn quote_base_output(&self, amount_out: u64) -> Result<SwapQuote, ProgramError> {
+        self.quote(|pool_state, amm_config, direction, vault_0, vault_1| {
+            curve::swap_base_output_quote(pool_state, amm_config, direction, vault_0, vault_1, amount_out)
+        })
+    }
+
+    fn quote(
+        &self,
+        quote: impl FnOnce(&PoolState, &AmmConfig, TradeDirection, u64, u64) -> Option<SwapQuote>,
+    ) -> Result<SwapQuote, ProgramError> {
+        let pool_state = PoolState::from_account_info(self.pool_state)?;
+        if pool_state.amm_config != *self.amm_config.key() {
+            return Err(ValidationError::InvalidAmmConfig.into());
+        }
+        let amm_config = AmmConfig::from_account_info(self.amm_config)?;
+
+        let direction = if pool_state.token_0_vault == *self.input_vault.key()
+            && pool_state.token_1_vault == *self.output_vault.key()
+        {
+            TradeDirection::ZeroForOne
+        } else if pool_state.token_1_vault == *self.input_vault.key()
+            && pool_state.token_0_vault == *self.output_vault.key()
+        {
+            TradeDirection::OneForZero
+        } else {
+            return Err(ValidationError::InvalidSwapVaults.into());
+        };
+        let (vault_0, vault_1) = match direction {
+            TradeDirection::ZeroForOne => (self.input_vault, self.output_vault),
+            TradeDirection::OneForZero => (self.output_vault, self.input_vault),
+        };
+
+        quote(
+            &pool_state,
+            &amm_config,
+            direction,
+            token_account_amount(vault_0)?,
+            token_account_amount(vault_1)?,
+        )
+        .ok_or(ProgramError::ArithmeticOverflow)
+    }
+}
+
+/// Read the amount of an SPL token or Token-2022 account
+#[inline]
+fn token_account_amount(account_info: &AccountInfo) -> Result<u64, ProgramError> {
+    let data = account_info.try_borrow_data()?;
+    if data.len() < 72 {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let mut amount = [0u8; 8];
+    amount.copy_from_slice(&data[64..72]);
+    Ok(u64::from_le_bytes(amount))
+}
+
+/// Lower `amount` by `slippage_bps` basis points, rounded down
+#[inline]
+pub fn minimum_amount_out(amount: u64, slippage_bps: u16) -> Result<u64, ProgramError> {
+    let slippage_bps = u64::from(slippage_bps);
+    if slippage_bps > BPS_DENOMINATOR {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let amount = u128::from(amount) * u128::from(BPS_DENOMINATOR - slippage_bps)
+        / u128::from(BPS_DENOMINATOR);
+    Ok(amount as u64)
+}
+
+/// Raise `amount` by `slippage_bps` basis points, rounded up
+#[inline]
+pub fn maximum_amount_in(amount: u64, slippage_bps: u16) -> Result<u64, ProgramError> {
+    let slippage_bps = u64::from(slippage_bps);
+    if slippage_bps > BPS_DENOMINATOR {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let amount = (u128::from(amount) * u128::from(BPS_DENOMINATOR + slippage_bps))
+        .div_ceil(u128::from(BPS_DENOMINATOR));
+    u64::try_from(amount).map_err(|_| ProgramError::ArithmeticOverflow)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn minimum_amount_out_rounds_down() {
+        // 19_653.24
+        assert_eq!(minimum_amount_out(19_752, 50), Ok(19_653));
+        // 0.9999
+        assert_eq!(minimum_amount_out(1, 1), Ok(0));
+        assert_eq!(minimum_amount_out(19_752, 0), Ok(19_752));
+        assert_eq!(minimum_amount_out(19_752, 10_000), Ok(0));
+        assert_eq!(minimum_amount_out(u64::MAX, 1), Ok(18_444_899_399_302_180_659));
+    }
+
+    #[test]
+    fn maximum_amount_in_rounds_up() {
+        // 20_355.27
+        assert_eq!(maximum_amount_in(20_254, 50), Ok(20_356));
+        // 1.0001
+        assert_eq!(maximum_amount_in(1, 1), Ok(2));
+        assert_eq!(maximum_amount_in(20_254, 0), Ok(20_254));
+        assert_eq!(maximum_amount_in(20_254, 10_000), Ok(40_508));
+        assert_eq!(maximum_amount_in(u64::MAX / 2, 10_000), Ok(u64::MAX - 1));
+    }
+
+    #[test]
+    fn maximum_amount_in_overflowing_u64() {
+        assert_eq!(maximum_amount_in(u64::MAX / 2 + 1, 10_000), Err(ProgramError::ArithmeticOverflow));
+        assert_eq!(maximum_amount_in(u64::MAX, 1), Err(ProgramError::ArithmeticOverflow));
+    }
+
+    #[test]
+    fn slippage_over_bps_denominator() {
+        assert_eq!(minimum_amount_out(19_752, 10_001), Err(ProgramError::InvalidArgument));
+        assert_eq!(maximum_amount_in(20_254, 10_001), Err(ProgramError::InvalidArgument));
+        assert_eq!(minimum_amount_out(0, u16::MAX), Err(ProgramError::InvalidArgument));
+        assert_eq!(maximum_amount_in(0, u16::MAX), Err(ProgramError::InvalidArgument));
+    }
+}
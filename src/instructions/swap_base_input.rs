@@ -1,13 +1,17 @@
-use core::slice::from_raw_parts;
+use core::{mem::MaybeUninit, slice::from_raw_parts};
 
 use pinocchio::{
     account_info::AccountInfo,
-    instruction::{AccountMeta, Instruction, Signer},
+    instruction::{Instruction, Signer},
     program::invoke_signed,
+    program_error::ProgramError,
     ProgramResult,
 };
 
-use crate::{write_bytes, UNINIT_BYTE};
+use crate::{error::ValidationError, write_bytes, UNINIT_BYTE};
+
+use super::encoding::{account_metas, exact_accounts, AccountRole};
+use super::slippage::{minimum_amount_out, SwapAccounts};
 
 /// Swap the tokens in the pool base input amount
 ///
@@ -58,58 +62,159 @@ pub struct SwapBaseInput<'a> {
     pub minimum_amount_out: u64,
 }
 
-impl SwapBaseInput<'_> {
-    #[inline(always)]
-    pub fn invoke(&self) -> ProgramResult {
-        self.invoke_signed(&[])
+impl<'a> SwapBaseInput<'a> {
+    /// Bind `accounts` in the documented order.
+    ///
+    /// `accounts` must hold exactly the accounts of the instruction, fewer fail with
+    /// `NotEnoughAccountKeys` and more with `InvalidArgument`.
+    pub fn from_accounts(
+        accounts: &'a [AccountInfo],
+        amount_in: u64,
+        minimum_amount_out: u64,
+    ) -> Result<Self, ProgramError> {
+        let [
+            payer, authority, amm_config, pool_state, input_token_account, output_token_account,
+            input_vault, output_vault, input_token_program, output_token_program, input_token_mint,
+            output_token_mint, observation_state,
+        ] = exact_accounts(accounts)?;
+        Ok(Self {
+            payer, authority, amm_config, pool_state, input_token_account, output_token_account,
+            input_vault, output_vault, input_token_program, output_token_program, input_token_mint,
+            output_token_mint, observation_state, amount_in, minimum_amount_out,
+        })
     }
 
-    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
-        // Account metadata - 13 accounts total
-        let account_metas: [AccountMeta; 13] = [
-            AccountMeta::writable_signer(self.payer.key()),
-            AccountMeta::readonly(self.authority.key()),
-            AccountMeta::readonly(self.amm_config.key()),
-            AccountMeta::writable(self.pool_state.key()),
-            AccountMeta::writable(self.input_token_account.key()),
-            AccountMeta::writable(self.output_token_account.key()),
-            AccountMeta::writable(self.input_vault.key()),
-            AccountMeta::writable(self.output_vault.key()),
-            AccountMeta::readonly(self.input_token_program.key()),
-            AccountMeta::readonly(self.output_token_program.key()),
-            AccountMeta::readonly(self.input_token_mint.key()),
-            AccountMeta::readonly(self.output_token_mint.key()),
-            AccountMeta::writable(self.observation_state.key()),
-        ];
+    /// Build a swap of `amount_in` from the current state of the pool.
+    ///
+    /// Reads `PoolState`, `AmmConfig` and the vault balances to quote the swap the same
+    /// way the program does. `minimum_amount_out` is the quoted output lowered by `slippage_bps` basis points.
+    pub fn with_slippage(
+        accounts: SwapAccounts<'a>,
+        amount_in: u64,
+        slippage_bps: u16,
+    ) -> Result<Self, ProgramError> {
+        let quote = accounts.quote_base_input(amount_in)?;
+        Ok(Self {
+            payer: accounts.payer,
+            authority: accounts.authority,
+            amm_config: accounts.amm_config,
+            pool_state: accounts.pool_state,
+            input_token_account: accounts.input_token_account,
+            output_token_account: accounts.output_token_account,
+            input_vault: accounts.input_vault,
+            output_vault: accounts.output_vault,
+            input_token_program: accounts.input_token_program,
+            output_token_program: accounts.output_token_program,
+            input_token_mint: accounts.input_token_mint,
+            output_token_mint: accounts.output_token_mint,
+            observation_state: accounts.observation_state,
+            amount_in,
+            minimum_amount_out: minimum_amount_out(quote.amount_out, slippage_bps)?,
+        })
+    }
+}
+
+impl SwapBaseInput<'_> {
+    /// Anchor discriminator of the instruction
+    pub const DISCRIMINATOR: [u8; 8] = [143, 190, 90, 218, 196, 30, 51, 222];
+
+    /// Length of the instruction data
+    pub const DATA_LEN: usize = 24;
+
+    /// Roles of the accounts, in order
+    pub const ACCOUNTS: [AccountRole; 13] = [
+        AccountRole::READONLY_SIGNER,
+        AccountRole::READONLY,
+        AccountRole::READONLY,
+        AccountRole::WRITABLE,
+        AccountRole::WRITABLE,
+        AccountRole::WRITABLE,
+        AccountRole::WRITABLE,
+        AccountRole::WRITABLE,
+        AccountRole::READONLY,
+        AccountRole::READONLY,
+        AccountRole::READONLY,
+        AccountRole::READONLY,
+        AccountRole::WRITABLE,
+    ];
 
+    /// Names of the accounts, in order
+    pub const ACCOUNT_NAMES: [&'static str; 13] = [
+        "payer", "authority", "amm_config", "pool_state", "input_token_account",
+        "output_token_account", "input_vault", "output_vault", "input_token_program",
+        "output_token_program", "input_token_mint", "output_token_mint", "observation_state",
+    ];
+
+    /// Write the instruction data, initializing every byte of `data`
+    #[inline(always)]
+    pub fn write_data(data: &mut [MaybeUninit<u8>; 24], amount_in: u64, minimum_amount_out: u64) {
         // Instruction data layout:
         // -  [0..8]: instruction discriminator (8 bytes)
         // -  [8..16]: amount_in (8 bytes, u64)
         // -  [16..24]: minimum_amount_out (8 bytes, u64)
-        let mut instruction_data = [UNINIT_BYTE; 24];
 
         // Set discriminator (8 bytes)
-        write_bytes(&mut instruction_data[0..8], &[143, 190, 90, 218, 196, 30, 51, 222]);
-        
+        write_bytes(&mut data[0..8], &Self::DISCRIMINATOR);
+
         // Set amount_in (8 bytes)
-        write_bytes(&mut instruction_data[8..16], &self.amount_in.to_le_bytes());
-        
+        write_bytes(&mut data[8..16], &amount_in.to_le_bytes());
+
         // Set minimum_amount_out (8 bytes)
-        write_bytes(&mut instruction_data[16..24], &self.minimum_amount_out.to_le_bytes());
+        write_bytes(&mut data[16..24], &minimum_amount_out.to_le_bytes());
+    }
 
-        let instruction = Instruction {
-            program_id: &crate::ID,
-            accounts: &account_metas,
-            data: unsafe { from_raw_parts(instruction_data.as_ptr() as _, 24) },
-        };
+    /// Check the accounts against the `PoolState` before invoking, see `SwapAccounts::validate`.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        SwapAccounts {
+            payer: self.payer,
+            authority: self.authority,
+            amm_config: self.amm_config,
+            pool_state: self.pool_state,
+            input_token_account: self.input_token_account,
+            output_token_account: self.output_token_account,
+            input_vault: self.input_vault,
+            output_vault: self.output_vault,
+            input_token_program: self.input_token_program,
+            output_token_program: self.output_token_program,
+            input_token_mint: self.input_token_mint,
+            output_token_mint: self.output_token_mint,
+            observation_state: self.observation_state,
+        }
+        .validate()
+    }
+
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        self.with_instruction(|instruction, accounts| invoke_signed(instruction, accounts, signers))
+    }
 
+    /// Build the instruction and call `f` with it and the account infos, in instruction order.
+    ///
+    /// `invoke_signed` passes them to `pinocchio::program::invoke_signed`, other executors such
+    /// as `test_utils::svm::Svm` can run the same instruction.
+    #[inline(always)]
+    pub fn with_instruction<R>(&self, f: impl FnOnce(&Instruction, &[&AccountInfo; 13]) -> R) -> R {
         let accounts = [
             self.payer, self.authority, self.amm_config, self.pool_state,
             self.input_token_account, self.output_token_account, self.input_vault, self.output_vault,
             self.input_token_program, self.output_token_program, self.input_token_mint, self.output_token_mint,
             self.observation_state,
         ];
+        let account_metas = account_metas(&Self::ACCOUNTS, &accounts);
+
+        let mut instruction_data = [UNINIT_BYTE; 24];
+        Self::write_data(&mut instruction_data, self.amount_in, self.minimum_amount_out);
+
+        let instruction = Instruction {
+            program_id: crate::program_id(),
+            accounts: &account_metas,
+            data: unsafe { from_raw_parts(instruction_data.as_ptr() as _, 24) },
+        };
 
-        invoke_signed(&instruction, &accounts, signers)
+        f(&instruction, &accounts)
     }
 } 
\ No newline at end of file
@@ -0,0 +1,244 @@
+use core::{mem::MaybeUninit, slice::from_raw_parts};
+
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Instruction, Signer},
+    program::slice_invoke_signed,
+    program_error::ProgramError,
+    ProgramResult,
+};
+
+use crate::{admin, error::ValidationError, write_bytes, UNINIT_BYTE};
+
+use super::checks::{check_key, load_amm_config};
+use super::encoding::{account_metas, AccountRole};
+
+/// The field of an `AmmConfig` changed by `UpdateAmmConfig`, the owner variants carrying
+/// the new owner as an `Owner`
+///
+/// Each variant maps to the `param` value expected by the program. CPIs use it as
+/// `UpdateAmmConfigParam`, with the new owner account, and the `client` feature with the
+/// address of the new owner, so both encode the instruction the same way.
+#[derive(Clone, Copy, Debug)]
+pub enum UpdateAmmConfigField<Owner> {
+    /// `param = 0`: the new trade fee rate
+    TradeFeeRate(u64),
+    /// `param = 1`: the new protocol fee rate
+    ProtocolFeeRate(u64),
+    /// `param = 2`: the new fund fee rate
+    FundFeeRate(u64),
+    /// `param = 3`: the new protocol owner
+    NewProtocolOwner(Owner),
+    /// `param = 4`: the new fund owner
+    NewFundOwner(Owner),
+    /// `param = 5`: the new create pool fee
+    CreatePoolFee(u64),
+    /// `param = 6`: whether new pools can be created with the config
+    DisableCreatePool(bool),
+    /// `param = 7`: the new pool creator fee rate, only supported by
+    /// releases with creator fees
+    CreatorFeeRate(u64),
+}
+
+/// The field of an `AmmConfig` changed by `UpdateAmmConfig`
+///
+/// The owner variants carry the new owner account, which is passed as the first
+/// remaining account of the instruction.
+pub type UpdateAmmConfigParam<'a> = UpdateAmmConfigField<&'a AccountInfo>;
+
+impl<Owner: Copy> UpdateAmmConfigField<Owner> {
+    pub const TRADE_FEE_RATE: u8 = 0;
+    pub const PROTOCOL_FEE_RATE: u8 = 1;
+    pub const FUND_FEE_RATE: u8 = 2;
+    pub const NEW_PROTOCOL_OWNER: u8 = 3;
+    pub const NEW_FUND_OWNER: u8 = 4;
+    pub const CREATE_POOL_FEE: u8 = 5;
+    pub const DISABLE_CREATE_POOL: u8 = 6;
+    pub const CREATOR_FEE_RATE: u8 = 7;
+
+    /// The `param` value of the instruction.
+    #[inline(always)]
+    pub fn param(&self) -> u8 {
+        match self {
+            Self::TradeFeeRate(_) => Self::TRADE_FEE_RATE,
+            Self::ProtocolFeeRate(_) => Self::PROTOCOL_FEE_RATE,
+            Self::FundFeeRate(_) => Self::FUND_FEE_RATE,
+            Self::NewProtocolOwner(_) => Self::NEW_PROTOCOL_OWNER,
+            Self::NewFundOwner(_) => Self::NEW_FUND_OWNER,
+            Self::CreatePoolFee(_) => Self::CREATE_POOL_FEE,
+            Self::DisableCreatePool(_) => Self::DISABLE_CREATE_POOL,
+            Self::CreatorFeeRate(_) => Self::CREATOR_FEE_RATE,
+        }
+    }
+
+    /// The `value` of the instruction. Owner updates ignore it and send `0`.
+    #[inline(always)]
+    pub fn value(&self) -> u64 {
+        match self {
+            Self::TradeFeeRate(value)
+            | Self::ProtocolFeeRate(value)
+            | Self::FundFeeRate(value)
+            | Self::CreatePoolFee(value)
+            | Self::CreatorFeeRate(value) => *value,
+            Self::DisableCreatePool(disable) => *disable as u64,
+            Self::NewProtocolOwner(_) | Self::NewFundOwner(_) => 0,
+        }
+    }
+
+    /// The account passed as remaining account, if any.
+    #[inline(always)]
+    pub fn new_owner(&self) -> Option<Owner> {
+        match self {
+            Self::NewProtocolOwner(owner) | Self::NewFundOwner(owner) => Some(*owner),
+            _ => None,
+        }
+    }
+}
+
+/// Updates a field of an `AmmConfig`
+///
+/// ### Accounts:
+///   0. `[SIGNER]` owner - The amm config owner or admin, must be `crate::admin::ID`
+///   1. `[WRITE]` amm_config - Amm config account to be changed
+///   2. `[]` new_owner - Only for `NewProtocolOwner` and `NewFundOwner`
+pub struct UpdateAmmConfig<'a> {
+    /// The amm config owner or admin. Must be `crate::admin::ID`
+    pub owner: &'a AccountInfo,
+    /// Amm config account to be changed
+    pub amm_config: &'a AccountInfo,
+
+    /// Instruction parameters
+    pub param: UpdateAmmConfigParam<'a>,
+}
+
+impl<'a> UpdateAmmConfig<'a> {
+    /// Bind `accounts` in the documented order.
+    ///
+    /// `accounts` must hold exactly the accounts of the instruction, optionally followed by
+    /// the new owner account of `NewProtocolOwner` and `NewFundOwner`, which is carried by
+    /// `param`. Fewer fail with `NotEnoughAccountKeys` and any other account with
+    /// `InvalidArgument`.
+    pub fn from_accounts(
+        accounts: &'a [AccountInfo],
+        param: UpdateAmmConfigParam<'a>,
+    ) -> Result<Self, ProgramError> {
+        let [owner, amm_config, remaining @ ..] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+        match (remaining, param.new_owner()) {
+            ([], _) => {}
+            ([account], Some(new_owner)) if account.key() == new_owner.key() => {}
+            _ => return Err(ProgramError::InvalidArgument),
+        }
+        Ok(Self { owner, amm_config, param })
+    }
+}
+
+impl UpdateAmmConfig<'_> {
+    /// Anchor discriminator of the instruction
+    pub const DISCRIMINATOR: [u8; 8] = [49, 60, 174, 136, 154, 28, 116, 200];
+
+    /// Length of the instruction data
+    pub const DATA_LEN: usize = 17;
+
+    /// Roles of the accounts, in order
+    pub const ACCOUNTS: [AccountRole; 2] = [
+        AccountRole::READONLY_SIGNER,
+        AccountRole::WRITABLE,
+    ];
+
+    /// Names of the accounts, in order
+    pub const ACCOUNT_NAMES: [&'static str; 2] = [
+        "owner", "amm_config",
+    ];
+
+    /// Role of the new owner account, passed after `ACCOUNTS` by owner updates
+    pub const NEW_OWNER_ACCOUNT: AccountRole = AccountRole::READONLY;
+
+    /// Name of the new owner account
+    pub const NEW_OWNER_ACCOUNT_NAME: &'static str = "new_owner";
+
+    /// Write the instruction data, initializing every byte of `data`
+    #[inline(always)]
+    pub fn write_data(data: &mut [MaybeUninit<u8>; 17], param: u8, value: u64) {
+        // Instruction data layout:
+        // -  [0..8]: instruction discriminator (8 bytes)
+        // -  [8]: param (1 byte, u8)
+        // -  [9..17]: value (8 bytes, u64)
+
+        // Set discriminator (8 bytes)
+        write_bytes(&mut data[0..8], &Self::DISCRIMINATOR);
+
+        // Set param (1 byte)
+        write_bytes(&mut data[8..9], &[param]);
+
+        // Set value (8 bytes)
+        write_bytes(&mut data[9..17], &value.to_le_bytes());
+    }
+
+    /// Check the accounts against the admin and `AmmConfig` before invoking.
+    ///
+    /// This is opt-in, the program performs the same checks but fails with less
+    /// descriptive errors after the CPI compute has been spent.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        check_key(self.owner, &admin::ID, ValidationError::InvalidOwner)?;
+        load_amm_config(self.amm_config)?;
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        self.with_instruction(|instruction, accounts| {
+            slice_invoke_signed(instruction, accounts, signers)
+        })
+    }
+
+    /// Build the instruction and call `f` with it and the account infos, in instruction order,
+    /// the new owner included for `NewProtocolOwner` and `NewFundOwner`.
+    ///
+    /// `invoke_signed` passes them to `pinocchio::program::slice_invoke_signed`, other executors
+    /// such as `test_utils::svm::Svm` can run the same instruction.
+    #[inline(always)]
+    pub fn with_instruction<R>(&self, f: impl FnOnce(&Instruction, &[&AccountInfo]) -> R) -> R {
+        let mut instruction_data = [UNINIT_BYTE; 17];
+        Self::write_data(&mut instruction_data, self.param.param(), self.param.value());
+
+        let data = unsafe { from_raw_parts(instruction_data.as_ptr() as _, 17) };
+
+        let accounts = [self.owner, self.amm_config];
+        let [owner_meta, amm_config_meta] = account_metas(&Self::ACCOUNTS, &accounts);
+
+        match self.param.new_owner() {
+            Some(new_owner) => {
+                let account_metas = [
+                    owner_meta,
+                    amm_config_meta,
+                    Self::NEW_OWNER_ACCOUNT.meta(new_owner.key()),
+                ];
+
+                let instruction = Instruction {
+                    program_id: crate::program_id(),
+                    accounts: &account_metas,
+                    data,
+                };
+
+                f(&instruction, &[self.owner, self.amm_config, new_owner])
+            }
+            None => {
+                let account_metas = [owner_meta, amm_config_meta];
+
+                let instruction = Instruction {
+                    program_id: crate::program_id(),
+                    accounts: &account_metas,
+                    data,
+                };
+
+                f(&instruction, &accounts)
+            }
+        }
+    }
+}
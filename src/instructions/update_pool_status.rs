@@ -0,0 +1,115 @@
+use core::{mem::MaybeUninit, slice::from_raw_parts};
+
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Instruction, Signer},
+    program::invoke_signed,
+    program_error::ProgramError,
+    ProgramResult,
+};
+
+use crate::{admin, error::ValidationError, write_bytes, UNINIT_BYTE};
+
+use super::checks::{check_key, load_pool_state};
+use super::encoding::{account_metas, exact_accounts, AccountRole};
+
+/// Update pool status for given value
+///
+/// ### Accounts:
+///   0. `[SIGNER]` authority - Admin authority, must be `crate::admin::ID`
+///   1. `[WRITE]` pool_state - Pool state account
+pub struct UpdatePoolStatus<'a> {
+    /// Admin authority. Must be `crate::admin::ID`
+    pub authority: &'a AccountInfo,
+    /// Pool state account
+    pub pool_state: &'a AccountInfo,
+
+    /// Instruction parameters
+    ///
+    /// Bitwise representation of the pool status, see `PoolState::status`
+    pub status: u8,
+}
+
+impl<'a> UpdatePoolStatus<'a> {
+    /// Bind `accounts` in the documented order.
+    ///
+    /// `accounts` must hold exactly the accounts of the instruction, fewer fail with
+    /// `NotEnoughAccountKeys` and more with `InvalidArgument`.
+    pub fn from_accounts(accounts: &'a [AccountInfo], status: u8) -> Result<Self, ProgramError> {
+        let [authority, pool_state] = exact_accounts(accounts)?;
+        Ok(Self { authority, pool_state, status })
+    }
+}
+
+impl UpdatePoolStatus<'_> {
+    /// Anchor discriminator of the instruction
+    pub const DISCRIMINATOR: [u8; 8] = [130, 87, 108, 6, 46, 224, 117, 123];
+
+    /// Length of the instruction data
+    pub const DATA_LEN: usize = 9;
+
+    /// Roles of the accounts, in order
+    pub const ACCOUNTS: [AccountRole; 2] = [
+        AccountRole::READONLY_SIGNER,
+        AccountRole::WRITABLE,
+    ];
+
+    /// Names of the accounts, in order
+    pub const ACCOUNT_NAMES: [&'static str; 2] = [
+        "authority", "pool_state",
+    ];
+
+    /// Write the instruction data, initializing every byte of `data`
+    #[inline(always)]
+    pub fn write_data(data: &mut [MaybeUninit<u8>; 9], status: u8) {
+        // Instruction data layout:
+        // -  [0..8]: instruction discriminator (8 bytes)
+        // -  [8]: status (1 byte, u8)
+
+        // Set discriminator (8 bytes)
+        write_bytes(&mut data[0..8], &Self::DISCRIMINATOR);
+
+        // Set status (1 byte)
+        write_bytes(&mut data[8..9], &[status]);
+    }
+
+    /// Check the accounts against the admin and `PoolState` before invoking.
+    ///
+    /// This is opt-in, the program performs the same checks but fails with less
+    /// descriptive errors after the CPI compute has been spent.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        check_key(self.authority, &admin::ID, ValidationError::InvalidOwner)?;
+        load_pool_state(self.pool_state)?;
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        self.with_instruction(|instruction, accounts| invoke_signed(instruction, accounts, signers))
+    }
+
+    /// Build the instruction and call `f` with it and the account infos, in instruction order.
+    ///
+    /// `invoke_signed` passes them to `pinocchio::program::invoke_signed`, other executors such
+    /// as `test_utils::svm::Svm` can run the same instruction.
+    #[inline(always)]
+    pub fn with_instruction<R>(&self, f: impl FnOnce(&Instruction, &[&AccountInfo; 2]) -> R) -> R {
+        let accounts = [self.authority, self.pool_state];
+        let account_metas = account_metas(&Self::ACCOUNTS, &accounts);
+
+        let mut instruction_data = [UNINIT_BYTE; 9];
+        Self::write_data(&mut instruction_data, self.status);
+
+        let instruction = Instruction {
+            program_id: crate::program_id(),
+            accounts: &account_metas,
+            data: unsafe { from_raw_parts(instruction_data.as_ptr() as _, 9) },
+        };
+
+        f(&instruction, &accounts)
+    }
+}
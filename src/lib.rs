@@ -1,13 +1,27 @@
 #![no_std]
 
+#[cfg(any(feature = "client", feature = "test-utils"))]
+extern crate std;
+
+#[cfg(feature = "client")]
+pub mod client;
+pub mod curve;
+pub mod error;
+pub mod events;
 pub mod instructions;
+#[cfg(feature = "mock-program")]
+pub mod mock_program;
+pub mod oracle;
+pub mod pda;
 pub mod states;
+#[cfg(feature = "test-utils")]
+pub mod test_utils;
 
 use pinocchio::pubkey::Pubkey;
 use core::mem::MaybeUninit;
 
 // CPMDWBwJDtYax9qW7AyRuVC19Cc4L4Vcy4n2BHAbHkCW
-#[cfg(feature = "devnet")]
+#[cfg(all(feature = "devnet", not(feature = "localnet")))]
 pub const ID: Pubkey = [
     0xa9, 0x2a, 0x31, 0x1a, 0x88, 0x98, 0x86, 0x4d,
     0x20, 0x63, 0xc8, 0xfc, 0xcb, 0x53, 0x6e, 0x1e,
@@ -15,17 +29,76 @@ pub const ID: Pubkey = [
     0x4e, 0xb3, 0xc1, 0x44, 0x07, 0xd6, 0x74, 0xe7,
 ];
 //CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C
-#[cfg(not(feature = "devnet"))]
+#[cfg(not(any(feature = "devnet", feature = "localnet")))]
 pub const ID: Pubkey = [
     0xa9, 0x2a, 0x5a, 0x8b, 0x4f, 0x29, 0x59, 0x52,
     0x84, 0x25, 0x50, 0xaa, 0x93, 0xfd, 0x5b, 0x95,
     0xb5, 0xac, 0xe6, 0xa8, 0xeb, 0x92, 0x0c, 0x93,
     0x94, 0x2e, 0x43, 0x69, 0x0c, 0x20, 0xec, 0x73,
 ];
+// Mainnet address unless `CPMM_PROGRAM_ID` is set when building
+#[cfg(feature = "localnet")]
+pub const ID: Pubkey = match option_env!("CPMM_PROGRAM_ID") {
+    Some(id) => pinocchio_pubkey::from_str(id),
+    None => pinocchio_pubkey::from_str("CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C"),
+};
+
+/// Return the CPMM program id used by the instruction structs, PDA helpers and owner checks.
+///
+/// This is `ID` unless overridden with `set_program_id`.
+#[cfg(not(target_os = "solana"))]
+#[inline(always)]
+pub fn program_id() -> &'static Pubkey {
+    let program_id = PROGRAM_ID_OVERRIDE.load(core::sync::atomic::Ordering::Acquire);
+    if program_id.is_null() {
+        &ID
+    } else {
+        // SAFETY: only `&'static Pubkey`s are stored by `set_program_id`.
+        unsafe { &*program_id }
+    }
+}
+
+/// Return the CPMM program id used by the instruction structs, PDA helpers and owner checks.
+///
+/// Programs cannot hold writable statics, on-chain this is always `ID`.
+#[cfg(target_os = "solana")]
+#[inline(always)]
+pub fn program_id() -> &'static Pubkey {
+    &ID
+}
+
+#[cfg(not(target_os = "solana"))]
+static PROGRAM_ID_OVERRIDE: core::sync::atomic::AtomicPtr<Pubkey> =
+    core::sync::atomic::AtomicPtr::new(core::ptr::null_mut());
+
+/// Use `program_id` instead of `ID`, for CPMM builds deployed at a custom address.
+///
+/// The override is process wide and only available off-chain, on-chain builds select the
+/// address at build time with the `localnet` feature.
+///
+/// Every thread sees the override, including the other tests of a `cargo test` binary,
+/// which run in parallel. Tests calling this should live in their own test binary, or hold
+/// a lock shared by every test depending on `program_id` for as long as the override is set.
+#[cfg(not(target_os = "solana"))]
+pub fn set_program_id(program_id: &'static Pubkey) {
+    PROGRAM_ID_OVERRIDE.store(
+        program_id as *const Pubkey as *mut Pubkey,
+        core::sync::atomic::Ordering::Release,
+    );
+}
+
+/// Remove the override of `set_program_id`, going back to `ID`.
+///
+/// As `set_program_id`, this is seen by every thread of the process.
+#[cfg(not(target_os = "solana"))]
+pub fn reset_program_id() {
+    PROGRAM_ID_OVERRIDE.store(core::ptr::null_mut(), core::sync::atomic::Ordering::Release);
+}
+
 pub mod admin {
     use pinocchio::pubkey::Pubkey;
 //adMCyoCgfkg7bQiJ9aBJ59H3BXLY3r5LNLfPpQfMzBe
-    #[cfg(feature = "devnet")]
+    #[cfg(all(feature = "devnet", not(feature = "localnet")))]
     pub const ID: Pubkey = [
         0x08, 0x9d, 0x44, 0x15, 0x87, 0x33, 0xc1, 0xa8,
         0x20, 0x23, 0x5f, 0x6a, 0xb0, 0xf4, 0x34, 0xa2,
@@ -33,19 +106,25 @@ pub mod admin {
         0xc8, 0x96, 0xe7, 0xc8, 0x93, 0x6b, 0xe9, 0x0d,
     ];
 //GThUX1Atko4tqhN2NaiTazWSeFWMuiUvfFnyJyUghFMJ
-    #[cfg(not(feature = "devnet"))]
+    #[cfg(not(any(feature = "devnet", feature = "localnet")))]
     pub const ID: Pubkey = [
         0xe5, 0xb6, 0x2b, 0x65, 0xcb, 0x3b, 0xbd, 0xa6,
         0xf5, 0x68, 0x88, 0xe6, 0x6f, 0xee, 0x8e, 0x64,
         0xdc, 0x55, 0x60, 0x19, 0x9c, 0x0f, 0x88, 0xb1,
         0x1f, 0xe2, 0x73, 0xbd, 0x05, 0x9e, 0x8a, 0xa1,
     ];
+    // Mainnet address unless `CPMM_ADMIN` is set when building
+    #[cfg(feature = "localnet")]
+    pub const ID: Pubkey = match option_env!("CPMM_ADMIN") {
+        Some(id) => pinocchio_pubkey::from_str(id),
+        None => pinocchio_pubkey::from_str("GThUX1Atko4tqhN2NaiTazWSeFWMuiUvfFnyJyUghFMJ"),
+    };
 }
 
 pub mod create_pool_fee_reveiver {
     use pinocchio::pubkey::Pubkey;
 //G11FKBRaAkHAKuLCgLM6K6NUc9rTjPAznRCjZifrTQe2
-    #[cfg(feature = "devnet")]
+    #[cfg(all(feature = "devnet", not(feature = "localnet")))]
     pub const ID: Pubkey = [
         0xde, 0xdf, 0x95, 0x3b, 0x2e, 0x71, 0x83, 0x7b,
         0xb5, 0x72, 0xab, 0x09, 0x14, 0x21, 0x99, 0x74,
@@ -53,13 +132,80 @@ pub mod create_pool_fee_reveiver {
         0x03, 0x20, 0x1e, 0x84, 0x15, 0xb3, 0xe4, 0xbf,
     ];
 //DNXgeM9EiiaAbaWvwjHj9fQQLAX5ZsfHyvmYUNRAdNC8
-    #[cfg(not(feature = "devnet"))]
+    #[cfg(not(any(feature = "devnet", feature = "localnet")))]
     pub const ID: Pubkey = [
         0xb7, 0xd0, 0x22, 0x52, 0x54, 0xac, 0x07, 0xe3,
         0xb2, 0xbd, 0x3f, 0x86, 0xc1, 0xf0, 0xf1, 0x10,
         0x3f, 0xc0, 0x70, 0x8c, 0xc1, 0x5a, 0xef, 0x14,
         0x07, 0x3a, 0xa6, 0x45, 0x3f, 0x55, 0xea, 0x69,
     ];
+    // Mainnet address unless `CPMM_CREATE_POOL_FEE_RECEIVER` is set when building
+    #[cfg(feature = "localnet")]
+    pub const ID: Pubkey = match option_env!("CPMM_CREATE_POOL_FEE_RECEIVER") {
+        Some(id) => pinocchio_pubkey::from_str(id),
+        None => pinocchio_pubkey::from_str("DNXgeM9EiiaAbaWvwjHj9fQQLAX5ZsfHyvmYUNRAdNC8"),
+    };
+}
+
+pub mod token_program {
+    use pinocchio::pubkey::Pubkey;
+//TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA
+    pub const ID: Pubkey = [
+        0x06, 0xdd, 0xf6, 0xe1, 0xd7, 0x65, 0xa1, 0x93,
+        0xd9, 0xcb, 0xe1, 0x46, 0xce, 0xeb, 0x79, 0xac,
+        0x1c, 0xb4, 0x85, 0xed, 0x5f, 0x5b, 0x37, 0x91,
+        0x3a, 0x8c, 0xf5, 0x85, 0x7e, 0xff, 0x00, 0xa9,
+    ];
+}
+
+pub mod token_program_2022 {
+    use pinocchio::pubkey::Pubkey;
+//TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb
+    pub const ID: Pubkey = [
+        0x06, 0xdd, 0xf6, 0xe1, 0xee, 0x75, 0x8f, 0xde,
+        0x18, 0x42, 0x5d, 0xbc, 0xe4, 0x6c, 0xcd, 0xda,
+        0xb6, 0x1a, 0xfc, 0x4d, 0x83, 0xb9, 0x0d, 0x27,
+        0xfe, 0xbd, 0xf9, 0x28, 0xd8, 0xa1, 0x8b, 0xfc,
+    ];
+}
+
+pub mod memo_program {
+    use pinocchio::pubkey::Pubkey;
+//MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr
+    pub const ID: Pubkey = [
+        0x05, 0x4a, 0x53, 0x5a, 0x99, 0x29, 0x21, 0x06,
+        0x4d, 0x24, 0xe8, 0x71, 0x60, 0xda, 0x38, 0x7c,
+        0x7c, 0x35, 0xb5, 0xdd, 0xbc, 0x92, 0xbb, 0x81,
+        0xe4, 0x1f, 0xa8, 0x40, 0x41, 0x05, 0x44, 0x8d,
+    ];
+}
+
+pub mod associated_token_program {
+    use pinocchio::pubkey::Pubkey;
+//ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL
+    pub const ID: Pubkey = [
+        0x8c, 0x97, 0x25, 0x8f, 0x4e, 0x24, 0x89, 0xf1,
+        0xbb, 0x3d, 0x10, 0x29, 0x14, 0x8e, 0x0d, 0x83,
+        0x0b, 0x5a, 0x13, 0x99, 0xda, 0xff, 0x10, 0x84,
+        0x04, 0x8e, 0x7b, 0xd8, 0xdb, 0xe9, 0xf8, 0x59,
+    ];
+}
+
+pub mod system_program {
+    use pinocchio::pubkey::Pubkey;
+//11111111111111111111111111111111
+    pub const ID: Pubkey = [0; 32];
+}
+
+pub mod rent_sysvar {
+    use pinocchio::pubkey::Pubkey;
+//SysvarRent111111111111111111111111111111111
+    pub const ID: Pubkey = [
+        0x06, 0xa7, 0xd5, 0x17, 0x19, 0x2c, 0x5c, 0x51,
+        0x21, 0x8c, 0xc9, 0x4c, 0x3d, 0x4a, 0xf1, 0x7f,
+        0x58, 0xda, 0xee, 0x08, 0x9b, 0xa1, 0xfd, 0x44,
+        0xe3, 0xdb, 0xd9, 0x8a, 0x00, 0x00, 0x00, 0x00,
+    ];
 }
 
 pub const AUTH_SEED: &str = "vault_and_lp_mint_auth_seed";
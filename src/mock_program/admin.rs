@@ -0,0 +1,170 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    ProgramResult,
+};
+
+use crate::{
+    admin,
+    curve::FEE_RATE_DENOMINATOR_VALUE,
+    error::CpmmError,
+    pda::try_find_program_address,
+    states::{AmmConfig, Permission, PoolState, AMM_CONFIG_SEED, PERMISSION_SEED},
+    system_program,
+};
+
+use super::{initialize_account, load, update_account, update_pool_state, AnchorError};
+
+/// Check that `owner` is the admin and signed
+fn check_admin(owner: &AccountInfo) -> ProgramResult {
+    if owner.key() != &admin::ID {
+        return Err(CpmmError::InvalidOwner.into());
+    }
+    if !owner.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    Ok(())
+}
+
+/// Check the trade, protocol, fund and creator fee rates of `amm_config`
+fn check_rates(amm_config: &AmmConfig) -> ProgramResult {
+    let valid = amm_config.trade_fee_rate < FEE_RATE_DENOMINATOR_VALUE
+        && amm_config.protocol_fee_rate <= FEE_RATE_DENOMINATOR_VALUE
+        && amm_config.fund_fee_rate <= FEE_RATE_DENOMINATOR_VALUE
+        && amm_config.protocol_fee_rate.saturating_add(amm_config.fund_fee_rate)
+            <= FEE_RATE_DENOMINATOR_VALUE
+        && amm_config.trade_fee_rate.saturating_add(amm_config.creator_fee_rate)
+            <= FEE_RATE_DENOMINATOR_VALUE;
+    if !valid {
+        return Err(CpmmError::InvalidInput.into());
+    }
+    Ok(())
+}
+
+/// `CreateAmmConfig`, `rates` holds the trade, protocol, fund and creator fee rates
+pub(crate) fn create_amm_config(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    index: u16,
+    rates: [u64; 4],
+    create_pool_fee: u64,
+) -> ProgramResult {
+    let [owner, amm_config, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    check_admin(owner)?;
+
+    let [trade_fee_rate, protocol_fee_rate, fund_fee_rate, creator_fee_rate] = rates;
+    let config = AmmConfig {
+        bump: 0,
+        disable_create_pool: false,
+        index,
+        trade_fee_rate,
+        protocol_fee_rate,
+        fund_fee_rate,
+        create_pool_fee,
+        protocol_owner: *owner.key(),
+        fund_owner: *owner.key(),
+        creator_fee_rate,
+        padding: [0; 15],
+    };
+    check_rates(&config)?;
+
+    let seeds: [&[u8]; 2] = [AMM_CONFIG_SEED.as_bytes(), &index.to_be_bytes()];
+    initialize_account(program_id, amm_config, &seeds, |state: &mut AmmConfig, bump| {
+        *state = AmmConfig { bump, ..config };
+    })
+}
+
+/// `UpdateAmmConfig`, the new owner of `param` 3 and 4 is the first account after the config
+pub(crate) fn update_amm_config(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    param: u8,
+    value: u64,
+) -> ProgramResult {
+    let [owner, amm_config, remaining @ ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    check_admin(owner)?;
+    let mut config = load::<AmmConfig>(program_id, amm_config)?;
+
+    let new_owner = || -> Result<Pubkey, ProgramError> {
+        let new_owner = *remaining.first().ok_or(ProgramError::NotEnoughAccountKeys)?.key();
+        if new_owner == Pubkey::default() {
+            return Err(AnchorError::RequireKeysNeqViolated.into());
+        }
+        Ok(new_owner)
+    };
+    match param {
+        0 => config.trade_fee_rate = value,
+        1 => config.protocol_fee_rate = value,
+        2 => config.fund_fee_rate = value,
+        3 => config.protocol_owner = new_owner()?,
+        4 => config.fund_owner = new_owner()?,
+        5 => config.create_pool_fee = value,
+        6 => config.disable_create_pool = value != 0,
+        7 => config.creator_fee_rate = value,
+        _ => return Err(CpmmError::InvalidInput.into()),
+    }
+    check_rates(&config)?;
+
+    update_account(amm_config, |state: &mut AmmConfig| {
+        *state = config;
+        Ok(())
+    })
+}
+
+pub(crate) fn update_pool_status(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    status: u8,
+) -> ProgramResult {
+    let [authority, pool_state, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    check_admin(authority)?;
+    load::<PoolState>(program_id, pool_state)?;
+
+    update_pool_state(pool_state, |pool| {
+        pool.status = status;
+        Ok(())
+    })
+}
+
+pub(crate) fn create_permission_pda(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let [owner, permission_authority, permission, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    check_admin(owner)?;
+
+    let seeds = [PERMISSION_SEED.as_bytes(), permission_authority.key()];
+    initialize_account(program_id, permission, &seeds, |state: &mut Permission, _| {
+        state.authority = *permission_authority.key();
+    })
+}
+
+/// `ClosePermissionPda`, the rent of the permission goes to the owner
+pub(crate) fn close_permission_pda(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let [owner, permission_authority, permission, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    check_admin(owner)?;
+    let seeds = [PERMISSION_SEED.as_bytes(), permission_authority.key()];
+    match try_find_program_address(&seeds, program_id) {
+        Some((address, _)) if address == *permission.key() => {}
+        _ => return Err(AnchorError::ConstraintSeeds.into()),
+    }
+    load::<Permission>(program_id, permission)?;
+
+    // Closed as Anchor does: the lamports move out, the account goes back to the system
+    // program with no data
+    let lamports = permission.lamports();
+    *owner.try_borrow_mut_lamports()? += lamports;
+    *permission.try_borrow_mut_lamports()? = 0;
+    permission.try_borrow_mut_data()?.fill(0);
+    // SAFETY: the data and lamports of the permission are not borrowed.
+    unsafe { permission.assign(&system_program::ID) };
+    permission.realloc(0, false)
+}
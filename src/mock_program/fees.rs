@@ -0,0 +1,185 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    ProgramResult,
+};
+
+use crate::{
+    admin,
+    error::CpmmError,
+    states::{AmmConfig, PoolState},
+    token_program, token_program_2022,
+};
+
+use super::{
+    check_authority, check_pool_key, load, token::transfer_checked, update_pool_state,
+    with_authority_signer, AnchorError,
+};
+
+/// The fees collected by `CollectProtocolFee` or `CollectFundFee`
+enum Fees {
+    Protocol,
+    Fund,
+}
+
+pub(crate) fn collect_protocol_fee(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount_0_requested: u64,
+    amount_1_requested: u64,
+) -> ProgramResult {
+    collect(program_id, accounts, Fees::Protocol, amount_0_requested, amount_1_requested)
+}
+
+pub(crate) fn collect_fund_fee(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount_0_requested: u64,
+    amount_1_requested: u64,
+) -> ProgramResult {
+    collect(program_id, accounts, Fees::Fund, amount_0_requested, amount_1_requested)
+}
+
+/// Transfer up to the requested amounts of `fees` to the recipients, the owner must be the
+/// admin or the fee owner of the config
+fn collect(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    fees: Fees,
+    amount_0_requested: u64,
+    amount_1_requested: u64,
+) -> ProgramResult {
+    let [
+        owner, authority, pool_state, amm_config, token_0_vault, token_1_vault, vault_0_mint,
+        vault_1_mint, recipient_token_0_account, recipient_token_1_account, token_program,
+        token_program_2022, ..
+    ] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if !owner.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if token_program.key() != &token_program::ID || token_program_2022.key() != &token_program_2022::ID {
+        return Err(AnchorError::InvalidProgramId.into());
+    }
+    let pool = load::<PoolState>(program_id, pool_state)?;
+    let config = load::<AmmConfig>(program_id, amm_config)?;
+    check_authority(program_id, authority, &pool)?;
+    check_pool_key(amm_config.key(), &pool.amm_config, CpmmError::InvalidInput)?;
+    let fee_owner = match fees {
+        Fees::Protocol => config.protocol_owner,
+        Fees::Fund => config.fund_owner,
+    };
+    if *owner.key() != fee_owner && *owner.key() != admin::ID {
+        return Err(CpmmError::InvalidOwner.into());
+    }
+    check_vaults(&pool, [token_0_vault, token_1_vault], [vault_0_mint, vault_1_mint])?;
+
+    let (fees_0, fees_1) = match fees {
+        Fees::Protocol => (pool.protocol_fees_token_0, pool.protocol_fees_token_1),
+        Fees::Fund => (pool.fund_fees_token_0, pool.fund_fees_token_1),
+    };
+    let amount_0 = amount_0_requested.min(fees_0);
+    let amount_1 = amount_1_requested.min(fees_1);
+    update_pool_state(pool_state, |pool| {
+        match fees {
+            Fees::Protocol => {
+                pool.protocol_fees_token_0 = fees_0 - amount_0;
+                pool.protocol_fees_token_1 = fees_1 - amount_1;
+            }
+            Fees::Fund => {
+                pool.fund_fees_token_0 = fees_0 - amount_0;
+                pool.fund_fees_token_1 = fees_1 - amount_1;
+            }
+        }
+        Ok(())
+    })?;
+
+    let token_program_of = |mint: &AccountInfo| {
+        if mint.is_owned_by(&token_program_2022::ID) {
+            token_program_2022
+        } else {
+            token_program
+        }
+    };
+    transfer_from_vault(
+        &pool,
+        [token_program_of(vault_0_mint), token_0_vault, vault_0_mint],
+        recipient_token_0_account,
+        authority,
+        amount_0,
+    )?;
+    transfer_from_vault(
+        &pool,
+        [token_program_of(vault_1_mint), token_1_vault, vault_1_mint],
+        recipient_token_1_account,
+        authority,
+        amount_1,
+    )
+}
+
+/// `CollectCreatorFee`, all the creator fees go to the pool creator
+pub(crate) fn collect_creator_fee(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let [
+        creator, authority, pool_state, amm_config, token_0_vault, token_1_vault, vault_0_mint,
+        vault_1_mint, creator_token_0, creator_token_1, token_0_program, token_1_program, ..
+    ] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if !creator.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let pool = load::<PoolState>(program_id, pool_state)?;
+    check_authority(program_id, authority, &pool)?;
+    check_pool_key(amm_config.key(), &pool.amm_config, CpmmError::InvalidInput)?;
+    check_pool_key(creator.key(), &pool.pool_creator, CpmmError::InvalidOwner)?;
+    check_vaults(&pool, [token_0_vault, token_1_vault], [vault_0_mint, vault_1_mint])?;
+    if !vault_0_mint.is_owned_by(token_0_program.key()) || !vault_1_mint.is_owned_by(token_1_program.key()) {
+        return Err(AnchorError::ConstraintMintTokenProgram.into());
+    }
+
+    let (amount_0, amount_1) = (pool.creator_fees_token_0, pool.creator_fees_token_1);
+    if amount_0 == 0 && amount_1 == 0 {
+        return Err(CpmmError::NoFeeCollect.into());
+    }
+    update_pool_state(pool_state, |pool| {
+        pool.creator_fees_token_0 = 0;
+        pool.creator_fees_token_1 = 0;
+        Ok(())
+    })?;
+
+    let vault_0 = [token_0_program, token_0_vault, vault_0_mint];
+    let vault_1 = [token_1_program, token_1_vault, vault_1_mint];
+    transfer_from_vault(&pool, vault_0, creator_token_0, authority, amount_0)?;
+    transfer_from_vault(&pool, vault_1, creator_token_1, authority, amount_1)
+}
+
+/// Check the vaults and their mints against the `PoolState`
+fn check_vaults(pool: &PoolState, vaults: [&AccountInfo; 2], mints: [&AccountInfo; 2]) -> ProgramResult {
+    check_pool_key(vaults[0].key(), &pool.token_0_vault, CpmmError::InvalidVault)?;
+    check_pool_key(vaults[1].key(), &pool.token_1_vault, CpmmError::InvalidVault)?;
+    check_pool_key(mints[0].key(), &pool.token_0_mint, CpmmError::InvalidInput)?;
+    check_pool_key(mints[1].key(), &pool.token_1_mint, CpmmError::InvalidInput)
+}
+
+/// Transfer `amount` out of the vault of `[token program, vault, mint]`, nothing when zero as
+/// in the program
+fn transfer_from_vault(
+    pool: &PoolState,
+    [token_program, vault, mint]: [&AccountInfo; 3],
+    to: &AccountInfo,
+    authority: &AccountInfo,
+    amount: u64,
+) -> ProgramResult {
+    if amount == 0 {
+        return Ok(());
+    }
+    with_authority_signer(pool, |signers| {
+        transfer_checked(token_program, vault, mint, to, authority, amount, signers)
+    })
+}
@@ -0,0 +1,231 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    ProgramResult,
+};
+
+use crate::{
+    error::CpmmError,
+    pda::try_find_program_address,
+    states::{AmmConfig, CreatorFeeOn, ObservationState, Permission, PoolState, PERMISSION_SEED},
+    AUTH_SEED,
+};
+
+use super::{
+    block_timestamp, check_uninitialized, load, AnchorError,
+    token::{mint_decimals, mint_to, transfer_checked},
+    with_authority_signer,
+};
+
+/// Lp tokens locked in the pool at initialization, as in the program
+const LOCK_LP_AMOUNT: u64 = 100;
+
+/// Accounts of `Initialize` and `InitializeWithPermission`
+struct InitializeAccounts<'a> {
+    payer: &'a AccountInfo,
+    creator: &'a AccountInfo,
+    amm_config: &'a AccountInfo,
+    authority: &'a AccountInfo,
+    pool_state: &'a AccountInfo,
+    token_0_mint: &'a AccountInfo,
+    token_1_mint: &'a AccountInfo,
+    lp_mint: &'a AccountInfo,
+    payer_token_0: &'a AccountInfo,
+    payer_token_1: &'a AccountInfo,
+    payer_lp_token: &'a AccountInfo,
+    token_0_vault: &'a AccountInfo,
+    token_1_vault: &'a AccountInfo,
+    observation_state: &'a AccountInfo,
+    token_program: &'a AccountInfo,
+    token_0_program: &'a AccountInfo,
+    token_1_program: &'a AccountInfo,
+}
+
+pub(crate) fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    init_amount_0: u64,
+    init_amount_1: u64,
+    open_time: u64,
+) -> ProgramResult {
+    let [
+        creator, amm_config, authority, pool_state, token_0_mint, token_1_mint, lp_mint,
+        creator_token_0, creator_token_1, creator_lp_token, token_0_vault, token_1_vault,
+        _create_pool_fee, observation_state, token_program, token_0_program, token_1_program, ..
+    ] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    let accounts = InitializeAccounts {
+        payer: creator,
+        creator,
+        amm_config,
+        authority,
+        pool_state,
+        token_0_mint,
+        token_1_mint,
+        lp_mint,
+        payer_token_0: creator_token_0,
+        payer_token_1: creator_token_1,
+        payer_lp_token: creator_lp_token,
+        token_0_vault,
+        token_1_vault,
+        observation_state,
+        token_program,
+        token_0_program,
+        token_1_program,
+    };
+    initialize(program_id, &accounts, init_amount_0, init_amount_1, open_time, None)
+}
+
+/// `InitializeWithPermission`, the payer must hold a `Permission` and the pool creator
+/// collects the creator fees on `creator_fee_on`
+pub(crate) fn process_with_permission(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    init_amount_0: u64,
+    init_amount_1: u64,
+    open_time: u64,
+    creator_fee_on: CreatorFeeOn,
+) -> ProgramResult {
+    let [
+        payer, creator, amm_config, authority, pool_state, token_0_mint, token_1_mint, lp_mint,
+        payer_token_0, payer_token_1, payer_lp_token, token_0_vault, token_1_vault,
+        _create_pool_fee, observation_state, permission, token_program, token_0_program,
+        token_1_program, ..
+    ] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    match try_find_program_address(&[PERMISSION_SEED.as_bytes(), payer.key()], program_id) {
+        Some((address, _)) if address == *permission.key() => {}
+        _ => return Err(AnchorError::ConstraintSeeds.into()),
+    }
+    load::<Permission>(program_id, permission)?;
+
+    let accounts = InitializeAccounts {
+        payer,
+        creator,
+        amm_config,
+        authority,
+        pool_state,
+        token_0_mint,
+        token_1_mint,
+        lp_mint,
+        payer_token_0,
+        payer_token_1,
+        payer_lp_token,
+        token_0_vault,
+        token_1_vault,
+        observation_state,
+        token_program,
+        token_0_program,
+        token_1_program,
+    };
+    initialize(program_id, &accounts, init_amount_0, init_amount_1, open_time, Some(creator_fee_on))
+}
+
+/// Initialize the pool, with the creator fee enabled on `creator_fee_on` when set
+fn initialize(
+    program_id: &Pubkey,
+    accounts: &InitializeAccounts,
+    init_amount_0: u64,
+    init_amount_1: u64,
+    open_time: u64,
+    creator_fee_on: Option<CreatorFeeOn>,
+) -> ProgramResult {
+    let InitializeAccounts {
+        payer, creator, amm_config, authority, pool_state, token_0_mint, token_1_mint, lp_mint,
+        payer_token_0, payer_token_1, payer_lp_token, token_0_vault, token_1_vault,
+        observation_state, token_program, token_0_program, token_1_program,
+    } = *accounts;
+
+    if !payer.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if load::<AmmConfig>(program_id, amm_config)?.disable_create_pool {
+        return Err(CpmmError::NotApproved.into());
+    }
+    if token_0_mint.key() >= token_1_mint.key() {
+        return Err(CpmmError::InvalidInput.into());
+    }
+    if !token_0_mint.is_owned_by(token_0_program.key()) || !token_1_mint.is_owned_by(token_1_program.key()) {
+        return Err(AnchorError::ConstraintMintTokenProgram.into());
+    }
+    check_uninitialized(program_id, pool_state, PoolState::LEN)?;
+    check_uninitialized(program_id, observation_state, ObservationState::LEN)?;
+
+    let auth_bump = match try_find_program_address(&[AUTH_SEED.as_bytes()], program_id) {
+        Some((address, bump)) if address == *authority.key() => bump,
+        _ => return Err(AnchorError::ConstraintSeeds.into()),
+    };
+
+    let block_timestamp = block_timestamp()?;
+    let open_time = if open_time <= block_timestamp {
+        block_timestamp + 1
+    } else {
+        open_time
+    };
+
+    transfer_checked(token_0_program, payer_token_0, token_0_mint, token_0_vault, payer, init_amount_0, &[])?;
+    transfer_checked(token_1_program, payer_token_1, token_1_mint, token_1_vault, payer, init_amount_1, &[])?;
+
+    let liquidity = isqrt(u128::from(init_amount_0) * u128::from(init_amount_1)) as u64;
+    if liquidity <= LOCK_LP_AMOUNT {
+        return Err(CpmmError::InitLpAmountTooLess.into());
+    }
+
+    let mut data = pool_state.try_borrow_mut_data()?;
+    data[..8].copy_from_slice(PoolState::DISCRIMINATOR);
+    // SAFETY: the account length was checked and `PoolState` has no invalid bit pattern.
+    let pool = unsafe { PoolState::from_bytes_mut(&mut data[8..]) };
+    pool.amm_config = *amm_config.key();
+    pool.pool_creator = *creator.key();
+    pool.token_0_vault = *token_0_vault.key();
+    pool.token_1_vault = *token_1_vault.key();
+    pool.lp_mint = *lp_mint.key();
+    pool.token_0_mint = *token_0_mint.key();
+    pool.token_1_mint = *token_1_mint.key();
+    pool.token_0_program = *token_0_program.key();
+    pool.token_1_program = *token_1_program.key();
+    pool.observation_key = *observation_state.key();
+    pool.auth_bump = auth_bump;
+    pool.lp_mint_decimals = mint_decimals(lp_mint)?;
+    pool.mint_0_decimals = mint_decimals(token_0_mint)?;
+    pool.mint_1_decimals = mint_decimals(token_1_mint)?;
+    pool.lp_supply = liquidity;
+    pool.open_time = open_time;
+    if let Some(creator_fee_on) = creator_fee_on {
+        pool.creator_fee_on = creator_fee_on as u8;
+        pool.enable_creator_fee = true;
+    }
+    let pool = *pool;
+    drop(data);
+
+    let mut data = observation_state.try_borrow_mut_data()?;
+    data[..8].copy_from_slice(ObservationState::DISCRIMINATOR);
+    // SAFETY: the account length was checked and `ObservationState` has no invalid bit pattern.
+    unsafe { ObservationState::from_bytes_mut(&mut data[8..]) }.pool_id = *pool_state.key();
+    drop(data);
+
+    with_authority_signer(&pool, |signers| {
+        mint_to(token_program, lp_mint, payer_lp_token, authority, liquidity - LOCK_LP_AMOUNT, signers)
+    })
+}
+
+/// Integer square root, rounded down
+fn isqrt(value: u128) -> u128 {
+    if value < 2 {
+        return value;
+    }
+    let mut x = value;
+    let mut y = x.div_ceil(2);
+    while y < x {
+        x = y;
+        y = (x + value / x) / 2;
+    }
+    x
+}
@@ -0,0 +1,236 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    ProgramResult,
+};
+
+use crate::{
+    curve::{lp_tokens_to_trading_tokens, RoundDirection, TradingTokenResult},
+    error::CpmmError,
+    states::PoolState,
+    token_program, token_program_2022,
+};
+
+use super::{
+    check_authority, check_pool_key, load,
+    token::{burn, mint_to, token_amount, transfer_checked},
+    update_pool_state, with_authority_signer, AnchorError,
+};
+
+/// Accounts shared by `Deposit` and `Withdraw`, in instruction order
+struct LiquidityAccounts<'a> {
+    owner: &'a AccountInfo,
+    authority: &'a AccountInfo,
+    pool_state: &'a AccountInfo,
+    owner_lp_token: &'a AccountInfo,
+    token_0_account: &'a AccountInfo,
+    token_1_account: &'a AccountInfo,
+    token_0_vault: &'a AccountInfo,
+    token_1_vault: &'a AccountInfo,
+    token_program: &'a AccountInfo,
+    token_program_2022: &'a AccountInfo,
+    vault_0_mint: &'a AccountInfo,
+    vault_1_mint: &'a AccountInfo,
+    lp_mint: &'a AccountInfo,
+}
+
+impl<'a> LiquidityAccounts<'a> {
+    /// Parse the accounts and check them against the `PoolState`
+    fn load(program_id: &Pubkey, accounts: &'a [AccountInfo]) -> Result<(Self, PoolState), ProgramError> {
+        let [
+            owner, authority, pool_state, owner_lp_token, token_0_account, token_1_account,
+            token_0_vault, token_1_vault, token_program, token_program_2022, vault_0_mint,
+            vault_1_mint, lp_mint, ..
+        ] = accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if !owner.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if token_program.key() != &token_program::ID || token_program_2022.key() != &token_program_2022::ID {
+            return Err(AnchorError::InvalidProgramId.into());
+        }
+        let pool = load::<PoolState>(program_id, pool_state)?;
+        check_authority(program_id, authority, &pool)?;
+        check_pool_key(token_0_vault.key(), &pool.token_0_vault, CpmmError::InvalidVault)?;
+        check_pool_key(token_1_vault.key(), &pool.token_1_vault, CpmmError::InvalidVault)?;
+        check_pool_key(vault_0_mint.key(), &pool.token_0_mint, CpmmError::InvalidInput)?;
+        check_pool_key(vault_1_mint.key(), &pool.token_1_mint, CpmmError::InvalidInput)?;
+        check_pool_key(lp_mint.key(), &pool.lp_mint, CpmmError::IncorrectLpMint)?;
+
+        Ok((
+            Self {
+                owner,
+                authority,
+                pool_state,
+                owner_lp_token,
+                token_0_account,
+                token_1_account,
+                token_0_vault,
+                token_1_vault,
+                token_program,
+                token_program_2022,
+                vault_0_mint,
+                vault_1_mint,
+                lp_mint,
+            },
+            pool,
+        ))
+    }
+
+    /// Return the token program of `mint`, as the program selects it
+    fn token_program_of(&self, mint: &AccountInfo) -> Result<&'a AccountInfo, ProgramError> {
+        if mint.is_owned_by(&token_program_2022::ID) {
+            Ok(self.token_program_2022)
+        } else if mint.is_owned_by(&token_program::ID) {
+            Ok(self.token_program)
+        } else {
+            Err(AnchorError::AccountOwnedByWrongProgram.into())
+        }
+    }
+
+    /// Return the token amounts of `lp_token_amount` rounded in `round_direction`
+    fn trading_tokens(
+        &self,
+        pool: &PoolState,
+        lp_token_amount: u64,
+        round_direction: RoundDirection,
+    ) -> Result<TradingTokenResult, ProgramError> {
+        let (vault_0, vault_1) = pool
+            .vault_amount_without_fee(token_amount(self.token_0_vault)?, token_amount(self.token_1_vault)?)
+            .ok_or(CpmmError::InsufficientVault)?;
+        let result = lp_tokens_to_trading_tokens(
+            u128::from(lp_token_amount),
+            u128::from(pool.lp_supply),
+            u128::from(vault_0),
+            u128::from(vault_1),
+            round_direction,
+        )
+        .ok_or(CpmmError::MathOverflow)?;
+        if result.token_0_amount == 0 || result.token_1_amount == 0 {
+            return Err(CpmmError::ZeroTradingTokens.into());
+        }
+        Ok(result)
+    }
+}
+
+pub(crate) fn deposit(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    lp_token_amount: u64,
+    maximum_token_0_amount: u64,
+    maximum_token_1_amount: u64,
+) -> ProgramResult {
+    let (accounts, pool) = LiquidityAccounts::load(program_id, accounts)?;
+    if lp_token_amount == 0 {
+        return Err(CpmmError::InvalidInput.into());
+    }
+    if !pool.is_deposit_enabled() {
+        return Err(CpmmError::NotApproved.into());
+    }
+
+    let result = accounts.trading_tokens(&pool, lp_token_amount, RoundDirection::Ceiling)?;
+    let token_0_amount = u64::try_from(result.token_0_amount).map_err(|_| CpmmError::MathOverflow)?;
+    let token_1_amount = u64::try_from(result.token_1_amount).map_err(|_| CpmmError::MathOverflow)?;
+    if token_0_amount > maximum_token_0_amount || token_1_amount > maximum_token_1_amount {
+        return Err(CpmmError::ExceededSlippage.into());
+    }
+
+    transfer_checked(
+        accounts.token_program_of(accounts.vault_0_mint)?,
+        accounts.token_0_account,
+        accounts.vault_0_mint,
+        accounts.token_0_vault,
+        accounts.owner,
+        token_0_amount,
+        &[],
+    )?;
+    transfer_checked(
+        accounts.token_program_of(accounts.vault_1_mint)?,
+        accounts.token_1_account,
+        accounts.vault_1_mint,
+        accounts.token_1_vault,
+        accounts.owner,
+        token_1_amount,
+        &[],
+    )?;
+
+    let lp_supply = pool.lp_supply.checked_add(lp_token_amount).ok_or(CpmmError::MathOverflow)?;
+    update_pool_state(accounts.pool_state, |pool| {
+        pool.lp_supply = lp_supply;
+        Ok(())
+    })?;
+
+    with_authority_signer(&pool, |signers| {
+        mint_to(
+            accounts.token_program,
+            accounts.lp_mint,
+            accounts.owner_lp_token,
+            accounts.authority,
+            lp_token_amount,
+            signers,
+        )
+    })
+}
+
+pub(crate) fn withdraw(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    lp_token_amount: u64,
+    minimum_token_0_amount: u64,
+    minimum_token_1_amount: u64,
+) -> ProgramResult {
+    let (accounts, pool) = LiquidityAccounts::load(program_id, accounts)?;
+    if lp_token_amount == 0 {
+        return Err(CpmmError::InvalidInput.into());
+    }
+    if !pool.is_withdraw_enabled() {
+        return Err(CpmmError::NotApproved.into());
+    }
+
+    let result = accounts.trading_tokens(&pool, lp_token_amount, RoundDirection::Floor)?;
+    let token_0_amount = u64::try_from(result.token_0_amount).map_err(|_| CpmmError::MathOverflow)?;
+    let token_1_amount = u64::try_from(result.token_1_amount).map_err(|_| CpmmError::MathOverflow)?;
+    if token_0_amount < minimum_token_0_amount || token_1_amount < minimum_token_1_amount {
+        return Err(CpmmError::ExceededSlippage.into());
+    }
+
+    let lp_supply = pool.lp_supply.checked_sub(lp_token_amount).ok_or(CpmmError::MathOverflow)?;
+    update_pool_state(accounts.pool_state, |pool| {
+        pool.lp_supply = lp_supply;
+        Ok(())
+    })?;
+
+    burn(
+        accounts.token_program,
+        accounts.owner_lp_token,
+        accounts.lp_mint,
+        accounts.owner,
+        lp_token_amount,
+        &[],
+    )?;
+
+    with_authority_signer(&pool, |signers| {
+        transfer_checked(
+            accounts.token_program_of(accounts.vault_0_mint)?,
+            accounts.token_0_vault,
+            accounts.vault_0_mint,
+            accounts.token_0_account,
+            accounts.authority,
+            token_0_amount,
+            signers,
+        )?;
+        transfer_checked(
+            accounts.token_program_of(accounts.vault_1_mint)?,
+            accounts.token_1_vault,
+            accounts.vault_1_mint,
+            accounts.token_1_account,
+            accounts.authority,
+            token_1_amount,
+            signers,
+        )
+    })
+}
@@ -0,0 +1,287 @@
+//! A minimal stand-in for the CPMM program, for integration tests without the Raydium `.so`.
+//!
+//! `process_instruction` accepts the discriminators and account orders of every CPMM
+//! instruction, keeps the real account layouts and applies the constant product math of
+//! `crate::curve`. Tokens are moved with `TransferChecked`, `MintTo` and `Burn` CPIs into the
+//! token programs.
+//! Failures are reported with the codes of the program, `CpmmError` or the `AnchorError` of
+//! the violated account constraint, and the authority PDA is checked against the mock program
+//! id, on-chain and off.
+//!
+//! It is not the program:
+//! - Instructions do not create accounts. The pool, observation, config and permission
+//!   accounts must be allocated to their size and owned by the mock, the vaults must be token
+//!   accounts and the lp mint a mint, both with the authority PDA as authority, and the
+//!   creator token accounts of `CollectCreatorFee` must exist.
+//! - The create pool fee is not charged, Token-2022 transfer fees are not deducted and no
+//!   events are emitted.
+//! - Fee rates out of range fail with `CpmmError::InvalidInput`, the program panics on them,
+//!   and `recent_epoch` is not updated.
+//!
+//! Off-chain the clock is read from `set_unix_timestamp`. To load it in an SVM, build a
+//! `cdylib` declaring `pinocchio::entrypoint!(pinocchio_raydium_cpmm_cpi::mock_program::process_instruction);`.
+
+mod admin;
+mod fees;
+mod initialize;
+mod liquidity;
+mod swap;
+mod token;
+
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvars::clock::Clock,
+    ProgramResult,
+};
+
+use crate::{
+    instructions::CpmmInstruction,
+    pda::{create_program_address, try_find_program_address},
+    states::{account::ProgramAccount, PoolState},
+    AUTH_SEED,
+};
+
+/// The Anchor framework errors raised by the account constraints of the program
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AnchorError {
+    /// A seeds constraint was violated
+    ConstraintSeeds = 2006,
+    /// A mint token program constraint was violated
+    ConstraintMintTokenProgram = 2022,
+    /// A `require_keys_neq` expression was violated
+    RequireKeysNeqViolated = 2504,
+    /// The given account is owned by a different program than expected
+    AccountOwnedByWrongProgram = 3007,
+    /// Program ID was not as expected
+    InvalidProgramId = 3008,
+}
+
+impl From<AnchorError> for ProgramError {
+    fn from(e: AnchorError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+/// Process a CPMM instruction sent to the mock program `program_id`
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = CpmmInstruction::decode(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        CpmmInstruction::Initialize {
+            init_amount_0,
+            init_amount_1,
+            open_time,
+        } => initialize::process(program_id, accounts, init_amount_0, init_amount_1, open_time),
+        CpmmInstruction::Deposit {
+            lp_token_amount,
+            maximum_token_0_amount,
+            maximum_token_1_amount,
+        } => liquidity::deposit(
+            program_id,
+            accounts,
+            lp_token_amount,
+            maximum_token_0_amount,
+            maximum_token_1_amount,
+        ),
+        CpmmInstruction::Withdraw {
+            lp_token_amount,
+            minimum_token_0_amount,
+            minimum_token_1_amount,
+        } => liquidity::withdraw(
+            program_id,
+            accounts,
+            lp_token_amount,
+            minimum_token_0_amount,
+            minimum_token_1_amount,
+        ),
+        CpmmInstruction::SwapBaseInput {
+            amount_in,
+            minimum_amount_out,
+        } => swap::base_input(program_id, accounts, amount_in, minimum_amount_out),
+        CpmmInstruction::SwapBaseOutput {
+            max_amount_in,
+            amount_out,
+        } => swap::base_output(program_id, accounts, max_amount_in, amount_out),
+        CpmmInstruction::CreateAmmConfig {
+            index,
+            trade_fee_rate,
+            protocol_fee_rate,
+            fund_fee_rate,
+            create_pool_fee,
+            creator_fee_rate,
+        } => admin::create_amm_config(
+            program_id,
+            accounts,
+            index,
+            [trade_fee_rate, protocol_fee_rate, fund_fee_rate, creator_fee_rate],
+            create_pool_fee,
+        ),
+        CpmmInstruction::UpdateAmmConfig { param, value } => {
+            admin::update_amm_config(program_id, accounts, param, value)
+        }
+        CpmmInstruction::UpdatePoolStatus { status } => {
+            admin::update_pool_status(program_id, accounts, status)
+        }
+        CpmmInstruction::CollectProtocolFee {
+            amount_0_requested,
+            amount_1_requested,
+        } => fees::collect_protocol_fee(program_id, accounts, amount_0_requested, amount_1_requested),
+        CpmmInstruction::CollectFundFee {
+            amount_0_requested,
+            amount_1_requested,
+        } => fees::collect_fund_fee(program_id, accounts, amount_0_requested, amount_1_requested),
+        CpmmInstruction::InitializeWithPermission {
+            init_amount_0,
+            init_amount_1,
+            open_time,
+            creator_fee_on,
+        } => initialize::process_with_permission(
+            program_id,
+            accounts,
+            init_amount_0,
+            init_amount_1,
+            open_time,
+            creator_fee_on,
+        ),
+        CpmmInstruction::CollectCreatorFee => fees::collect_creator_fee(program_id, accounts),
+        CpmmInstruction::CreatePermissionPda => admin::create_permission_pda(program_id, accounts),
+        CpmmInstruction::ClosePermissionPda => admin::close_permission_pda(program_id, accounts),
+    }
+}
+
+/// Return the `Clock` sysvar
+#[cfg(target_os = "solana")]
+#[inline(always)]
+fn clock() -> Result<Clock, ProgramError> {
+    use pinocchio::sysvars::Sysvar;
+    Clock::get()
+}
+
+/// Return a `Clock` at the unix timestamp set with `set_unix_timestamp`, the `Clock` sysvar
+/// is only available on-chain.
+#[cfg(not(target_os = "solana"))]
+#[inline(always)]
+fn clock() -> Result<Clock, ProgramError> {
+    Ok(Clock {
+        slot: 0,
+        epoch_start_timestamp: 0,
+        epoch: 0,
+        leader_schedule_epoch: 0,
+        unix_timestamp: UNIX_TIMESTAMP.load(core::sync::atomic::Ordering::Relaxed) as i64,
+    })
+}
+
+/// Return the current unix timestamp, as the program reads it from the `Clock`
+#[inline(always)]
+fn block_timestamp() -> Result<u64, ProgramError> {
+    Ok(clock()?.unix_timestamp as u64)
+}
+
+#[cfg(not(target_os = "solana"))]
+static UNIX_TIMESTAMP: core::sync::atomic::AtomicU64 = core::sync::atomic::AtomicU64::new(0);
+
+/// Set the unix timestamp seen by the mock program off-chain, `0` by default
+#[cfg(not(target_os = "solana"))]
+pub fn set_unix_timestamp(unix_timestamp: u64) {
+    UNIX_TIMESTAMP.store(unix_timestamp, core::sync::atomic::Ordering::Relaxed);
+}
+
+/// Check that `account` is a `T` owned by the mock program and return a copy of it
+#[inline]
+fn load<T: ProgramAccount>(program_id: &Pubkey, account: &AccountInfo) -> Result<T, ProgramError> {
+    if !account.is_owned_by(program_id) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+    crate::states::account::deserialize(&account.try_borrow_data()?)
+}
+
+/// Check that the pool account keys match the ones passed to the instruction
+#[inline]
+fn check_pool_key(key: &Pubkey, expected: &Pubkey, error: crate::error::CpmmError) -> ProgramResult {
+    if key != expected {
+        return Err(error.into());
+    }
+    Ok(())
+}
+
+/// Check that `authority` is the pool vault and lp mint authority of the mock program
+#[inline]
+fn check_authority(program_id: &Pubkey, authority: &AccountInfo, pool_state: &PoolState) -> ProgramResult {
+    match create_program_address(&[AUTH_SEED.as_bytes(), &[pool_state.auth_bump]], program_id) {
+        Ok(address) if address == *authority.key() => Ok(()),
+        _ => Err(AnchorError::ConstraintSeeds.into()),
+    }
+}
+
+/// Run `f` with the signer of the pool vault and lp mint authority
+#[inline]
+fn with_authority_signer<R>(pool_state: &PoolState, f: impl FnOnce(&[Signer]) -> R) -> R {
+    let bump = [pool_state.auth_bump];
+    let seeds = [Seed::from(AUTH_SEED.as_bytes()), Seed::from(&bump)];
+    f(&[Signer::from(&seeds)])
+}
+
+/// Update `pool_state` in place
+#[inline]
+fn update_pool_state(
+    account: &AccountInfo,
+    f: impl FnOnce(&mut PoolState) -> ProgramResult,
+) -> ProgramResult {
+    update_account(account, f)
+}
+
+/// Update the `T` of `account` in place
+#[inline]
+fn update_account<T: ProgramAccount>(
+    account: &AccountInfo,
+    f: impl FnOnce(&mut T) -> ProgramResult,
+) -> ProgramResult {
+    let mut data = account.try_borrow_mut_data()?;
+    let data = data.get_mut(8..8 + T::LEN).ok_or(ProgramError::InvalidAccountData)?;
+    // SAFETY: `data` holds `T::LEN` bytes, `T` is packed and has no invalid bit pattern.
+    f(unsafe { &mut *(data.as_mut_ptr() as *mut T) })
+}
+
+/// Check that `account` is owned by the mock program, allocated to `len` and not initialized
+fn check_uninitialized(program_id: &Pubkey, account: &AccountInfo, len: usize) -> ProgramResult {
+    if !account.is_owned_by(program_id) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+    let data = account.try_borrow_data()?;
+    if data.len() != len + 8 {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if data[..8] != [0; 8] {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+    Ok(())
+}
+
+/// Initialize the uninitialized PDA `account` of `seeds` as a `T` set by `f`, which takes the
+/// bump of the address
+fn initialize_account<T: ProgramAccount>(
+    program_id: &Pubkey,
+    account: &AccountInfo,
+    seeds: &[&[u8]],
+    f: impl FnOnce(&mut T, u8),
+) -> ProgramResult {
+    let bump = match try_find_program_address(seeds, program_id) {
+        Some((address, bump)) if address == *account.key() => bump,
+        _ => return Err(AnchorError::ConstraintSeeds.into()),
+    };
+    check_uninitialized(program_id, account, T::LEN)?;
+    account.try_borrow_mut_data()?[..8].copy_from_slice(T::DISCRIMINATOR);
+    update_account(account, |state| {
+        f(state, bump);
+        Ok(())
+    })
+}
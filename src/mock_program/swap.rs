@@ -0,0 +1,268 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    ProgramResult,
+};
+
+use crate::{
+    curve::{swap_base_input_quote, swap_base_output_quote, SwapQuote, TradeDirection},
+    error::CpmmError,
+    states::{
+        AmmConfig, Observation, ObservationState, PoolState, OBSERVATION_NUM,
+        OBSERVATION_UPDATE_DURATION_DEFAULT, Q32,
+    },
+};
+
+use super::{
+    check_authority, check_pool_key, clock, load,
+    token::{token_amount, transfer_checked},
+    update_pool_state, with_authority_signer,
+};
+
+/// Accounts of `SwapBaseInput` and `SwapBaseOutput`, in instruction order
+struct SwapAccounts<'a> {
+    payer: &'a AccountInfo,
+    authority: &'a AccountInfo,
+    pool_state: &'a AccountInfo,
+    input_token_account: &'a AccountInfo,
+    output_token_account: &'a AccountInfo,
+    input_vault: &'a AccountInfo,
+    output_vault: &'a AccountInfo,
+    input_token_program: &'a AccountInfo,
+    output_token_program: &'a AccountInfo,
+    input_token_mint: &'a AccountInfo,
+    output_token_mint: &'a AccountInfo,
+    observation_state: &'a AccountInfo,
+}
+
+/// A swap checked against the pool, ready to be quoted
+struct Swap<'a> {
+    accounts: SwapAccounts<'a>,
+    pool: PoolState,
+    amm_config: AmmConfig,
+    direction: TradeDirection,
+    vault_0_amount: u64,
+    vault_1_amount: u64,
+    block_timestamp: u64,
+}
+
+impl<'a> Swap<'a> {
+    /// Parse the accounts and check them against the `PoolState`
+    fn load(program_id: &Pubkey, accounts: &'a [AccountInfo]) -> Result<Self, ProgramError> {
+        let [
+            payer, authority, amm_config, pool_state, input_token_account, output_token_account,
+            input_vault, output_vault, input_token_program, output_token_program, input_token_mint,
+            output_token_mint, observation_state, ..
+        ] = accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if !payer.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let pool = load::<PoolState>(program_id, pool_state)?;
+        let amm_config_state = load::<AmmConfig>(program_id, amm_config)?;
+        check_authority(program_id, authority, &pool)?;
+        check_pool_key(amm_config.key(), &pool.amm_config, CpmmError::InvalidInput)?;
+        check_pool_key(observation_state.key(), &pool.observation_key, CpmmError::InvalidInput)?;
+
+        let direction = if *input_vault.key() == pool.token_0_vault && *output_vault.key() == pool.token_1_vault {
+            TradeDirection::ZeroForOne
+        } else if *input_vault.key() == pool.token_1_vault && *output_vault.key() == pool.token_0_vault {
+            TradeDirection::OneForZero
+        } else {
+            return Err(CpmmError::InvalidVault.into());
+        };
+        let (input_mint, output_mint) = match direction {
+            TradeDirection::ZeroForOne => (pool.token_0_mint, pool.token_1_mint),
+            TradeDirection::OneForZero => (pool.token_1_mint, pool.token_0_mint),
+        };
+        check_pool_key(input_token_mint.key(), &input_mint, CpmmError::InvalidInput)?;
+        check_pool_key(output_token_mint.key(), &output_mint, CpmmError::InvalidInput)?;
+
+        let clock = clock()?;
+        if !pool.can_swap_now(&clock) {
+            return Err(CpmmError::NotApproved.into());
+        }
+        let block_timestamp = clock.unix_timestamp as u64;
+
+        let (vault_0_amount, vault_1_amount) = match direction {
+            TradeDirection::ZeroForOne => (token_amount(input_vault)?, token_amount(output_vault)?),
+            TradeDirection::OneForZero => (token_amount(output_vault)?, token_amount(input_vault)?),
+        };
+
+        Ok(Self {
+            accounts: SwapAccounts {
+                payer,
+                authority,
+                pool_state,
+                input_token_account,
+                output_token_account,
+                input_vault,
+                output_vault,
+                input_token_program,
+                output_token_program,
+                input_token_mint,
+                output_token_mint,
+                observation_state,
+            },
+            pool,
+            amm_config: amm_config_state,
+            direction,
+            vault_0_amount,
+            vault_1_amount,
+            block_timestamp,
+        })
+    }
+
+    /// Accrue the fees, move the tokens and record the pre-swap price
+    fn execute(self, quote: SwapQuote) -> ProgramResult {
+        let (vault_0, vault_1) = self
+            .pool
+            .vault_amount_without_fee(self.vault_0_amount, self.vault_1_amount)
+            .ok_or(CpmmError::InsufficientVault)?;
+
+        let zero_for_one = self.direction == TradeDirection::ZeroForOne;
+        let creator_fee_on_token_0 = quote.creator_fee_on_input == zero_for_one;
+        update_pool_state(self.accounts.pool_state, |pool| {
+            if zero_for_one {
+                pool.protocol_fees_token_0 = accrue(pool.protocol_fees_token_0, quote.protocol_fee)?;
+                pool.fund_fees_token_0 = accrue(pool.fund_fees_token_0, quote.fund_fee)?;
+            } else {
+                pool.protocol_fees_token_1 = accrue(pool.protocol_fees_token_1, quote.protocol_fee)?;
+                pool.fund_fees_token_1 = accrue(pool.fund_fees_token_1, quote.fund_fee)?;
+            }
+            if creator_fee_on_token_0 {
+                pool.creator_fees_token_0 = accrue(pool.creator_fees_token_0, quote.creator_fee)?;
+            } else {
+                pool.creator_fees_token_1 = accrue(pool.creator_fees_token_1, quote.creator_fee)?;
+            }
+            Ok(())
+        })?;
+
+        let accounts = &self.accounts;
+        transfer_checked(
+            accounts.input_token_program,
+            accounts.input_token_account,
+            accounts.input_token_mint,
+            accounts.input_vault,
+            accounts.payer,
+            quote.amount_in,
+            &[],
+        )?;
+        with_authority_signer(&self.pool, |signers| {
+            transfer_checked(
+                accounts.output_token_program,
+                accounts.output_vault,
+                accounts.output_token_mint,
+                accounts.output_token_account,
+                accounts.authority,
+                quote.amount_out,
+                signers,
+            )
+        })?;
+
+        let token_0_price_x32 = u128::from(vault_1) * Q32 / u128::from(vault_0);
+        let token_1_price_x32 = u128::from(vault_0) * Q32 / u128::from(vault_1);
+        let mut data = accounts.observation_state.try_borrow_mut_data()?;
+        if data.len() != ObservationState::LEN + 8 || &data[..8] != ObservationState::DISCRIMINATOR {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        // SAFETY: the account length and discriminator were checked.
+        let observation_state = unsafe { ObservationState::from_bytes_mut(&mut data[8..]) };
+        update_observation(observation_state, self.block_timestamp, token_0_price_x32, token_1_price_x32);
+        Ok(())
+    }
+}
+
+pub(crate) fn base_input(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount_in: u64,
+    minimum_amount_out: u64,
+) -> ProgramResult {
+    let swap = Swap::load(program_id, accounts)?;
+    let quote = swap_base_input_quote(
+        &swap.pool,
+        &swap.amm_config,
+        swap.direction,
+        swap.vault_0_amount,
+        swap.vault_1_amount,
+        amount_in,
+    )
+    .ok_or(CpmmError::MathOverflow)?;
+    if quote.amount_out == 0 {
+        return Err(CpmmError::ZeroTradingTokens.into());
+    }
+    if quote.amount_out < minimum_amount_out {
+        return Err(CpmmError::ExceededSlippage.into());
+    }
+    swap.execute(quote)
+}
+
+pub(crate) fn base_output(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    max_amount_in: u64,
+    amount_out: u64,
+) -> ProgramResult {
+    let swap = Swap::load(program_id, accounts)?;
+    let quote = swap_base_output_quote(
+        &swap.pool,
+        &swap.amm_config,
+        swap.direction,
+        swap.vault_0_amount,
+        swap.vault_1_amount,
+        amount_out,
+    )
+    .ok_or(CpmmError::MathOverflow)?;
+    if quote.amount_in > max_amount_in {
+        return Err(CpmmError::ExceededSlippage.into());
+    }
+    swap.execute(quote)
+}
+
+/// Add `fee` to the `fees` accumulated by the pool
+#[inline(always)]
+fn accrue(fees: u64, fee: u64) -> Result<u64, ProgramError> {
+    fees.checked_add(fee).ok_or_else(|| CpmmError::MathOverflow.into())
+}
+
+/// Record the prices at `block_timestamp`, at most once per
+/// `OBSERVATION_UPDATE_DURATION_DEFAULT` seconds, as the program does
+fn update_observation(
+    observation_state: &mut ObservationState,
+    block_timestamp: u64,
+    token_0_price_x32: u128,
+    token_1_price_x32: u128,
+) {
+    let index = observation_state.observation_index as usize;
+    if !observation_state.initialized {
+        observation_state.initialized = true;
+        observation_state.observations[index] = Observation {
+            block_timestamp,
+            cumulative_token_0_price_x32: 0,
+            cumulative_token_1_price_x32: 0,
+        };
+        return;
+    }
+
+    let last = observation_state.observations[index];
+    let delta_time = block_timestamp.saturating_sub(last.block_timestamp);
+    if delta_time < OBSERVATION_UPDATE_DURATION_DEFAULT {
+        return;
+    }
+    let next_index = (index + 1) % OBSERVATION_NUM;
+    observation_state.observations[next_index] = Observation {
+        block_timestamp,
+        cumulative_token_0_price_x32: last
+            .cumulative_token_0_price_x32
+            .wrapping_add(token_0_price_x32.wrapping_mul(u128::from(delta_time))),
+        cumulative_token_1_price_x32: last
+            .cumulative_token_1_price_x32
+            .wrapping_add(token_1_price_x32.wrapping_mul(u128::from(delta_time))),
+    };
+    observation_state.observation_index = next_index as u16;
+}
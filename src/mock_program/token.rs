@@ -0,0 +1,139 @@
+use core::slice::from_raw_parts;
+
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{AccountMeta, Instruction, Signer},
+    program_error::ProgramError,
+    ProgramResult,
+};
+
+use crate::{write_bytes, UNINIT_BYTE};
+
+/// Invoke `instruction`, through the `test_utils::svm` harness when one is installed on the
+/// current thread
+#[inline(always)]
+fn invoke_signed<const ACCOUNTS: usize>(
+    instruction: &Instruction,
+    account_infos: &[&AccountInfo; ACCOUNTS],
+    signers: &[Signer],
+) -> ProgramResult {
+    #[cfg(all(feature = "test-utils", not(target_os = "solana")))]
+    if let Some(result) =
+        crate::test_utils::svm::invoke_signed(instruction, account_infos, signers)
+    {
+        return result;
+    }
+    pinocchio::program::invoke_signed(instruction, account_infos, signers)
+}
+
+/// Return the amount of an SPL token account
+#[inline(always)]
+pub(crate) fn token_amount(account: &AccountInfo) -> Result<u64, ProgramError> {
+    let data = account.try_borrow_data()?;
+    let bytes = data.get(64..72).ok_or(ProgramError::InvalidAccountData)?;
+    Ok(u64::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+/// Return the decimals of an SPL mint
+#[inline(always)]
+pub(crate) fn mint_decimals(mint: &AccountInfo) -> Result<u8, ProgramError> {
+    let data = mint.try_borrow_data()?;
+    data.get(44).copied().ok_or(ProgramError::InvalidAccountData)
+}
+
+/// `TransferChecked` of SPL Token and Token-2022
+pub(crate) fn transfer_checked(
+    token_program: &AccountInfo,
+    from: &AccountInfo,
+    mint: &AccountInfo,
+    to: &AccountInfo,
+    authority: &AccountInfo,
+    amount: u64,
+    signers: &[Signer],
+) -> ProgramResult {
+    let account_metas = [
+        AccountMeta::writable(from.key()),
+        AccountMeta::readonly(mint.key()),
+        AccountMeta::writable(to.key()),
+        AccountMeta::readonly_signer(authority.key()),
+    ];
+
+    // Instruction data layout:
+    // -  [0]: instruction discriminator (1 byte, u8)
+    // -  [1..9]: amount (8 bytes, u64)
+    // -  [9]: decimals (1 byte, u8)
+    let mut instruction_data = [UNINIT_BYTE; 10];
+    write_bytes(&mut instruction_data[0..1], &[12]);
+    write_bytes(&mut instruction_data[1..9], &amount.to_le_bytes());
+    write_bytes(&mut instruction_data[9..10], &[mint_decimals(mint)?]);
+
+    let instruction = Instruction {
+        program_id: token_program.key(),
+        accounts: &account_metas,
+        data: unsafe { from_raw_parts(instruction_data.as_ptr() as _, 10) },
+    };
+
+    invoke_signed(&instruction, &[from, mint, to, authority], signers)
+}
+
+/// `MintTo` of SPL Token
+pub(crate) fn mint_to(
+    token_program: &AccountInfo,
+    mint: &AccountInfo,
+    to: &AccountInfo,
+    authority: &AccountInfo,
+    amount: u64,
+    signers: &[Signer],
+) -> ProgramResult {
+    let account_metas = [
+        AccountMeta::writable(mint.key()),
+        AccountMeta::writable(to.key()),
+        AccountMeta::readonly_signer(authority.key()),
+    ];
+
+    // Instruction data layout:
+    // -  [0]: instruction discriminator (1 byte, u8)
+    // -  [1..9]: amount (8 bytes, u64)
+    let mut instruction_data = [UNINIT_BYTE; 9];
+    write_bytes(&mut instruction_data[0..1], &[7]);
+    write_bytes(&mut instruction_data[1..9], &amount.to_le_bytes());
+
+    let instruction = Instruction {
+        program_id: token_program.key(),
+        accounts: &account_metas,
+        data: unsafe { from_raw_parts(instruction_data.as_ptr() as _, 9) },
+    };
+
+    invoke_signed(&instruction, &[mint, to, authority], signers)
+}
+
+/// `Burn` of SPL Token
+pub(crate) fn burn(
+    token_program: &AccountInfo,
+    from: &AccountInfo,
+    mint: &AccountInfo,
+    authority: &AccountInfo,
+    amount: u64,
+    signers: &[Signer],
+) -> ProgramResult {
+    let account_metas = [
+        AccountMeta::writable(from.key()),
+        AccountMeta::writable(mint.key()),
+        AccountMeta::readonly_signer(authority.key()),
+    ];
+
+    // Instruction data layout:
+    // -  [0]: instruction discriminator (1 byte, u8)
+    // -  [1..9]: amount (8 bytes, u64)
+    let mut instruction_data = [UNINIT_BYTE; 9];
+    write_bytes(&mut instruction_data[0..1], &[8]);
+    write_bytes(&mut instruction_data[1..9], &amount.to_le_bytes());
+
+    let instruction = Instruction {
+        program_id: token_program.key(),
+        accounts: &account_metas,
+        data: unsafe { from_raw_parts(instruction_data.as_ptr() as _, 9) },
+    };
+
+    invoke_signed(&instruction, &[from, mint, authority], signers)
+}
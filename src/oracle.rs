@@ -0,0 +1,226 @@
+//! Time-weighted average prices read from the `ObservationState` of a pool.
+//!
+//! The program records, at most every `OBSERVATION_UPDATE_DURATION_DEFAULT` seconds, the
+//! cumulative Q32.32 price of token_0 (in token_1) and token_1 (in token_0) before each swap.
+//! The average price over a window is the difference of two cumulative prices divided by the
+//! time elapsed between them. Cumulative prices wrap around on overflow, as in the program.
+
+use crate::{
+    error::OracleError,
+    states::{Observation, ObservationState},
+};
+
+/// Time-weighted average prices, Q32.32
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TwapPrice {
+    /// Average price of token_0 expressed in token_1, Q32.32
+    pub token_0_price_x32: u128,
+    /// Average price of token_1 expressed in token_0, Q32.32
+    pub token_1_price_x32: u128,
+    /// Timestamp of the observation starting the window
+    pub start_timestamp: u64,
+    /// Timestamp of the observation ending the window, the latest observation
+    pub end_timestamp: u64,
+}
+
+/// Compute the time-weighted average prices over at least the last `window` seconds of
+/// observations.
+///
+/// The window ends at the latest observation and starts at the newest observation recorded at
+/// least `window` seconds before it, so the averaged period may be slightly longer than
+/// `window`, see `TwapPrice::start_timestamp`.
+///
+/// Fails with `OracleError::StaleObservation` if the latest observation is more than
+/// `max_staleness` seconds older than `now`, and with `OracleError::InsufficientHistory` if
+/// no observation is old enough to start the window.
+pub fn twap(
+    observation_state: &ObservationState,
+    now: u64,
+    window: u64,
+    max_staleness: u64,
+) -> Result<TwapPrice, OracleError> {
+    if window == 0 {
+        return Err(OracleError::InvalidWindow);
+    }
+    if !observation_state.initialized {
+        return Err(OracleError::NotInitialized);
+    }
+
+    let end = observation_state.latest().ok_or(OracleError::NotInitialized)?;
+    let end_timestamp = end.block_timestamp;
+    if now.saturating_sub(end_timestamp) > max_staleness {
+        return Err(OracleError::StaleObservation);
+    }
+
+    let target = end_timestamp
+        .checked_sub(window)
+        .ok_or(OracleError::InsufficientHistory)?;
+    let start = observation_state
+        .at_or_before(target)
+        .ok_or(OracleError::InsufficientHistory)?;
+
+    twap_between(&start, &end)
+}
+
+/// Compute the time-weighted average prices between two observations, `start` older than `end`
+pub fn twap_between(start: &Observation, end: &Observation) -> Result<TwapPrice, OracleError> {
+    let start_timestamp = start.block_timestamp;
+    let end_timestamp = end.block_timestamp;
+    let elapsed = end_timestamp
+        .checked_sub(start_timestamp)
+        .filter(|elapsed| *elapsed > 0)
+        .ok_or(OracleError::InvalidWindow)?;
+
+    let token_0_price_x32 = { end.cumulative_token_0_price_x32 }
+        .wrapping_sub(start.cumulative_token_0_price_x32)
+        .checked_div(u128::from(elapsed))
+        .ok_or(OracleError::MathOverflow)?;
+    let token_1_price_x32 = { end.cumulative_token_1_price_x32 }
+        .wrapping_sub(start.cumulative_token_1_price_x32)
+        .checked_div(u128::from(elapsed))
+        .ok_or(OracleError::MathOverflow)?;
+
+    Ok(TwapPrice {
+        token_0_price_x32,
+        token_1_price_x32,
+        start_timestamp,
+        end_timestamp,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::ObservationStateBuilder;
+
+    /// 3.0 and 0.25, Q32.32
+    const PRICE_0: u128 = 3 << 32;
+    const PRICE_1: u128 = 1 << 30;
+
+    /// Return the observation state of 130 observations 15 seconds apart from 1_000 at constant
+    /// prices, the ring holds the last 100 of them from 1_450 to 2_935. The cumulative prices
+    /// wrap around at the 100th observation, recorded at 2_500.
+    fn observation_state() -> ObservationState {
+        ObservationStateBuilder::new()
+            .observation(1_000, (PRICE_0 * 1_500).wrapping_neg(), (PRICE_1 * 1_500).wrapping_neg())
+            .constant_prices(1_015, 15, 129, PRICE_0, PRICE_1)
+            .build()
+    }
+
+    fn price(start_timestamp: u64) -> TwapPrice {
+        TwapPrice {
+            token_0_price_x32: PRICE_0,
+            token_1_price_x32: PRICE_1,
+            start_timestamp,
+            end_timestamp: 2_935,
+        }
+    }
+
+    #[test]
+    fn window_ending_at_the_latest_observation() {
+        let observation_state = observation_state();
+        assert_eq!({ observation_state.observation_index }, 29);
+
+        assert_eq!(twap(&observation_state, 2_940, 150, 60), Ok(price(2_785)));
+    }
+
+    #[test]
+    fn window_starts_at_the_newest_observation_old_enough() {
+        // 2_835 falls between the observations of 2_830 and 2_845
+        let observation_state = observation_state();
+        assert_eq!(twap(&observation_state, 2_940, 100, 60), Ok(price(2_830)));
+        assert_eq!(twap(&observation_state, 2_940, 1, 60), Ok(price(2_920)));
+    }
+
+    #[test]
+    fn window_across_the_wrapped_cumulative_prices() {
+        let observation_state = observation_state();
+        let start = observation_state.at_or_before(2_335).unwrap();
+        let end = observation_state.latest().unwrap();
+        assert!({ end.cumulative_token_0_price_x32 } < { start.cumulative_token_0_price_x32 });
+        assert!({ end.cumulative_token_1_price_x32 } < { start.cumulative_token_1_price_x32 });
+
+        assert_eq!(twap(&observation_state, 2_940, 600, 60), Ok(price(2_335)));
+    }
+
+    #[test]
+    fn window_back_to_the_oldest_observation() {
+        let observation_state = observation_state();
+        assert_eq!(twap(&observation_state, 2_940, 1_485, 60), Ok(price(1_450)));
+        assert_eq!(
+            twap(&observation_state, 2_940, 1_486, 60),
+            Err(OracleError::InsufficientHistory)
+        );
+        assert_eq!(
+            twap(&observation_state, 2_940, 2_936, 60),
+            Err(OracleError::InsufficientHistory)
+        );
+    }
+
+    #[test]
+    fn stale_observations() {
+        let observation_state = observation_state();
+        assert_eq!(twap(&observation_state, 2_995, 150, 60), Ok(price(2_785)));
+        assert_eq!(
+            twap(&observation_state, 2_996, 150, 60),
+            Err(OracleError::StaleObservation)
+        );
+        // a clock behind the latest observation is not stale
+        assert_eq!(twap(&observation_state, 2_000, 150, 0), Ok(price(2_785)));
+    }
+
+    #[test]
+    fn empty_window_or_observation_state() {
+        let observation_state = observation_state();
+        assert_eq!(twap(&observation_state, 2_940, 0, 60), Err(OracleError::InvalidWindow));
+
+        let empty = ObservationStateBuilder::new().build();
+        assert_eq!(twap(&empty, 2_940, 150, 60), Err(OracleError::NotInitialized));
+    }
+
+    #[test]
+    fn twap_between_rounds_down() {
+        let start = Observation {
+            block_timestamp: 100,
+            cumulative_token_0_price_x32: u128::MAX,
+            cumulative_token_1_price_x32: 7,
+        };
+        // 10 * PRICE_0 + 9 and 10 * PRICE_1 + 9 over 10 seconds, across the wrap for token 0
+        let end = Observation {
+            block_timestamp: 110,
+            cumulative_token_0_price_x32: 10 * PRICE_0 + 8,
+            cumulative_token_1_price_x32: 10 * PRICE_1 + 16,
+        };
+
+        assert_eq!(
+            twap_between(&start, &end),
+            Ok(TwapPrice {
+                token_0_price_x32: PRICE_0,
+                token_1_price_x32: PRICE_1,
+                start_timestamp: 100,
+                end_timestamp: 110,
+            })
+        );
+    }
+
+    #[test]
+    fn twap_between_requires_elapsed_time() {
+        let observation_state = observation_state();
+        let start = observation_state.oldest().unwrap();
+        let end = observation_state.latest().unwrap();
+
+        assert_eq!(twap_between(&end, &start), Err(OracleError::InvalidWindow));
+        assert_eq!(twap_between(&end, &end), Err(OracleError::InvalidWindow));
+    }
+
+    #[test]
+    fn error_codes_start_at_500() {
+        use pinocchio::program_error::ProgramError;
+
+        assert_eq!(
+            ProgramError::from(OracleError::NotInitialized),
+            ProgramError::Custom(500)
+        );
+        assert_eq!(ProgramError::from(OracleError::MathOverflow), ProgramError::Custom(504));
+    }
+}
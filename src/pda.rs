@@ -0,0 +1,270 @@
+//! Program derived addresses of the CPMM program.
+//!
+//! The `find_*` functions search for the canonical bump, which is expensive on-chain. The
+//! `create_*` functions take a known bump. The `verify_*` functions compare an address
+//! against the one derived from a bump stored in the program accounts, at the cost of a
+//! single `create_program_address`, or against the address recorded by the `PoolState`
+//! when the program does not store the bump.
+//!
+//! Derivation uses the Solana syscalls on-chain and an equivalent host implementation
+//! elsewhere, so the helpers and the account validation built on them also work in tests
+//! and off-chain tools.
+
+use pinocchio::{program_error::ProgramError, pubkey::Pubkey};
+
+use crate::{
+    states::{
+        AmmConfig, PoolState, AMM_CONFIG_SEED, OBSERVATION_SEED, PERMISSION_SEED,
+        POOL_LP_MINT_SEED, POOL_SEED, POOL_VAULT_SEED,
+    },
+    program_id, AUTH_SEED,
+};
+
+/// Create a program derived address from `seeds`, including the bump, and `program_id`.
+///
+/// Same as `pinocchio::pubkey::create_program_address`, which is only available on-chain.
+#[inline]
+pub fn create_program_address(seeds: &[&[u8]], program_id: &Pubkey) -> Result<Pubkey, ProgramError> {
+    #[cfg(target_os = "solana")]
+    {
+        pinocchio::pubkey::create_program_address(seeds, program_id)
+    }
+    #[cfg(not(target_os = "solana"))]
+    {
+        host::create_program_address(seeds, program_id)
+    }
+}
+
+/// Find the program derived address of `seeds` and `program_id` with the canonical bump,
+/// `None` if no bump gives an address off the curve.
+///
+/// Same as `pinocchio::pubkey::try_find_program_address`, which is only available on-chain.
+#[inline]
+pub fn try_find_program_address(seeds: &[&[u8]], program_id: &Pubkey) -> Option<(Pubkey, u8)> {
+    #[cfg(target_os = "solana")]
+    {
+        pinocchio::pubkey::try_find_program_address(seeds, program_id)
+    }
+    #[cfg(not(target_os = "solana"))]
+    {
+        host::try_find_program_address(seeds, program_id)
+    }
+}
+
+#[inline]
+fn find_program_address(seeds: &[&[u8]], program_id: &Pubkey) -> (Pubkey, u8) {
+    try_find_program_address(seeds, program_id).expect("Unable to find a viable program address bump seed")
+}
+
+#[cfg(not(target_os = "solana"))]
+mod host {
+    use curve25519_dalek::edwards::CompressedEdwardsY;
+    use pinocchio::{
+        program_error::ProgramError,
+        pubkey::{Pubkey, MAX_SEEDS, MAX_SEED_LEN},
+    };
+    use sha2::{Digest, Sha256};
+
+    const PDA_MARKER: &[u8] = b"ProgramDerivedAddress";
+
+    pub fn create_program_address(seeds: &[&[u8]], program_id: &Pubkey) -> Result<Pubkey, ProgramError> {
+        if seeds.len() > MAX_SEEDS || seeds.iter().any(|seed| seed.len() > MAX_SEED_LEN) {
+            return Err(ProgramError::MaxSeedLengthExceeded);
+        }
+        let mut hasher = Sha256::new();
+        for seed in seeds {
+            hasher.update(seed);
+        }
+        hasher.update(program_id);
+        hasher.update(PDA_MARKER);
+        let address: Pubkey = hasher.finalize().into();
+        // A program derived address must not have a private key, i.e. lie off the curve
+        if CompressedEdwardsY(address).decompress().is_some() {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        Ok(address)
+    }
+
+    pub fn try_find_program_address(seeds: &[&[u8]], program_id: &Pubkey) -> Option<(Pubkey, u8)> {
+        if seeds.len() >= MAX_SEEDS {
+            return None;
+        }
+        for bump in (0..=u8::MAX).rev() {
+            let bump_seed = [bump];
+            let mut seeds_with_bump: [&[u8]; MAX_SEEDS] = [&[]; MAX_SEEDS];
+            seeds_with_bump[..seeds.len()].copy_from_slice(seeds);
+            seeds_with_bump[seeds.len()] = &bump_seed;
+            match create_program_address(&seeds_with_bump[..=seeds.len()], program_id) {
+                Ok(address) => return Some((address, bump)),
+                Err(ProgramError::InvalidSeeds) => continue,
+                Err(_) => return None,
+            }
+        }
+        None
+    }
+}
+
+/// Find the pool vault and lp mint authority
+#[inline]
+pub fn find_authority_address() -> (Pubkey, u8) {
+    find_program_address(&[AUTH_SEED.as_bytes()], program_id())
+}
+
+/// Find the amm config of the given index
+#[inline]
+pub fn find_amm_config_address(index: u16) -> (Pubkey, u8) {
+    find_program_address(&[AMM_CONFIG_SEED.as_bytes(), &index.to_be_bytes()], program_id())
+}
+
+/// Find the pool of the given config and mints, `token_0_mint` must be smaller than `token_1_mint`
+#[inline]
+pub fn find_pool_address(amm_config: &Pubkey, token_0_mint: &Pubkey, token_1_mint: &Pubkey) -> (Pubkey, u8) {
+    find_program_address(
+        &[POOL_SEED.as_bytes(), amm_config, token_0_mint, token_1_mint],
+        program_id(),
+    )
+}
+
+/// Find the lp mint of the given pool
+#[inline]
+pub fn find_lp_mint_address(pool_state: &Pubkey) -> (Pubkey, u8) {
+    find_program_address(&[POOL_LP_MINT_SEED.as_bytes(), pool_state], program_id())
+}
+
+/// Find the vault of the given pool holding `token_mint`
+#[inline]
+pub fn find_vault_address(pool_state: &Pubkey, token_mint: &Pubkey) -> (Pubkey, u8) {
+    find_program_address(&[POOL_VAULT_SEED.as_bytes(), pool_state, token_mint], program_id())
+}
+
+/// Find the observation account of the given pool
+#[inline]
+pub fn find_observation_address(pool_state: &Pubkey) -> (Pubkey, u8) {
+    find_program_address(&[OBSERVATION_SEED.as_bytes(), pool_state], program_id())
+}
+
+/// Find the permission account of the given authority
+#[inline]
+pub fn find_permission_address(permission_authority: &Pubkey) -> (Pubkey, u8) {
+    find_program_address(&[PERMISSION_SEED.as_bytes(), permission_authority], program_id())
+}
+
+/// Create the pool vault and lp mint authority from its bump
+#[inline]
+pub fn create_authority_address(bump: u8) -> Result<Pubkey, ProgramError> {
+    create_program_address(&[AUTH_SEED.as_bytes(), &[bump]], program_id())
+}
+
+/// Create the amm config of the given index from its bump
+#[inline]
+pub fn create_amm_config_address(index: u16, bump: u8) -> Result<Pubkey, ProgramError> {
+    create_program_address(&[AMM_CONFIG_SEED.as_bytes(), &index.to_be_bytes(), &[bump]], program_id())
+}
+
+/// Create the pool of the given config and mints from its bump
+#[inline]
+pub fn create_pool_address(
+    amm_config: &Pubkey,
+    token_0_mint: &Pubkey,
+    token_1_mint: &Pubkey,
+    bump: u8,
+) -> Result<Pubkey, ProgramError> {
+    create_program_address(
+        &[POOL_SEED.as_bytes(), amm_config, token_0_mint, token_1_mint, &[bump]],
+        program_id(),
+    )
+}
+
+/// Create the lp mint of the given pool from its bump
+#[inline]
+pub fn create_lp_mint_address(pool_state: &Pubkey, bump: u8) -> Result<Pubkey, ProgramError> {
+    create_program_address(&[POOL_LP_MINT_SEED.as_bytes(), pool_state, &[bump]], program_id())
+}
+
+/// Create the vault of the given pool holding `token_mint` from its bump
+#[inline]
+pub fn create_vault_address(pool_state: &Pubkey, token_mint: &Pubkey, bump: u8) -> Result<Pubkey, ProgramError> {
+    create_program_address(&[POOL_VAULT_SEED.as_bytes(), pool_state, token_mint, &[bump]], program_id())
+}
+
+/// Create the observation account of the given pool from its bump
+#[inline]
+pub fn create_observation_address(pool_state: &Pubkey, bump: u8) -> Result<Pubkey, ProgramError> {
+    create_program_address(&[OBSERVATION_SEED.as_bytes(), pool_state, &[bump]], program_id())
+}
+
+/// Create the permission account of the given authority from its bump
+#[inline]
+pub fn create_permission_address(permission_authority: &Pubkey, bump: u8) -> Result<Pubkey, ProgramError> {
+    create_program_address(&[PERMISSION_SEED.as_bytes(), permission_authority, &[bump]], program_id())
+}
+
+#[inline(always)]
+fn check_address(address: &Pubkey, expected: Result<Pubkey, ProgramError>) -> Result<(), ProgramError> {
+    match expected {
+        Ok(expected) if &expected == address => Ok(()),
+        _ => Err(ProgramError::InvalidSeeds),
+    }
+}
+
+/// Verify `address` is the authority of the pool, using `PoolState::auth_bump`
+#[inline]
+pub fn verify_authority(address: &Pubkey, pool_state: &PoolState) -> Result<(), ProgramError> {
+    check_address(address, create_authority_address(pool_state.auth_bump))
+}
+
+/// Verify `address` is the amm config account of `amm_config`, using `AmmConfig::bump`
+#[inline]
+pub fn verify_amm_config(address: &Pubkey, amm_config: &AmmConfig) -> Result<(), ProgramError> {
+    check_address(address, create_amm_config_address(amm_config.index, amm_config.bump))
+}
+
+/// Verify `address` is the pool of the given config and mints.
+///
+/// The pool bump is not stored by the program, it must be provided by the caller,
+/// e.g. as instruction data.
+#[inline]
+pub fn verify_pool(
+    address: &Pubkey,
+    amm_config: &Pubkey,
+    token_0_mint: &Pubkey,
+    token_1_mint: &Pubkey,
+    bump: u8,
+) -> Result<(), ProgramError> {
+    check_address(address, create_pool_address(amm_config, token_0_mint, token_1_mint, bump))
+}
+
+/// Verify `address` is the lp mint of `pool_state`, using the mint recorded by the pool
+#[inline]
+pub fn verify_lp_mint(address: &Pubkey, pool_state: &PoolState) -> Result<(), ProgramError> {
+    if &pool_state.lp_mint != address {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    Ok(())
+}
+
+/// Verify `address` is the vault of `pool_state` holding `token_mint`, using the vaults
+/// recorded by the pool
+#[inline]
+pub fn verify_vault(address: &Pubkey, pool_state: &PoolState, token_mint: &Pubkey) -> Result<(), ProgramError> {
+    let vault = if &pool_state.token_0_mint == token_mint {
+        pool_state.token_0_vault
+    } else if &pool_state.token_1_mint == token_mint {
+        pool_state.token_1_vault
+    } else {
+        return Err(ProgramError::InvalidSeeds);
+    };
+    if &vault != address {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    Ok(())
+}
+
+/// Verify `address` is the observation account of `pool_state`, using the key recorded by the pool
+#[inline]
+pub fn verify_observation(address: &Pubkey, pool_state: &PoolState) -> Result<(), ProgramError> {
+    if &pool_state.observation_key != address {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    Ok(())
+}
@@ -0,0 +1,105 @@
+use pinocchio::{
+    account_info::{AccountInfo, Ref},
+    program_error::ProgramError,
+};
+
+use crate::program_id;
+
+/// Length of the Anchor discriminator prefixing every account
+pub const DISCRIMINATOR_LEN: usize = 8;
+
+/// An account of the CPMM program, read zero-copy after its Anchor discriminator.
+///
+/// Every account type goes through the same owner, exact size and discriminator checks,
+/// so an account of one type can never be read as another.
+///
+/// # Safety
+///
+/// Implementors must be `#[repr(C, packed)]` structs of exactly `LEN` bytes mirroring the
+/// program's account layout.
+pub unsafe trait ProgramAccount: Sized + Copy {
+    /// The length of the account data, without the discriminator
+    const LEN: usize;
+
+    /// Anchor-compatible discriminator
+    const DISCRIMINATOR: &'static [u8];
+}
+
+/// Check the account is owned by the CPMM program
+#[inline(always)]
+pub fn check_owner(account_info: &AccountInfo) -> Result<(), ProgramError> {
+    if !account_info.is_owned_by(program_id()) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+    Ok(())
+}
+
+/// Check `data`, discriminator included, has the exact size and the discriminator of `T`
+#[inline(always)]
+pub fn check_data<T: ProgramAccount>(data: &[u8]) -> Result<(), ProgramError> {
+    if data.len() != T::LEN + DISCRIMINATOR_LEN {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if &data[..DISCRIMINATOR_LEN] != T::DISCRIMINATOR {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(())
+}
+
+/// Check the owner, exact size and discriminator of an account of type `T`
+#[inline]
+pub fn check_account<T: ProgramAccount>(account_info: &AccountInfo) -> Result<(), ProgramError> {
+    check_owner(account_info)?;
+    check_data::<T>(&account_info.try_borrow_data()?)
+}
+
+/// Return a `T` from the given account info, after the owner, size and discriminator checks,
+/// safe borrowing the account data.
+#[inline]
+pub fn load<T: ProgramAccount>(account_info: &AccountInfo) -> Result<Ref<'_, T>, ProgramError> {
+    check_owner(account_info)?;
+    let data = account_info.try_borrow_data()?;
+    check_data::<T>(&data)?;
+    Ok(Ref::map(data, |data| unsafe { cast(&data[DISCRIMINATOR_LEN..]) }))
+}
+
+/// Return a `T` from the given account info, after the owner, size and discriminator checks,
+/// without the borrow check.
+///
+/// # Safety
+///
+/// The caller must ensure that it is safe to borrow the account data – e.g., there are
+/// no mutable borrows of the account data.
+#[inline]
+pub unsafe fn load_unchecked<T: ProgramAccount>(account_info: &AccountInfo) -> Result<&T, ProgramError> {
+    check_owner(account_info)?;
+    let data = account_info.borrow_data_unchecked();
+    check_data::<T>(data)?;
+    Ok(cast(&data[DISCRIMINATOR_LEN..]))
+}
+
+/// Return a copy of the `T` in `data`, discriminator included, after the size and
+/// discriminator checks.
+#[inline]
+pub fn deserialize<T: ProgramAccount>(data: &[u8]) -> Result<T, ProgramError> {
+    check_data::<T>(data)?;
+    Ok(unsafe { *cast(&data[DISCRIMINATOR_LEN..]) })
+}
+
+/// Return a copy of the `T` in `data`, discriminator included, without checking the
+/// discriminator.
+#[inline]
+pub fn deserialize_unchecked<T: ProgramAccount>(data: &[u8]) -> Result<T, ProgramError> {
+    if data.len() < T::LEN + DISCRIMINATOR_LEN {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(unsafe { *cast(&data[DISCRIMINATOR_LEN..]) })
+}
+
+/// # Safety
+///
+/// `bytes` must hold at least `T::LEN` bytes.
+#[inline(always)]
+unsafe fn cast<T: ProgramAccount>(bytes: &[u8]) -> &T {
+    &*(bytes.as_ptr() as *const T)
+}
@@ -1,14 +1,15 @@
 use pinocchio::{
-    account_info::AccountInfo,
+    account_info::{AccountInfo, Ref},
     program_error::ProgramError,
     pubkey::Pubkey,
 };
-use borsh::{BorshDeserialize, BorshSerialize};
 
-use crate::ID;
+use crate::{curve::FEE_RATE_DENOMINATOR_VALUE, program_id};
+use super::{account::{self, ProgramAccount}, LayoutVersion};
 
 /// Holds the current owner of the factory
-#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq)]
+#[repr(C, packed)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub struct AmmConfig {
     /// Bump to identify PDA
     pub bump: u8,
@@ -28,58 +29,38 @@ pub struct AmmConfig {
     pub protocol_owner: Pubkey,
     /// Address of the fund fee owner
     pub fund_owner: Pubkey,
+    /// The pool creator fee, denominated in hundredths of a bip (10^-6)
+    pub creator_fee_rate: u64,
     /// padding
-    pub padding: [u64; 16],
-}
-
-impl Default for AmmConfig {
-    fn default() -> Self {
-        Self {
-            bump: 0,
-            disable_create_pool: false,
-            index: 0,
-            trade_fee_rate: 0,
-            protocol_fee_rate: 0,
-            fund_fee_rate: 0,
-            create_pool_fee: 0,
-            protocol_owner: Pubkey::default(),
-            fund_owner: Pubkey::default(),
-            padding: [0u64; 16],
-        }
-    }
+    pub padding: [u64; 15],
 }
 
 impl AmmConfig {
-    /// The length of the `AmmConfig` account data (including 8-byte discriminator).
-    pub const LEN: usize = 8 + 1 + 1 + 2 + 8 + 8 + 8 + 8 + 32 + 32 + (8 * 16);
+    /// The length of the `AmmConfig` layout, without the 8-byte discriminator, as the `LEN`
+    /// of the other states. See `ACCOUNT_LEN` for the length of the account data.
+    pub const LEN: usize = core::mem::size_of::<AmmConfig>();
 
-    /// The 8-byte discriminator for AmmConfig accounts (matches Anchor's discriminator)
+    /// The length of the `AmmConfig` account data, including the 8-byte discriminator.
+    ///
+    /// `LEN` had this value before it was aligned with the other states.
+    pub const ACCOUNT_LEN: usize = account::DISCRIMINATOR_LEN + Self::LEN;
+
+    /// Anchor-compatible discriminator
     pub const DISCRIMINATOR: &'static [u8] = &[218, 244, 33, 104, 203, 203, 43, 111];
 
     /// Return a `AmmConfig` from the given account info.
     ///
-    /// This method performs owner and length validation on `AccountInfo`, safe borrowing
-    /// the account data and deserializing using Borsh.
+    /// This method performs owner, length and discriminator validation on `AccountInfo`,
+    /// safe borrowing the account data.
     #[inline]
-    pub fn from_account_info(account_info: &AccountInfo) -> Result<AmmConfig, ProgramError> {
-        if !account_info.is_owned_by(&ID) {
-            return Err(ProgramError::InvalidAccountOwner);
-        }
-        
-        let data = account_info.try_borrow_data()?;
-        if data.len() < 8 {
-            return Err(ProgramError::InvalidAccountData);
-        }
-        
-        // Skip the 8-byte discriminator
-        AmmConfig::try_from_slice(&data[8..])
-            .map_err(|_| ProgramError::InvalidAccountData)
+    pub fn from_account_info(account_info: &AccountInfo) -> Result<Ref<'_, AmmConfig>, ProgramError> {
+        account::load(account_info)
     }
 
     /// Return a `AmmConfig` from the given account info.
     ///
-    /// This method performs owner and length validation on `AccountInfo`, but does not
-    /// perform the borrow check. Uses Borsh deserialization.
+    /// This method performs owner, length and discriminator validation on `AccountInfo`,
+    /// but does not perform the borrow check.
     ///
     /// # Safety
     ///
@@ -88,48 +69,51 @@ impl AmmConfig {
     #[inline]
     pub unsafe fn from_account_info_unchecked(
         account_info: &AccountInfo,
-    ) -> Result<AmmConfig, ProgramError> {
-        if account_info.owner() != &ID {
-            return Err(ProgramError::InvalidAccountOwner);
-        }
-        
-        let data = account_info.borrow_data_unchecked();
-        if data.len() < 8 {
-            return Err(ProgramError::InvalidAccountData);
-        }
-        
-        // Skip the 8-byte discriminator
-        AmmConfig::try_from_slice(&data[8..])
-            .map_err(|_| ProgramError::InvalidAccountData)
+    ) -> Result<&Self, ProgramError> {
+        account::load_unchecked(account_info)
+    }
+
+    /// Return a `AmmConfig` from the given bytes.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that `bytes` contains a valid representation of `AmmConfig`.
+    #[inline(always)]
+    pub unsafe fn from_bytes(bytes: &[u8]) -> &Self {
+        &*(bytes.as_ptr() as *const AmmConfig)
     }
 
-    /// Return a `AmmConfig` from the given bytes using Borsh deserialization.
+    /// Return a mutable `AmmConfig` from the given bytes.
     ///
     /// # Safety
     ///
-    /// The caller must ensure that `bytes` contains a valid Borsh-serialized representation of `AmmConfig`.
+    /// The caller must ensure that `bytes` contains a valid representation of `AmmConfig`.
+    #[inline(always)]
+    pub unsafe fn from_bytes_mut(bytes: &mut [u8]) -> &mut Self {
+        &mut *(bytes.as_mut_ptr() as *mut AmmConfig)
+    }
+
+    /// Guess the layout revision the config account was written with.
+    ///
+    /// Best effort: both revisions share the same size and discriminator, `creator_fee_rate`
+    /// reuses the first padding word of the legacy layout. A config is reported as
+    /// `LayoutVersion::CreatorFee` when that word is a valid fee rate other than zero.
     #[inline]
-    pub fn from_bytes(bytes: &[u8]) -> Result<AmmConfig, ProgramError> {
-        AmmConfig::try_from_slice(bytes)
-            .map_err(|_| ProgramError::InvalidAccountData)
+    pub fn guess_layout_version(&self) -> LayoutVersion {
+        let creator_fee_rate = self.creator_fee_rate;
+        if creator_fee_rate != 0 && creator_fee_rate <= FEE_RATE_DENOMINATOR_VALUE {
+            LayoutVersion::CreatorFee
+        } else {
+            LayoutVersion::Legacy
+        }
     }
 
     pub fn try_deserialize(buf: &mut &[u8]) -> Result<Self, ProgramError> {
-        if buf.len() < Self::DISCRIMINATOR.len() {
-            return Err(ProgramError::InvalidAccountData);
-        }
-        
-        let given_disc = &buf[..Self::DISCRIMINATOR.len()];
-        if Self::DISCRIMINATOR != given_disc {
-            return Err(ProgramError::InvalidAccountData);
-        }
-        
-        Self::try_deserialize_unchecked(buf)
+        account::deserialize(buf)
     }
 
     pub fn try_deserialize_unchecked(buf: &mut &[u8]) -> Result<Self, ProgramError> {
-        let mut data: &[u8] = &buf[Self::DISCRIMINATOR.len()..];
-        BorshDeserialize::deserialize(&mut data).map_err(|_| ProgramError::InvalidAccountData)
+        account::deserialize_unchecked(buf)
     }
 
     pub fn discriminator() -> &'static [u8] {
@@ -137,10 +121,51 @@ impl AmmConfig {
     }
 
     pub fn owner() -> Pubkey {
-        ID
+        *program_id()
     }
 
     pub fn size() -> usize {
-        Self::LEN
+        Self::ACCOUNT_LEN
     }
-} 
\ No newline at end of file
+}
+
+#[cfg(feature = "borsh")]
+impl borsh::BorshSerialize for AmmConfig {
+    fn serialize<W: borsh::io::Write>(&self, writer: &mut W) -> borsh::io::Result<()> {
+        { self.bump }.serialize(writer)?;
+        { self.disable_create_pool }.serialize(writer)?;
+        { self.index }.serialize(writer)?;
+        { self.trade_fee_rate }.serialize(writer)?;
+        { self.protocol_fee_rate }.serialize(writer)?;
+        { self.fund_fee_rate }.serialize(writer)?;
+        { self.create_pool_fee }.serialize(writer)?;
+        { self.protocol_owner }.serialize(writer)?;
+        { self.fund_owner }.serialize(writer)?;
+        { self.creator_fee_rate }.serialize(writer)?;
+        { self.padding }.serialize(writer)
+    }
+}
+
+#[cfg(feature = "borsh")]
+impl borsh::BorshDeserialize for AmmConfig {
+    fn deserialize_reader<R: borsh::io::Read>(reader: &mut R) -> borsh::io::Result<Self> {
+        Ok(AmmConfig {
+            bump: borsh::BorshDeserialize::deserialize_reader(reader)?,
+            disable_create_pool: borsh::BorshDeserialize::deserialize_reader(reader)?,
+            index: borsh::BorshDeserialize::deserialize_reader(reader)?,
+            trade_fee_rate: borsh::BorshDeserialize::deserialize_reader(reader)?,
+            protocol_fee_rate: borsh::BorshDeserialize::deserialize_reader(reader)?,
+            fund_fee_rate: borsh::BorshDeserialize::deserialize_reader(reader)?,
+            create_pool_fee: borsh::BorshDeserialize::deserialize_reader(reader)?,
+            protocol_owner: borsh::BorshDeserialize::deserialize_reader(reader)?,
+            fund_owner: borsh::BorshDeserialize::deserialize_reader(reader)?,
+            creator_fee_rate: borsh::BorshDeserialize::deserialize_reader(reader)?,
+            padding: borsh::BorshDeserialize::deserialize_reader(reader)?,
+        })
+    }
+}
+
+unsafe impl ProgramAccount for AmmConfig {
+    const LEN: usize = AmmConfig::LEN;
+    const DISCRIMINATOR: &'static [u8] = AmmConfig::DISCRIMINATOR;
+}
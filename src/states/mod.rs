@@ -1,12 +1,16 @@
+pub mod account;
 pub mod amm_config;
 pub mod pool_state;
 pub mod observation;
 pub mod observation_state;
+pub mod permission;
 
+pub use account::ProgramAccount;
 pub use amm_config::*;
 pub use pool_state::*;
 pub use observation::*;
 pub use observation_state::*;
+pub use permission::*;
 
 /// Seed to derive account address and signature
 pub const POOL_SEED: &str = "pool";
@@ -14,6 +18,7 @@ pub const POOL_LP_MINT_SEED: &str = "pool_lp_mint";
 pub const POOL_VAULT_SEED: &str = "pool_vault";
 pub const OBSERVATION_SEED: &str = "observation";
 pub const AMM_CONFIG_SEED: &str = "amm_config";
+pub const PERMISSION_SEED: &str = "permission";
 
 // Number of ObservationState element
 pub const OBSERVATION_NUM: usize = 100;
@@ -21,14 +26,27 @@ pub const OBSERVATION_UPDATE_DURATION_DEFAULT: u64 = 15;
 
 pub const Q32: u128 = (u32::MAX as u128) + 1; // 2^32
 
+/// Bit of `PoolState::status` controlling an operation of the pool
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum PoolStatusBitIndex {
     Deposit,
     Withdraw,
     Swap,
 }
 
-#[derive(PartialEq, Eq)]
+/// State of an operation of the pool, see `PoolState::get_status_flag_by_bit`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum PoolStatusBitFlag {
     Enable,
     Disable,
-}
\ No newline at end of file
+}
+
+/// Account layout revision of the CPMM program, see `PoolState::guess_layout_version` and
+/// `AmmConfig::guess_layout_version`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LayoutVersion {
+    /// Layout of the original release, without creator fees
+    Legacy,
+    /// Layout of the releases supporting creator fees and permissioned pool creation
+    CreatorFee,
+}
@@ -4,8 +4,8 @@ use pinocchio::{
     pubkey::Pubkey,
 };
 
-use crate::ID;
-use super::{Observation, OBSERVATION_NUM};
+use crate::program_id;
+use super::{account::{self, ProgramAccount}, Observation, OBSERVATION_NUM};
 
 #[repr(C, packed)]
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -30,22 +30,17 @@ impl ObservationState {
 
     /// Return a `ObservationState` from the given account info.
     ///
-    /// This method performs owner and length validation on `AccountInfo`, safe borrowing
-    /// the account data.
+    /// This method performs owner, length and discriminator validation on `AccountInfo`,
+    /// safe borrowing the account data.
     #[inline]
-    pub fn from_account_info(account_info: &AccountInfo) -> Result<Ref<ObservationState>, ProgramError> {
-        if !account_info.is_owned_by(&ID) {
-            return Err(ProgramError::InvalidAccountOwner);
-        }
-        Ok(Ref::map(account_info.try_borrow_data()?, |data| unsafe {
-            Self::from_bytes(&data[8..])
-        }))
+    pub fn from_account_info(account_info: &AccountInfo) -> Result<Ref<'_, ObservationState>, ProgramError> {
+        account::load(account_info)
     }
 
     /// Return a `ObservationState` from the given account info.
     ///
-    /// This method performs owner and length validation on `AccountInfo`, but does not
-    /// perform the borrow check.
+    /// This method performs owner, length and discriminator validation on `AccountInfo`,
+    /// but does not perform the borrow check.
     ///
     /// # Safety
     ///
@@ -55,13 +50,7 @@ impl ObservationState {
     pub unsafe fn from_account_info_unchecked(
         account_info: &AccountInfo,
     ) -> Result<&Self, ProgramError> {
-        if account_info.data_len() != Self::LEN + 8 {
-            return Err(ProgramError::InvalidAccountData);
-        }
-        if account_info.owner() != &ID {
-            return Err(ProgramError::InvalidAccountOwner);
-        }
-        Ok(Self::from_bytes(&account_info.borrow_data_unchecked()[8..]))
+        account::load_unchecked(account_info)
     }
 
     /// Return a `ObservationState` from the given bytes.
@@ -74,35 +63,61 @@ impl ObservationState {
         &*(bytes.as_ptr() as *const ObservationState)
     }
 
+    /// Return a mutable `ObservationState` from the given bytes.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that `bytes` contains a valid representation of `ObservationState`.
+    #[inline(always)]
+    pub unsafe fn from_bytes_mut(bytes: &mut [u8]) -> &mut Self {
+        &mut *(bytes.as_mut_ptr() as *mut ObservationState)
+    }
+
     #[inline(always)]
     pub fn is_initialized(&self) -> bool {
         self.initialized
     }
 
-
-    pub fn try_deserialize(buf: &mut &[u8]) -> Result<Self, ProgramError> {
-        if buf.len() < Self::DISCRIMINATOR.len() {
-            return Err(ProgramError::InvalidAccountData);
-        }
-        
-        let given_disc = &buf[..Self::DISCRIMINATOR.len()];
-        if Self::DISCRIMINATOR != given_disc {
-            return Err(ProgramError::InvalidAccountData);
+    /// Return an iterator over the initialized observations, by copy, in chronological order.
+    ///
+    /// The ring is walked from the slot after `observation_index`, the oldest one once the ring
+    /// is full, and uninitialized slots (zero timestamp) are skipped.
+    #[inline]
+    pub fn iter(&self) -> ObservationIter<'_> {
+        ObservationIter {
+            observation_state: self,
+            front: 0,
+            back: OBSERVATION_NUM,
         }
-        
-        Self::try_deserialize_unchecked(buf)
     }
 
+    /// Return the most recent observation, if any
+    #[inline]
+    pub fn latest(&self) -> Option<Observation> {
+        self.iter().next_back()
+    }
+
+    /// Return the oldest observation still in the ring, if any
+    #[inline]
+    pub fn oldest(&self) -> Option<Observation> {
+        self.iter().next()
+    }
+
+    /// Return the most recent observation recorded at or before `timestamp`, if any
+    #[inline]
+    pub fn at_or_before(&self, timestamp: u64) -> Option<Observation> {
+        self.iter()
+            .rev()
+            .find(|observation| observation.block_timestamp <= timestamp)
+    }
+
+
+    pub fn try_deserialize(buf: &mut &[u8]) -> Result<Self, ProgramError> {
+        account::deserialize(buf)
+    }
 
     pub fn try_deserialize_unchecked(buf: &mut &[u8]) -> Result<Self, ProgramError> {
-        let data: &[u8] = &buf[Self::DISCRIMINATOR.len()..];
-        if data.len() < Self::LEN {
-            return Err(ProgramError::InvalidAccountData);
-        }
-        
-        unsafe {
-            Ok(*Self::from_bytes(data))
-        }
+        account::deserialize_unchecked(buf)
     }
 
     pub fn discriminator() -> &'static [u8] {
@@ -110,10 +125,175 @@ impl ObservationState {
     }
 
     pub fn owner() -> Pubkey {
-        ID
+        *program_id()
     }
 
     pub fn size() -> usize {
         Self::LEN + 8
     }
-} 
\ No newline at end of file
+} 
+/// Iterator over the observations of an `ObservationState`, see `ObservationState::iter`
+#[derive(Clone)]
+pub struct ObservationIter<'a> {
+    observation_state: &'a ObservationState,
+    /// Position of the next observation from the front, relative to the oldest slot
+    front: usize,
+    /// Position after the next observation from the back, relative to the oldest slot
+    back: usize,
+}
+
+impl ObservationIter<'_> {
+    #[inline(always)]
+    fn get(&self, position: usize) -> Observation {
+        let index = (self.observation_state.observation_index as usize + 1 + position) % OBSERVATION_NUM;
+        self.observation_state.observations[index]
+    }
+}
+
+impl Iterator for ObservationIter<'_> {
+    type Item = Observation;
+
+    fn next(&mut self) -> Option<Observation> {
+        while self.front < self.back {
+            let observation = self.get(self.front);
+            self.front += 1;
+            if observation.block_timestamp != 0 {
+                return Some(observation);
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.back - self.front))
+    }
+}
+
+impl DoubleEndedIterator for ObservationIter<'_> {
+    fn next_back(&mut self) -> Option<Observation> {
+        while self.front < self.back {
+            self.back -= 1;
+            let observation = self.get(self.back);
+            if observation.block_timestamp != 0 {
+                return Some(observation);
+            }
+        }
+        None
+    }
+}
+
+unsafe impl ProgramAccount for ObservationState {
+    const LEN: usize = ObservationState::LEN;
+    const DISCRIMINATOR: &'static [u8] = ObservationState::DISCRIMINATOR;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+
+    use super::*;
+    use crate::test_utils::ObservationStateBuilder;
+
+    /// Return the observation state of `count` observations every `interval` seconds from
+    /// `start`, at prices of 2 and 3
+    fn observation_state(start: u64, interval: u64, count: usize) -> ObservationState {
+        ObservationStateBuilder::new().constant_prices(start, interval, count, 2, 3).build()
+    }
+
+    fn timestamps(observations: impl Iterator<Item = Observation>) -> Vec<u64> {
+        observations.map(|observation| observation.block_timestamp).collect()
+    }
+
+    fn timestamp(observation: Option<Observation>) -> Option<u64> {
+        observation.map(|observation| observation.block_timestamp)
+    }
+
+    #[test]
+    fn empty_ring() {
+        let observation_state = observation_state(0, 0, 0);
+
+        assert!(!observation_state.is_initialized());
+        assert_eq!(observation_state.iter().next(), None);
+        assert_eq!(observation_state.iter().next_back(), None);
+        assert_eq!(observation_state.latest(), None);
+        assert_eq!(observation_state.oldest(), None);
+        assert_eq!(observation_state.at_or_before(u64::MAX), None);
+    }
+
+    #[test]
+    fn partially_filled_ring() {
+        let observation_state = observation_state(10, 10, 3);
+        assert_eq!({ observation_state.observation_index }, 2);
+
+        assert_eq!(timestamps(observation_state.iter()), [10, 20, 30]);
+        assert_eq!(timestamps(observation_state.iter().rev()), [30, 20, 10]);
+        assert_eq!(timestamp(observation_state.latest()), Some(30));
+        assert_eq!(timestamp(observation_state.oldest()), Some(10));
+        let latest = observation_state.latest().unwrap();
+        // 20 seconds at 2 and 3
+        assert_eq!({ latest.cumulative_token_0_price_x32 }, 40);
+        assert_eq!({ latest.cumulative_token_1_price_x32 }, 60);
+    }
+
+    #[test]
+    fn at_or_before_in_a_partially_filled_ring() {
+        let observation_state = observation_state(10, 10, 3);
+
+        assert_eq!(timestamp(observation_state.at_or_before(9)), None);
+        assert_eq!(timestamp(observation_state.at_or_before(10)), Some(10));
+        assert_eq!(timestamp(observation_state.at_or_before(25)), Some(20));
+        assert_eq!(timestamp(observation_state.at_or_before(30)), Some(30));
+        assert_eq!(timestamp(observation_state.at_or_before(u64::MAX)), Some(30));
+    }
+
+    #[test]
+    fn full_ring() {
+        let observation_state = observation_state(1, 1, OBSERVATION_NUM);
+        assert_eq!({ observation_state.observation_index }, 99);
+
+        assert_eq!(timestamps(observation_state.iter()), (1..=100).collect::<Vec<_>>());
+        assert_eq!(timestamp(observation_state.oldest()), Some(1));
+        assert_eq!(timestamp(observation_state.latest()), Some(100));
+    }
+
+    #[test]
+    fn wrapped_ring() {
+        // The last 100 of 105 observations, the oldest in the slot after the latest
+        let observation_state = observation_state(1, 1, 105);
+        assert_eq!({ observation_state.observation_index }, 4);
+        assert_eq!({ observation_state.observations[5].block_timestamp }, 6);
+
+        assert_eq!(timestamps(observation_state.iter()), (6..=105).collect::<Vec<_>>());
+        let reversed: Vec<_> = (6..=105).rev().collect();
+        assert_eq!(timestamps(observation_state.iter().rev()), reversed);
+        assert_eq!(timestamp(observation_state.oldest()), Some(6));
+        assert_eq!(timestamp(observation_state.latest()), Some(105));
+    }
+
+    #[test]
+    fn at_or_before_in_a_wrapped_ring() {
+        let observation_state = observation_state(10, 10, 105);
+
+        // overwritten observations are gone
+        assert_eq!(timestamp(observation_state.at_or_before(50)), None);
+        assert_eq!(timestamp(observation_state.at_or_before(60)), Some(60));
+        // either side of the slot boundary between the latest and the oldest observations
+        assert_eq!(timestamp(observation_state.at_or_before(1_009)), Some(1_000));
+        assert_eq!(timestamp(observation_state.at_or_before(1_050)), Some(1_050));
+        assert_eq!(timestamp(observation_state.at_or_before(555)), Some(550));
+    }
+
+    #[test]
+    fn iterating_from_both_ends() {
+        let observation_state = observation_state(10, 10, 3);
+        let mut iter = observation_state.iter();
+
+        assert_eq!(iter.size_hint(), (0, Some(OBSERVATION_NUM)));
+        assert_eq!(timestamp(iter.next()), Some(10));
+        assert_eq!(timestamp(iter.next_back()), Some(30));
+        assert_eq!(timestamp(iter.next()), Some(20));
+        assert_eq!(iter.next_back(), None);
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.size_hint(), (0, Some(0)));
+    }
+}
@@ -0,0 +1,96 @@
+use pinocchio::{
+    account_info::{AccountInfo, Ref},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use crate::program_id;
+use super::account::{self, ProgramAccount};
+
+/// Grants an authority the right to create pools with `InitializeWithPermission`
+#[repr(C, packed)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Permission {
+    /// authority
+    pub authority: Pubkey,
+    /// padding
+    pub padding: [u64; 30],
+}
+
+impl Permission {
+    /// The length of the `Permission` account data.
+    pub const LEN: usize = core::mem::size_of::<Permission>();
+
+    /// Anchor-compatible discriminator
+    pub const DISCRIMINATOR: &'static [u8] = &[224, 83, 28, 79, 10, 253, 161, 28];
+
+    /// Return a `Permission` from the given account info.
+    ///
+    /// This method performs owner, length and discriminator validation on `AccountInfo`,
+    /// safe borrowing the account data.
+    #[inline]
+    pub fn from_account_info(account_info: &AccountInfo) -> Result<Ref<'_, Permission>, ProgramError> {
+        account::load(account_info)
+    }
+
+    /// Return a `Permission` from the given account info.
+    ///
+    /// This method performs owner, length and discriminator validation on `AccountInfo`,
+    /// but does not perform the borrow check.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that it is safe to borrow the account data – e.g., there are
+    /// no mutable borrows of the account data.
+    #[inline]
+    pub unsafe fn from_account_info_unchecked(
+        account_info: &AccountInfo,
+    ) -> Result<&Self, ProgramError> {
+        account::load_unchecked(account_info)
+    }
+
+    /// Return a `Permission` from the given bytes.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that `bytes` contains a valid representation of `Permission`.
+    #[inline(always)]
+    pub unsafe fn from_bytes(bytes: &[u8]) -> &Self {
+        &*(bytes.as_ptr() as *const Permission)
+    }
+
+    /// Return a mutable `Permission` from the given bytes.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that `bytes` contains a valid representation of `Permission`.
+    #[inline(always)]
+    pub unsafe fn from_bytes_mut(bytes: &mut [u8]) -> &mut Self {
+        &mut *(bytes.as_mut_ptr() as *mut Permission)
+    }
+
+    pub fn try_deserialize(buf: &mut &[u8]) -> Result<Self, ProgramError> {
+        account::deserialize(buf)
+    }
+
+    pub fn try_deserialize_unchecked(buf: &mut &[u8]) -> Result<Self, ProgramError> {
+        account::deserialize_unchecked(buf)
+    }
+
+    pub fn discriminator() -> &'static [u8] {
+        Self::DISCRIMINATOR
+    }
+
+    pub fn owner() -> Pubkey {
+        *program_id()
+    }
+
+    pub fn size() -> usize {
+        Self::LEN + 8
+    }
+}
+
+unsafe impl ProgramAccount for Permission {
+    const LEN: usize = Permission::LEN;
+    const DISCRIMINATOR: &'static [u8] = Permission::DISCRIMINATOR;
+}
@@ -2,9 +2,14 @@ use pinocchio::{
     account_info::{AccountInfo, Ref},
     program_error::ProgramError,
     pubkey::Pubkey,
+    sysvars::clock::Clock,
 };
 
-use crate::ID;
+use crate::{curve::TradeDirection, program_id};
+use super::{
+    account::{self, ProgramAccount},
+    LayoutVersion, PoolStatusBitFlag, PoolStatusBitIndex,
+};
 
 #[repr(C, packed)]
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -57,8 +62,46 @@ pub struct PoolState {
 
     /// The timestamp allowed for swap in the pool.
     pub open_time: u64,
+    /// recent epoch
+    pub recent_epoch: u64,
+
+    /// Creator fee collect mode, see `CreatorFeeOn`
+    /// 0: both token_0 and token_1 can be used as trade fees. It depends on what the input token is when swapping
+    /// 1: only token_0 as trade fee
+    /// 2: only token_1 as trade fee
+    pub creator_fee_on: u8,
+    pub enable_creator_fee: bool,
+    pub padding1: [u8; 6],
+    /// The amounts of token_0 and token_1 that are owed to the pool creator.
+    pub creator_fees_token_0: u64,
+    pub creator_fees_token_1: u64,
     /// padding for future updates
-    pub padding: [u64; 32],
+    pub padding: [u64; 28],
+}
+
+/// Which token the creator fee of a pool is charged in
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CreatorFeeOn {
+    /// Both token_0 and token_1, depending on the input token of the swap
+    BothToken,
+    /// Only token_0
+    OnlyToken0,
+    /// Only token_1
+    OnlyToken1,
+}
+
+impl TryFrom<u8> for CreatorFeeOn {
+    type Error = ProgramError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(CreatorFeeOn::BothToken),
+            1 => Ok(CreatorFeeOn::OnlyToken0),
+            2 => Ok(CreatorFeeOn::OnlyToken1),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
 }
 
 impl PoolState {
@@ -70,25 +113,17 @@ impl PoolState {
 
     /// Return a `PoolState` from the given account info.
     ///
-    /// This method performs owner and length validation on `AccountInfo`, safe borrowing
-    /// the account data.
+    /// This method performs owner, length and discriminator validation on `AccountInfo`,
+    /// safe borrowing the account data.
     #[inline]
-    pub fn from_account_info(account_info: &AccountInfo) -> Result<Ref<PoolState>, ProgramError> {
-        if account_info.data_len() != Self::LEN + 8 {
-            return Err(ProgramError::InvalidAccountData);
-        }
-        if !account_info.is_owned_by(&ID) {
-            return Err(ProgramError::InvalidAccountOwner);
-        }
-        Ok(Ref::map(account_info.try_borrow_data()?, |data| unsafe {
-            Self::from_bytes(&data[8..])
-        }))
+    pub fn from_account_info(account_info: &AccountInfo) -> Result<Ref<'_, PoolState>, ProgramError> {
+        account::load(account_info)
     }
 
     /// Return a `PoolState` from the given account info.
     ///
-    /// This method performs owner and length validation on `AccountInfo`, but does not
-    /// perform the borrow check.
+    /// This method performs owner, length and discriminator validation on `AccountInfo`,
+    /// but does not perform the borrow check.
     ///
     /// # Safety
     ///
@@ -98,10 +133,7 @@ impl PoolState {
     pub unsafe fn from_account_info_unchecked(
         account_info: &AccountInfo,
     ) -> Result<&Self, ProgramError> {
-        if account_info.owner() != &ID {
-            return Err(ProgramError::InvalidAccountOwner);
-        }
-        Ok(Self::from_bytes(&account_info.borrow_data_unchecked()[8..]))
+        account::load_unchecked(account_info)
     }
 
     /// Return a `PoolState` from the given bytes.
@@ -114,39 +146,227 @@ impl PoolState {
         &*(bytes.as_ptr() as *const PoolState)
     }
 
-    pub fn try_deserialize(buf: &mut &[u8]) -> Result<Self, ProgramError> {
-        if buf.len() < Self::DISCRIMINATOR.len() {
-            return Err(ProgramError::InvalidAccountData);
-        }
-        
-        let given_disc = &buf[..Self::DISCRIMINATOR.len()];
-        if Self::DISCRIMINATOR != given_disc {
-            return Err(ProgramError::InvalidAccountData);
+    /// Return a mutable `PoolState` from the given bytes.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that `bytes` contains a valid representation of `PoolState`.
+    #[inline(always)]
+    pub unsafe fn from_bytes_mut(bytes: &mut [u8]) -> &mut Self {
+        &mut *(bytes.as_mut_ptr() as *mut PoolState)
+    }
+
+    /// Guess the layout revision the pool account was written with.
+    ///
+    /// Best effort: both revisions share the same size and discriminator, the creator fee
+    /// fields reuse bytes that are padding in the legacy layout. A pool is reported as
+    /// `LayoutVersion::CreatorFee` when `enable_creator_fee` is set with a valid
+    /// `creator_fee_on`, a legacy pool with those padding bytes set the same way is
+    /// misreported. Pools without creator fees decode to the same values in both layouts.
+    #[inline]
+    pub fn guess_layout_version(&self) -> LayoutVersion {
+        if self.enable_creator_fee && CreatorFeeOn::try_from(self.creator_fee_on).is_ok() {
+            LayoutVersion::CreatorFee
+        } else {
+            LayoutVersion::Legacy
         }
-        
-        Self::try_deserialize_unchecked(buf)
     }
 
-    pub fn try_deserialize_unchecked(buf: &mut &[u8]) -> Result<Self, ProgramError> {
-        let data: &[u8] = &buf[Self::DISCRIMINATOR.len()..];
-        if data.len() < Self::LEN {
-            return Err(ProgramError::InvalidAccountData);
+    /// Return the creator fee mode of the pool.
+    #[inline]
+    pub fn creator_fee_on(&self) -> Result<CreatorFeeOn, ProgramError> {
+        CreatorFeeOn::try_from(self.creator_fee_on)
+    }
+
+    /// Return the vault amounts available to the curve, excluding the protocol,
+    /// fund and creator fees owed by the pool.
+    ///
+    /// Returns `None` if a vault holds less than the fees it owes.
+    #[inline]
+    pub fn vault_amount_without_fee(&self, vault_0: u64, vault_1: u64) -> Option<(u64, u64)> {
+        let fees_token_0 = self
+            .protocol_fees_token_0
+            .checked_add(self.fund_fees_token_0)?
+            .checked_add(self.creator_fees_token_0)?;
+        let fees_token_1 = self
+            .protocol_fees_token_1
+            .checked_add(self.fund_fees_token_1)?
+            .checked_add(self.creator_fees_token_1)?;
+        Some((
+            vault_0.checked_sub(fees_token_0)?,
+            vault_1.checked_sub(fees_token_1)?,
+        ))
+    }
+
+    /// Return the creator fee rate applied to swaps, `0` unless creator fees are enabled.
+    #[inline]
+    pub fn adjust_creator_fee_rate(&self, creator_fee_rate: u64) -> u64 {
+        if self.enable_creator_fee {
+            creator_fee_rate
+        } else {
+            0
         }
-        
-        unsafe {
-            Ok(*Self::from_bytes(data))
+    }
+
+    /// Return whether the creator fee of a swap in `direction` is charged on the input token.
+    #[inline]
+    pub fn is_creator_fee_on_input(&self, direction: TradeDirection) -> Result<bool, ProgramError> {
+        Ok(match (self.creator_fee_on()?, direction) {
+            (CreatorFeeOn::BothToken, _) => true,
+            (CreatorFeeOn::OnlyToken0, TradeDirection::ZeroForOne) => true,
+            (CreatorFeeOn::OnlyToken0, TradeDirection::OneForZero) => false,
+            (CreatorFeeOn::OnlyToken1, TradeDirection::ZeroForOne) => false,
+            (CreatorFeeOn::OnlyToken1, TradeDirection::OneForZero) => true,
+        })
+    }
+
+    /// Return whether the operation controlled by `bit` is enabled, as the program checks it.
+    ///
+    /// A set bit in `status` disables the operation.
+    #[inline]
+    pub fn get_status_by_bit(&self, bit: PoolStatusBitIndex) -> bool {
+        let status = 1u8 << (bit as u8);
+        self.status & status == 0
+    }
+
+    /// Return the state of the operation controlled by `bit`
+    #[inline]
+    pub fn get_status_flag_by_bit(&self, bit: PoolStatusBitIndex) -> PoolStatusBitFlag {
+        if self.get_status_by_bit(bit) {
+            PoolStatusBitFlag::Enable
+        } else {
+            PoolStatusBitFlag::Disable
         }
     }
 
+    #[inline(always)]
+    pub fn is_deposit_enabled(&self) -> bool {
+        self.get_status_by_bit(PoolStatusBitIndex::Deposit)
+    }
+
+    #[inline(always)]
+    pub fn is_withdraw_enabled(&self) -> bool {
+        self.get_status_by_bit(PoolStatusBitIndex::Withdraw)
+    }
+
+    #[inline(always)]
+    pub fn is_swap_enabled(&self) -> bool {
+        self.get_status_by_bit(PoolStatusBitIndex::Swap)
+    }
+
+    /// Return whether the program accepts swaps at the time of `clock`.
+    ///
+    /// Swaps must be enabled and the pool open, i.e. `open_time` reached. A clock before
+    /// the unix epoch never reaches it.
+    #[inline]
+    pub fn can_swap_now(&self, clock: &Clock) -> bool {
+        self.is_swap_enabled()
+            && u64::try_from(clock.unix_timestamp).is_ok_and(|now| now >= self.open_time)
+    }
+
+    pub fn try_deserialize(buf: &mut &[u8]) -> Result<Self, ProgramError> {
+        account::deserialize(buf)
+    }
+
+    pub fn try_deserialize_unchecked(buf: &mut &[u8]) -> Result<Self, ProgramError> {
+        account::deserialize_unchecked(buf)
+    }
+
     pub fn discriminator() -> &'static [u8] {
         Self::DISCRIMINATOR
     }
 
     pub fn owner() -> Pubkey {
-        ID
+        *program_id()
     }
 
     pub fn size() -> usize {
         Self::LEN + 8
     }
-} 
\ No newline at end of file
+}
+
+unsafe impl ProgramAccount for PoolState {
+    const LEN: usize = PoolState::LEN;
+    const DISCRIMINATOR: &'static [u8] = PoolState::DISCRIMINATOR;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::PoolStateBuilder;
+
+    fn clock(unix_timestamp: i64) -> Clock {
+        Clock {
+            slot: 0,
+            epoch_start_timestamp: 0,
+            epoch: 0,
+            leader_schedule_epoch: 0,
+            unix_timestamp,
+        }
+    }
+
+    #[test]
+    fn status_bits_disable_their_operation() {
+        let enabled = |status| {
+            let pool_state = PoolStateBuilder::new().status(status).build();
+            [
+                pool_state.is_deposit_enabled(),
+                pool_state.is_withdraw_enabled(),
+                pool_state.is_swap_enabled(),
+            ]
+        };
+
+        assert_eq!(enabled(0b000), [true, true, true]);
+        assert_eq!(enabled(0b001), [false, true, true]);
+        assert_eq!(enabled(0b010), [true, false, true]);
+        assert_eq!(enabled(0b100), [true, true, false]);
+        assert_eq!(enabled(0b111), [false, false, false]);
+        // bits past the swap bit control nothing
+        assert_eq!(enabled(0b1111_1000), [true, true, true]);
+    }
+
+    #[test]
+    fn status_flags() {
+        let pool_state = PoolStateBuilder::new().status(0b010).build();
+
+        assert_eq!(
+            pool_state.get_status_flag_by_bit(PoolStatusBitIndex::Deposit),
+            PoolStatusBitFlag::Enable
+        );
+        assert_eq!(
+            pool_state.get_status_flag_by_bit(PoolStatusBitIndex::Withdraw),
+            PoolStatusBitFlag::Disable
+        );
+        assert_eq!(
+            pool_state.get_status_flag_by_bit(PoolStatusBitIndex::Swap),
+            PoolStatusBitFlag::Enable
+        );
+    }
+
+    #[test]
+    fn can_swap_from_open_time() {
+        let pool_state = PoolStateBuilder::new().open_time(1_000).build();
+
+        assert!(!pool_state.can_swap_now(&clock(999)));
+        assert!(pool_state.can_swap_now(&clock(1_000)));
+        assert!(pool_state.can_swap_now(&clock(1_001)));
+    }
+
+    #[test]
+    fn cannot_swap_when_disabled() {
+        let pool_state = PoolStateBuilder::new().open_time(1_000).status(0b100).build();
+        assert!(!pool_state.can_swap_now(&clock(1_001)));
+
+        // deposits and withdrawals being disabled does not stop swaps
+        let pool_state = PoolStateBuilder::new().open_time(1_000).status(0b011).build();
+        assert!(pool_state.can_swap_now(&clock(1_001)));
+    }
+
+    #[test]
+    fn cannot_swap_before_the_unix_epoch() {
+        let pool_state = PoolStateBuilder::new().build();
+        assert!(pool_state.can_swap_now(&clock(0)));
+        assert!(!pool_state.can_swap_now(&clock(-1)));
+        assert!(!pool_state.can_swap_now(&clock(i64::MIN)));
+    }
+}
@@ -0,0 +1,509 @@
+//! Fixtures for testing programs integrating the CPMM, behind the `test-utils` feature.
+//!
+//! The builders produce account data laid out exactly like the program's, discriminator
+//! included, and `ProgramInput` serializes accounts the way the runtime does to hand out
+//! real `AccountInfo`s.
+
+pub mod svm;
+
+use std::{mem::MaybeUninit, vec, vec::Vec};
+
+use pinocchio::{
+    account_info::{AccountInfo, MAX_PERMITTED_DATA_INCREASE},
+    entrypoint::deserialize,
+    pubkey::Pubkey,
+};
+
+use crate::{
+    states::{
+        AmmConfig, CreatorFeeOn, Observation, ObservationState, Permission, PoolState,
+        ProgramAccount, OBSERVATION_NUM,
+    },
+    program_id, token_program,
+};
+
+/// Length of an SPL token account
+pub const TOKEN_ACCOUNT_LEN: usize = 165;
+
+/// Length of an SPL mint
+pub const MINT_LEN: usize = 82;
+
+/// Return an all-zero `T`
+#[inline]
+pub fn zeroed<T: ProgramAccount>() -> T {
+    // SAFETY: `ProgramAccount` types are packed plain data, for which all zeroes is valid.
+    unsafe { core::mem::zeroed() }
+}
+
+/// Return the account data of `state`, discriminator included
+pub fn account_data<T: ProgramAccount>(state: &T) -> Vec<u8> {
+    let mut data = Vec::with_capacity(T::LEN + 8);
+    data.extend_from_slice(T::DISCRIMINATOR);
+    // SAFETY: `ProgramAccount` types are packed structs of `LEN` bytes.
+    data.extend_from_slice(unsafe {
+        core::slice::from_raw_parts(state as *const T as *const u8, T::LEN)
+    });
+    data
+}
+
+/// Return the data of an initialized SPL token or Token-2022 account, without extensions
+pub fn token_account_data(mint: &Pubkey, owner: &Pubkey, amount: u64) -> Vec<u8> {
+    let mut data = vec![0; TOKEN_ACCOUNT_LEN];
+    data[0..32].copy_from_slice(mint);
+    data[32..64].copy_from_slice(owner);
+    data[64..72].copy_from_slice(&amount.to_le_bytes());
+    // account state: initialized
+    data[108] = 1;
+    data
+}
+
+/// Return the data of an initialized SPL token or Token-2022 mint, without extensions
+pub fn mint_data(supply: u64, decimals: u8) -> Vec<u8> {
+    let mut data = vec![0; MINT_LEN];
+    data[36..44].copy_from_slice(&supply.to_le_bytes());
+    data[44] = decimals;
+    // is initialized
+    data[45] = 1;
+    data
+}
+
+/// Return the data of an initialized mint, with `mint_authority` as its mint authority
+pub fn mint_data_with_authority(mint_authority: &Pubkey, supply: u64, decimals: u8) -> Vec<u8> {
+    let mut data = mint_data(supply, decimals);
+    data[0] = 1;
+    data[4..36].copy_from_slice(mint_authority);
+    data
+}
+
+/// Builds a `PoolState`, starting from an open pool with every operation enabled
+pub struct PoolStateBuilder {
+    state: PoolState,
+}
+
+impl Default for PoolStateBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PoolStateBuilder {
+    pub fn new() -> Self {
+        let mut state: PoolState = zeroed();
+        state.token_0_program = token_program::ID;
+        state.token_1_program = token_program::ID;
+        state.lp_mint_decimals = 9;
+        state.mint_0_decimals = 9;
+        state.mint_1_decimals = 9;
+        PoolStateBuilder { state }
+    }
+
+    pub fn amm_config(mut self, amm_config: Pubkey) -> Self {
+        self.state.amm_config = amm_config;
+        self
+    }
+
+    pub fn pool_creator(mut self, pool_creator: Pubkey) -> Self {
+        self.state.pool_creator = pool_creator;
+        self
+    }
+
+    pub fn mints(mut self, token_0_mint: Pubkey, token_1_mint: Pubkey) -> Self {
+        self.state.token_0_mint = token_0_mint;
+        self.state.token_1_mint = token_1_mint;
+        self
+    }
+
+    pub fn vaults(mut self, token_0_vault: Pubkey, token_1_vault: Pubkey) -> Self {
+        self.state.token_0_vault = token_0_vault;
+        self.state.token_1_vault = token_1_vault;
+        self
+    }
+
+    pub fn token_programs(mut self, token_0_program: Pubkey, token_1_program: Pubkey) -> Self {
+        self.state.token_0_program = token_0_program;
+        self.state.token_1_program = token_1_program;
+        self
+    }
+
+    pub fn lp_mint(mut self, lp_mint: Pubkey) -> Self {
+        self.state.lp_mint = lp_mint;
+        self
+    }
+
+    pub fn observation_key(mut self, observation_key: Pubkey) -> Self {
+        self.state.observation_key = observation_key;
+        self
+    }
+
+    pub fn auth_bump(mut self, auth_bump: u8) -> Self {
+        self.state.auth_bump = auth_bump;
+        self
+    }
+
+    pub fn status(mut self, status: u8) -> Self {
+        self.state.status = status;
+        self
+    }
+
+    pub fn decimals(mut self, lp_mint_decimals: u8, mint_0_decimals: u8, mint_1_decimals: u8) -> Self {
+        self.state.lp_mint_decimals = lp_mint_decimals;
+        self.state.mint_0_decimals = mint_0_decimals;
+        self.state.mint_1_decimals = mint_1_decimals;
+        self
+    }
+
+    pub fn lp_supply(mut self, lp_supply: u64) -> Self {
+        self.state.lp_supply = lp_supply;
+        self
+    }
+
+    pub fn protocol_fees(mut self, token_0: u64, token_1: u64) -> Self {
+        self.state.protocol_fees_token_0 = token_0;
+        self.state.protocol_fees_token_1 = token_1;
+        self
+    }
+
+    pub fn fund_fees(mut self, token_0: u64, token_1: u64) -> Self {
+        self.state.fund_fees_token_0 = token_0;
+        self.state.fund_fees_token_1 = token_1;
+        self
+    }
+
+    pub fn creator_fees(mut self, token_0: u64, token_1: u64) -> Self {
+        self.state.creator_fees_token_0 = token_0;
+        self.state.creator_fees_token_1 = token_1;
+        self
+    }
+
+    /// Enable creator fees, charged on `creator_fee_on`
+    pub fn creator_fee_on(mut self, creator_fee_on: CreatorFeeOn) -> Self {
+        self.state.creator_fee_on = creator_fee_on as u8;
+        self.state.enable_creator_fee = true;
+        self
+    }
+
+    pub fn open_time(mut self, open_time: u64) -> Self {
+        self.state.open_time = open_time;
+        self
+    }
+
+    pub fn recent_epoch(mut self, recent_epoch: u64) -> Self {
+        self.state.recent_epoch = recent_epoch;
+        self
+    }
+
+    pub fn build(self) -> PoolState {
+        self.state
+    }
+
+    /// Return the account data of the pool, discriminator included
+    pub fn account_data(self) -> Vec<u8> {
+        account_data(&self.state)
+    }
+}
+
+/// Builds an `AmmConfig`, starting from the 0.25% fee tier of index 0
+pub struct AmmConfigBuilder {
+    config: AmmConfig,
+}
+
+impl Default for AmmConfigBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AmmConfigBuilder {
+    pub fn new() -> Self {
+        AmmConfigBuilder {
+            config: AmmConfig {
+                trade_fee_rate: 2500,
+                protocol_fee_rate: 120000,
+                fund_fee_rate: 40000,
+                ..AmmConfig::default()
+            },
+        }
+    }
+
+    pub fn bump(mut self, bump: u8) -> Self {
+        self.config.bump = bump;
+        self
+    }
+
+    pub fn index(mut self, index: u16) -> Self {
+        self.config.index = index;
+        self
+    }
+
+    pub fn disable_create_pool(mut self, disable_create_pool: bool) -> Self {
+        self.config.disable_create_pool = disable_create_pool;
+        self
+    }
+
+    pub fn trade_fee_rate(mut self, trade_fee_rate: u64) -> Self {
+        self.config.trade_fee_rate = trade_fee_rate;
+        self
+    }
+
+    pub fn protocol_fee_rate(mut self, protocol_fee_rate: u64) -> Self {
+        self.config.protocol_fee_rate = protocol_fee_rate;
+        self
+    }
+
+    pub fn fund_fee_rate(mut self, fund_fee_rate: u64) -> Self {
+        self.config.fund_fee_rate = fund_fee_rate;
+        self
+    }
+
+    pub fn creator_fee_rate(mut self, creator_fee_rate: u64) -> Self {
+        self.config.creator_fee_rate = creator_fee_rate;
+        self
+    }
+
+    pub fn create_pool_fee(mut self, create_pool_fee: u64) -> Self {
+        self.config.create_pool_fee = create_pool_fee;
+        self
+    }
+
+    pub fn protocol_owner(mut self, protocol_owner: Pubkey) -> Self {
+        self.config.protocol_owner = protocol_owner;
+        self
+    }
+
+    pub fn fund_owner(mut self, fund_owner: Pubkey) -> Self {
+        self.config.fund_owner = fund_owner;
+        self
+    }
+
+    pub fn build(self) -> AmmConfig {
+        self.config
+    }
+
+    /// Return the account data of the config, discriminator included
+    pub fn account_data(self) -> Vec<u8> {
+        account_data(&self.config)
+    }
+}
+
+/// Builds an `ObservationState` by recording observations in chronological order
+pub struct ObservationStateBuilder {
+    state: ObservationState,
+}
+
+impl Default for ObservationStateBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ObservationStateBuilder {
+    pub fn new() -> Self {
+        ObservationStateBuilder { state: zeroed() }
+    }
+
+    pub fn pool_id(mut self, pool_id: Pubkey) -> Self {
+        self.state.pool_id = pool_id;
+        self
+    }
+
+    /// Record an observation in the slot after `observation_index`, as the program does.
+    ///
+    /// The first observation goes to slot 0 and initializes the state.
+    pub fn observation(
+        mut self,
+        block_timestamp: u64,
+        cumulative_token_0_price_x32: u128,
+        cumulative_token_1_price_x32: u128,
+    ) -> Self {
+        let index = if self.state.initialized {
+            (self.state.observation_index as usize + 1) % OBSERVATION_NUM
+        } else {
+            self.state.initialized = true;
+            0
+        };
+        self.state.observations[index] = Observation {
+            block_timestamp,
+            cumulative_token_0_price_x32,
+            cumulative_token_1_price_x32,
+        };
+        self.state.observation_index = index as u16;
+        self
+    }
+
+    /// Record `count` observations every `interval` seconds from `start`, accumulating
+    /// constant prices as the program does.
+    ///
+    /// The prices accumulate from the latest observation, the first observation of an empty
+    /// state starts from zero cumulative prices. Cumulative prices wrap around as in the
+    /// program.
+    pub fn constant_prices(
+        mut self,
+        start: u64,
+        interval: u64,
+        count: usize,
+        token_0_price_x32: u128,
+        token_1_price_x32: u128,
+    ) -> Self {
+        for i in 0..count as u64 {
+            let block_timestamp = start + i * interval;
+            let (cumulative_token_0_price_x32, cumulative_token_1_price_x32) =
+                if self.state.initialized {
+                    let latest = self.state.observations[self.state.observation_index as usize];
+                    let elapsed = u128::from(block_timestamp - latest.block_timestamp);
+                    (
+                        { latest.cumulative_token_0_price_x32 }
+                            .wrapping_add(token_0_price_x32.wrapping_mul(elapsed)),
+                        { latest.cumulative_token_1_price_x32 }
+                            .wrapping_add(token_1_price_x32.wrapping_mul(elapsed)),
+                    )
+                } else {
+                    (0, 0)
+                };
+            self = self.observation(
+                block_timestamp,
+                cumulative_token_0_price_x32,
+                cumulative_token_1_price_x32,
+            );
+        }
+        self
+    }
+
+    pub fn build(self) -> ObservationState {
+        self.state
+    }
+
+    /// Return the account data of the observation state, discriminator included
+    pub fn account_data(self) -> Vec<u8> {
+        account_data(&self.state)
+    }
+}
+
+/// Return the account data of a permission granted to `authority`, discriminator included
+pub fn permission_data(authority: Pubkey) -> Vec<u8> {
+    let mut permission: Permission = zeroed();
+    permission.authority = authority;
+    account_data(&permission)
+}
+
+/// An account to lay out in a `ProgramInput`
+#[derive(Clone, Debug)]
+pub struct AccountFixture {
+    pub key: Pubkey,
+    pub owner: Pubkey,
+    pub lamports: u64,
+    pub data: Vec<u8>,
+    pub is_signer: bool,
+    pub is_writable: bool,
+    pub executable: bool,
+}
+
+impl AccountFixture {
+    /// A writable account
+    pub fn new(key: Pubkey, owner: Pubkey, data: Vec<u8>) -> Self {
+        AccountFixture {
+            key,
+            owner,
+            lamports: 1_000_000_000,
+            data,
+            is_signer: false,
+            is_writable: true,
+            executable: false,
+        }
+    }
+
+    /// A writable account owned by the CPMM program
+    pub fn cpmm(key: Pubkey, data: Vec<u8>) -> Self {
+        Self::new(key, *program_id(), data)
+    }
+
+    /// An executable program account
+    pub fn program(key: Pubkey) -> Self {
+        AccountFixture {
+            is_writable: false,
+            executable: true,
+            ..Self::new(key, [0; 32], Vec::new())
+        }
+    }
+
+    pub fn signer(mut self) -> Self {
+        self.is_signer = true;
+        self
+    }
+
+    pub fn readonly(mut self) -> Self {
+        self.is_writable = false;
+        self
+    }
+
+    pub fn lamports(mut self, lamports: u64) -> Self {
+        self.lamports = lamports;
+        self
+    }
+}
+
+/// Accounts serialized the way the runtime serializes a program input, handing out
+/// `AccountInfo`s backed by the serialized buffer
+pub struct ProgramInput {
+    _buffer: Vec<u128>,
+    accounts: Vec<AccountInfo>,
+}
+
+impl ProgramInput {
+    /// The maximum number of accounts of an input
+    pub const MAX_ACCOUNTS: usize = 64;
+
+    pub fn new(accounts: &[AccountFixture]) -> Self {
+        assert!(accounts.len() <= Self::MAX_ACCOUNTS, "too many accounts");
+
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&(accounts.len() as u64).to_le_bytes());
+        for account in accounts {
+            // non duplicate marker
+            bytes.push(u8::MAX);
+            bytes.push(account.is_signer as u8);
+            bytes.push(account.is_writable as u8);
+            bytes.push(account.executable as u8);
+            // original data length, lazily set by pinocchio
+            bytes.extend_from_slice(&[0; 4]);
+            bytes.extend_from_slice(&account.key);
+            bytes.extend_from_slice(&account.owner);
+            bytes.extend_from_slice(&account.lamports.to_le_bytes());
+            bytes.extend_from_slice(&(account.data.len() as u64).to_le_bytes());
+            bytes.extend_from_slice(&account.data);
+            bytes.resize(bytes.len() + MAX_PERMITTED_DATA_INCREASE, 0);
+            bytes.resize(bytes.len().next_multiple_of(8), 0);
+            // rent epoch
+            bytes.extend_from_slice(&u64::MAX.to_le_bytes());
+        }
+        // empty instruction data and program id
+        bytes.extend_from_slice(&0u64.to_le_bytes());
+        bytes.extend_from_slice(program_id());
+
+        let mut buffer = vec![0u128; bytes.len().div_ceil(16)];
+        // SAFETY: `buffer` holds at least `bytes.len()` bytes.
+        unsafe {
+            core::ptr::copy_nonoverlapping(bytes.as_ptr(), buffer.as_mut_ptr() as *mut u8, bytes.len());
+        }
+
+        let mut infos = [const { MaybeUninit::<AccountInfo>::uninit() }; Self::MAX_ACCOUNTS];
+        // SAFETY: `buffer` is laid out as a runtime input and outlives the account infos.
+        let (_, count, _) = unsafe {
+            deserialize::<{ Self::MAX_ACCOUNTS }>(buffer.as_mut_ptr() as *mut u8, &mut infos)
+        };
+        let accounts = infos[..count]
+            .iter()
+            // SAFETY: `deserialize` initialized the first `count` account infos.
+            .map(|info| unsafe { info.assume_init_ref().clone() })
+            .collect();
+
+        ProgramInput {
+            _buffer: buffer,
+            accounts,
+        }
+    }
+
+    /// Return the account infos, in the order of the fixtures
+    pub fn accounts(&self) -> &[AccountInfo] {
+        &self.accounts
+    }
+}
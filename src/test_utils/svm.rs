@@ -0,0 +1,451 @@
+//! An in-process stand-in for the SVM, executing the instruction structs of the crate off-chain.
+//!
+//! The instruction structs are run with `Execute::execute`, or any `with_instruction` through
+//! `Svm::invoke_signed`. Each instruction is recorded and dispatched to the processor
+//! registered for its program id, the CPIs of `crate::mock_program` are dispatched the same
+//! way. Like the runtime, the harness checks that each account of the instruction is passed
+//! and that its signer and writable flags do not escalate the privileges of the caller. A
+//! signer that did not sign the transaction must be a PDA of the calling program, derived
+//! from one of the signer seeds.
+//!
+//! SPL Token and Token-2022 are built in, with the `Transfer`, `TransferChecked`, `MintTo`
+//! and `Burn` instructions on the base account layouts.
+//!
+//! It is not the runtime: programs run natively, compute units, rent and account ownership of
+//! writes are not checked. Tests against the Raydium `.so` need an SVM such as `mollusk-svm`
+//! or `litesvm`.
+
+use std::{cell::RefCell, vec::Vec};
+
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Instruction, Seed, Signer},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    ProgramResult,
+};
+
+use crate::{instructions::*, pda::create_program_address, token_program, token_program_2022};
+
+/// The entrypoint of a program registered in the harness
+pub type Processor = fn(&Pubkey, &[AccountInfo], &[u8]) -> ProgramResult;
+
+/// An account of a recorded invocation
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InvokedAccount {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+impl InvokedAccount {
+    pub fn readonly(pubkey: Pubkey) -> Self {
+        InvokedAccount { pubkey, is_signer: false, is_writable: false }
+    }
+
+    pub fn writable(pubkey: Pubkey) -> Self {
+        InvokedAccount { pubkey, is_signer: false, is_writable: true }
+    }
+
+    pub fn readonly_signer(pubkey: Pubkey) -> Self {
+        InvokedAccount { pubkey, is_signer: true, is_writable: false }
+    }
+
+    pub fn writable_signer(pubkey: Pubkey) -> Self {
+        InvokedAccount { pubkey, is_signer: true, is_writable: true }
+    }
+}
+
+/// An instruction invoked through the harness
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Invocation {
+    pub program_id: Pubkey,
+    pub accounts: Vec<InvokedAccount>,
+    pub data: Vec<u8>,
+    /// `1` for the CPIs of the test, `2` for the CPIs they make, ...
+    pub depth: usize,
+}
+
+#[derive(Default)]
+struct State {
+    installed: bool,
+    programs: Vec<(Pubkey, Processor)>,
+    invocations: Vec<Invocation>,
+    /// The program making the instructions of the test, signing for its PDAs
+    caller: Pubkey,
+    /// The programs executing, innermost last
+    callers: Vec<Pubkey>,
+}
+
+std::thread_local! {
+    static STATE: RefCell<State> = RefCell::new(State::default());
+}
+
+/// The harness of the current thread, uninstalled when dropped
+pub struct Svm {
+    _not_send: core::marker::PhantomData<*const ()>,
+}
+
+impl Svm {
+    /// Install the harness on the current thread, with the token programs built in
+    pub fn new() -> Self {
+        STATE.with(|state| {
+            let mut state = state.borrow_mut();
+            assert!(!state.installed, "an Svm is already installed on this thread");
+            *state = State {
+                installed: true,
+                ..State::default()
+            };
+        });
+        Svm {
+            _not_send: core::marker::PhantomData,
+        }
+    }
+
+    /// Register the processor of `program_id`, replacing any previous one
+    pub fn with_program(self, program_id: Pubkey, processor: Processor) -> Self {
+        STATE.with(|state| {
+            let mut state = state.borrow_mut();
+            state.programs.retain(|(id, _)| *id != program_id);
+            state.programs.push((program_id, processor));
+        });
+        self
+    }
+
+    /// Sign the instructions of the test as `program_id`, for the PDAs derived from signer seeds.
+    ///
+    /// The caller is the system program unless set.
+    pub fn with_caller(self, program_id: Pubkey) -> Self {
+        STATE.with(|state| state.borrow_mut().caller = program_id);
+        self
+    }
+
+    /// Register `crate::mock_program` as the CPMM program
+    #[cfg(feature = "mock-program")]
+    pub fn with_mock_cpmm(self) -> Self {
+        self.with_program(*crate::program_id(), crate::mock_program::process_instruction)
+    }
+
+    /// Execute `instruction` as the caller, see `with_caller`
+    pub fn invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[&AccountInfo],
+        signers: &[Signer],
+    ) -> ProgramResult {
+        invoke_signed(instruction, account_infos, signers).expect("the Svm is installed")
+    }
+
+    /// Execute `instruction` as the caller, without signer seeds
+    pub fn invoke(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[&AccountInfo],
+    ) -> ProgramResult {
+        self.invoke_signed(instruction, account_infos, &[])
+    }
+
+    /// Return the invocations recorded so far, in execution order
+    pub fn invocations(&self) -> Vec<Invocation> {
+        STATE.with(|state| state.borrow().invocations.clone())
+    }
+
+    /// Return the last invocation made by the test itself
+    pub fn last_invocation(&self) -> Option<Invocation> {
+        STATE.with(|state| {
+            state
+                .borrow()
+                .invocations
+                .iter()
+                .rev()
+                .find(|invocation| invocation.depth == 1)
+                .cloned()
+        })
+    }
+
+    /// Forget the recorded invocations
+    pub fn clear(&self) {
+        STATE.with(|state| state.borrow_mut().invocations.clear());
+    }
+}
+
+impl Default for Svm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for Svm {
+    fn drop(&mut self) {
+        STATE.with(|state| *state.borrow_mut() = State::default());
+    }
+}
+
+/// Execute `instruction` if a harness is installed on the current thread, as the executing
+/// program or the caller of the test
+pub(crate) fn invoke_signed(
+    instruction: &Instruction,
+    account_infos: &[&AccountInfo],
+    signers: &[Signer],
+) -> Option<ProgramResult> {
+    let (caller, processor) = STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        if !state.installed {
+            return None;
+        }
+        let depth = state.callers.len() + 1;
+        state.invocations.push(Invocation {
+            program_id: *instruction.program_id,
+            accounts: instruction
+                .accounts
+                .iter()
+                .map(|meta| InvokedAccount {
+                    pubkey: *meta.pubkey,
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                })
+                .collect(),
+            data: instruction.data.to_vec(),
+            depth,
+        });
+        let caller = state.callers.last().copied().unwrap_or(state.caller);
+        let processor = state
+            .programs
+            .iter()
+            .find(|(id, _)| id == instruction.program_id)
+            .map(|(_, processor)| *processor);
+        Some((caller, processor))
+    })?;
+
+    Some(execute(instruction, account_infos, &caller, signers, processor))
+}
+
+fn execute(
+    instruction: &Instruction,
+    account_infos: &[&AccountInfo],
+    caller: &Pubkey,
+    signers: &[Signer],
+    processor: Option<Processor>,
+) -> ProgramResult {
+    let signed = signed_addresses(caller, signers)?;
+    let mut accounts = Vec::with_capacity(instruction.accounts.len());
+    let mut signer_flags = Vec::with_capacity(instruction.accounts.len());
+    for meta in instruction.accounts {
+        let account = account_infos
+            .iter()
+            .find(|account| account.key() == meta.pubkey)
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+        if meta.is_signer && !account.is_signer() && !signed.contains(meta.pubkey) {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if meta.is_writable {
+            if !account.is_writable() {
+                return Err(ProgramError::InvalidArgument);
+            }
+            account.can_borrow_mut_data()?;
+        } else {
+            account.can_borrow_data()?;
+        }
+        accounts.push((*account).clone());
+        signer_flags.push(meta.is_signer);
+    }
+
+    let program_id = instruction.program_id;
+    STATE.with(|state| state.borrow_mut().callers.push(*program_id));
+    let result = if *program_id == token_program::ID || *program_id == token_program_2022::ID {
+        process_token(program_id, &accounts, &signer_flags, instruction.data)
+    } else {
+        match processor {
+            Some(processor) => processor(program_id, &accounts, instruction.data),
+            None => Err(ProgramError::IncorrectProgramId),
+        }
+    };
+    STATE.with(|state| state.borrow_mut().callers.pop());
+    result
+}
+
+/// The layout of `Signer`, whose fields are private to pinocchio
+#[repr(C)]
+struct SignerSeeds<'a> {
+    seeds: *const Seed<'a>,
+    len: u64,
+}
+
+/// Return the PDAs of `program_id` derived from each of `signers`
+fn signed_addresses(program_id: &Pubkey, signers: &[Signer]) -> Result<Vec<Pubkey>, ProgramError> {
+    signers
+        .iter()
+        .map(|signer| {
+            // SAFETY: `Signer` is `repr(C)` with the fields of `SignerSeeds`, built from a
+            // `[Seed]` borrowed for its lifetime.
+            let seeds = unsafe {
+                let signer = &*(signer as *const Signer as *const SignerSeeds);
+                core::slice::from_raw_parts(signer.seeds, signer.len as usize)
+            };
+            let seeds: Vec<&[u8]> = seeds.iter().map(|seed| &**seed).collect();
+            create_program_address(&seeds, program_id)
+        })
+        .collect()
+}
+
+/// Instruction structs the harness executes
+pub trait Execute {
+    /// Execute the instruction in `svm`, signing for the PDAs of `signers`
+    fn execute_signed(&self, svm: &Svm, signers: &[Signer]) -> ProgramResult;
+
+    /// Execute the instruction in `svm`
+    fn execute(&self, svm: &Svm) -> ProgramResult {
+        self.execute_signed(svm, &[])
+    }
+}
+
+macro_rules! impl_execute {
+    ($($instruction:ident),* $(,)?) => {
+        $(
+            impl Execute for $instruction<'_> {
+                fn execute_signed(&self, svm: &Svm, signers: &[Signer]) -> ProgramResult {
+                    self.with_instruction(|instruction, account_infos| {
+                        svm.invoke_signed(instruction, account_infos, signers)
+                    })
+                }
+            }
+        )*
+    };
+}
+
+impl_execute!(
+    Initialize,
+    Deposit,
+    Withdraw,
+    SwapBaseInput,
+    SwapBaseOutput,
+    CreateAmmConfig,
+    UpdateAmmConfig,
+    UpdatePoolStatus,
+    CollectProtocolFee,
+    CollectFundFee,
+    InitializeWithPermission,
+    CollectCreatorFee,
+    CreatePermissionPda,
+    ClosePermissionPda,
+);
+
+/// SPL token errors returned by the built-in token program
+const INSUFFICIENT_FUNDS: u32 = 1;
+const MINT_MISMATCH: u32 = 3;
+const OWNER_MISMATCH: u32 = 4;
+const FIXED_SUPPLY: u32 = 5;
+
+/// Execute an SPL Token instruction, `signers` holds the signer flag of each account
+fn process_token(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    signers: &[bool],
+    data: &[u8],
+) -> ProgramResult {
+    let (&tag, args) = data.split_first().ok_or(ProgramError::InvalidInstructionData)?;
+    let amount = args
+        .get(..8)
+        .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+        .ok_or(ProgramError::InvalidInstructionData)?;
+    for account in accounts.iter().filter(|account| account.data_len() > 0) {
+        if !account.is_owned_by(program_id) {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+    }
+
+    match tag {
+        // Transfer: source, destination, owner
+        3 => {
+            let [source, destination, owner, ..] = accounts else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
+            let mint = read_key(source, 0)?;
+            if read_key(destination, 0)? != mint {
+                return Err(ProgramError::Custom(MINT_MISMATCH));
+            }
+            check_authority(owner, signers[2], &read_key(source, 32)?)?;
+            move_tokens(source, destination, amount)
+        }
+        // TransferChecked: source, mint, destination, owner
+        12 => {
+            let [source, mint, destination, owner, ..] = accounts else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
+            if read_key(source, 0)? != *mint.key() || read_key(destination, 0)? != *mint.key() {
+                return Err(ProgramError::Custom(MINT_MISMATCH));
+            }
+            if args.get(8) != mint.try_borrow_data()?.get(44) {
+                return Err(ProgramError::InvalidArgument);
+            }
+            check_authority(owner, signers[3], &read_key(source, 32)?)?;
+            move_tokens(source, destination, amount)
+        }
+        // MintTo: mint, destination, mint authority
+        7 => {
+            let [mint, destination, authority, ..] = accounts else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
+            if read_key(destination, 0)? != *mint.key() {
+                return Err(ProgramError::Custom(MINT_MISMATCH));
+            }
+            if mint.try_borrow_data()?.first() != Some(&1) {
+                return Err(ProgramError::Custom(FIXED_SUPPLY));
+            }
+            check_authority(authority, signers[2], &read_key(mint, 4)?)?;
+            add_amount(mint, 36, amount, true)?;
+            add_amount(destination, 64, amount, true)
+        }
+        // Burn: account, mint, owner
+        8 => {
+            let [account, mint, owner, ..] = accounts else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
+            if read_key(account, 0)? != *mint.key() {
+                return Err(ProgramError::Custom(MINT_MISMATCH));
+            }
+            check_authority(owner, signers[2], &read_key(account, 32)?)?;
+            add_amount(account, 64, amount, false)?;
+            add_amount(mint, 36, amount, false)
+        }
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+fn read_key(account: &AccountInfo, offset: usize) -> Result<Pubkey, ProgramError> {
+    let data = account.try_borrow_data()?;
+    data.get(offset..offset + 32)
+        .map(|bytes| bytes.try_into().unwrap())
+        .ok_or(ProgramError::InvalidAccountData)
+}
+
+fn check_authority(authority: &AccountInfo, is_signer: bool, expected: &Pubkey) -> ProgramResult {
+    if authority.key() != expected {
+        return Err(ProgramError::Custom(OWNER_MISMATCH));
+    }
+    if !is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    Ok(())
+}
+
+fn move_tokens(source: &AccountInfo, destination: &AccountInfo, amount: u64) -> ProgramResult {
+    add_amount(source, 64, amount, false)?;
+    add_amount(destination, 64, amount, true)
+}
+
+/// Add or subtract `amount` to the `u64` at `offset`
+fn add_amount(account: &AccountInfo, offset: usize, amount: u64, add: bool) -> ProgramResult {
+    let mut data = account.try_borrow_mut_data()?;
+    let bytes = data.get_mut(offset..offset + 8).ok_or(ProgramError::InvalidAccountData)?;
+    let current = u64::from_le_bytes((&*bytes).try_into().unwrap());
+    let updated = if add {
+        current.checked_add(amount).ok_or(ProgramError::ArithmeticOverflow)?
+    } else {
+        current
+            .checked_sub(amount)
+            .ok_or(ProgramError::Custom(INSUFFICIENT_FUNDS))?
+    };
+    bytes.copy_from_slice(&updated.to_le_bytes());
+    Ok(())
+}
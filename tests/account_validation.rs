@@ -0,0 +1,169 @@
+use pinocchio::program_error::ProgramError;
+use pinocchio_raydium_cpmm_cpi::{
+    states::{
+        account::{check_account, check_data},
+        AmmConfig, ObservationState, Permission, PoolState, ProgramAccount,
+    },
+    test_utils::{AccountFixture, ProgramInput},
+    ID,
+};
+
+const KEY: [u8; 32] = [1; 32];
+const OTHER_PROGRAM: [u8; 32] = [2; 32];
+
+fn account_data<T: ProgramAccount>() -> Vec<u8> {
+    with_discriminator(T::DISCRIMINATOR, &vec![0; T::LEN])
+}
+
+fn with_discriminator(discriminator: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut bytes = discriminator.to_vec();
+    bytes.extend_from_slice(data);
+    bytes
+}
+
+fn input(owner: [u8; 32], data: Vec<u8>) -> ProgramInput {
+    ProgramInput::new(&[AccountFixture::new(KEY, owner, data)])
+}
+
+macro_rules! account_validation_tests {
+    ($module:ident, $account:ty, $other:ty) => {
+        mod $module {
+            use super::*;
+
+            #[test]
+            fn accepts_valid_account() {
+                let input = input(ID, account_data::<$account>());
+                let account_info = &input.accounts()[0];
+
+                assert!(check_account::<$account>(account_info).is_ok());
+                assert!(<$account>::from_account_info(account_info).is_ok());
+                assert!(unsafe { <$account>::from_account_info_unchecked(account_info) }.is_ok());
+            }
+
+            #[test]
+            fn rejects_wrong_owner() {
+                let input = input(OTHER_PROGRAM, account_data::<$account>());
+                let account_info = &input.accounts()[0];
+
+                assert_eq!(
+                    <$account>::from_account_info(account_info).err(),
+                    Some(ProgramError::InvalidAccountOwner)
+                );
+                assert_eq!(
+                    unsafe { <$account>::from_account_info_unchecked(account_info) }.err(),
+                    Some(ProgramError::InvalidAccountOwner)
+                );
+            }
+
+            #[test]
+            fn rejects_wrong_discriminator() {
+                let mut data = account_data::<$account>();
+                data[0] ^= 0xff;
+                let input = input(ID, data);
+                let account_info = &input.accounts()[0];
+
+                assert_eq!(
+                    <$account>::from_account_info(account_info).err(),
+                    Some(ProgramError::InvalidAccountData)
+                );
+                assert_eq!(
+                    unsafe { <$account>::from_account_info_unchecked(account_info) }.err(),
+                    Some(ProgramError::InvalidAccountData)
+                );
+            }
+
+            #[test]
+            fn rejects_other_account_type() {
+                let input = input(ID, account_data::<$other>());
+                let account_info = &input.accounts()[0];
+
+                assert_eq!(
+                    <$account>::from_account_info(account_info).err(),
+                    Some(ProgramError::InvalidAccountData)
+                );
+                assert_eq!(
+                    unsafe { <$account>::from_account_info_unchecked(account_info) }.err(),
+                    Some(ProgramError::InvalidAccountData)
+                );
+            }
+
+            #[test]
+            fn rejects_wrong_size() {
+                let mut short = account_data::<$account>();
+                short.pop();
+                let mut long = account_data::<$account>();
+                long.push(0);
+
+                for data in [short, long] {
+                    let input = input(ID, data);
+                    let account_info = &input.accounts()[0];
+
+                    assert_eq!(
+                        <$account>::from_account_info(account_info).err(),
+                        Some(ProgramError::InvalidAccountData)
+                    );
+                    assert_eq!(
+                        unsafe { <$account>::from_account_info_unchecked(account_info) }.err(),
+                        Some(ProgramError::InvalidAccountData)
+                    );
+                }
+            }
+
+            #[test]
+            fn rejects_discriminator_only() {
+                let data = <$account>::DISCRIMINATOR.to_vec();
+
+                assert_eq!(check_data::<$account>(&data), Err(ProgramError::InvalidAccountData));
+                assert_eq!(
+                    <$account>::try_deserialize(&mut data.as_slice()).err(),
+                    Some(ProgramError::InvalidAccountData)
+                );
+                assert_eq!(
+                    <$account>::try_deserialize_unchecked(&mut data.as_slice()).err(),
+                    Some(ProgramError::InvalidAccountData)
+                );
+                assert_eq!(
+                    <$account>::try_deserialize_unchecked(&mut &data[..4]).err(),
+                    Some(ProgramError::InvalidAccountData)
+                );
+            }
+
+            #[test]
+            fn try_deserialize_checks_discriminator() {
+                let data = account_data::<$account>();
+                assert!(<$account>::try_deserialize(&mut data.as_slice()).is_ok());
+
+                let other = with_discriminator(<$other>::DISCRIMINATOR, &vec![0; <$account>::LEN]);
+                assert_eq!(
+                    <$account>::try_deserialize(&mut other.as_slice()).err(),
+                    Some(ProgramError::InvalidAccountData)
+                );
+                assert!(<$account>::try_deserialize_unchecked(&mut other.as_slice()).is_ok());
+            }
+
+            #[test]
+            fn size_includes_discriminator() {
+                assert_eq!(<$account>::size(), <$account>::LEN + 8);
+            }
+        }
+    };
+}
+
+account_validation_tests!(pool_state, PoolState, ObservationState);
+account_validation_tests!(amm_config, AmmConfig, PoolState);
+account_validation_tests!(observation_state, ObservationState, Permission);
+account_validation_tests!(permission, Permission, AmmConfig);
+
+#[test]
+fn account_sizes_match_the_program() {
+    assert_eq!(PoolState::size(), 637);
+    assert_eq!(AmmConfig::size(), 236);
+    assert_eq!(ObservationState::size(), 4075);
+    assert_eq!(Permission::size(), 280);
+}
+
+#[test]
+fn amm_config_len_excludes_the_discriminator() {
+    assert_eq!(AmmConfig::LEN, 228);
+    assert_eq!(AmmConfig::ACCOUNT_LEN, 236);
+}
@@ -0,0 +1,254 @@
+//! The `client` builders against the CPI structs: both must build the same instruction from
+//! the same accounts and arguments.
+
+use pinocchio::pubkey::Pubkey;
+use pinocchio_raydium_cpmm_cpi::{
+    client, instructions,
+    states::CreatorFeeOn,
+    test_utils::{AccountFixture, ProgramInput},
+};
+use solana_instruction::{AccountMeta, Instruction};
+
+fn key(index: usize) -> Pubkey {
+    [index as u8 + 1; 32]
+}
+
+/// `count` accounts keyed `[1; 32]`, `[2; 32]`, ...
+fn input(count: usize) -> ProgramInput {
+    let fixtures: Vec<_> = (0..count)
+        .map(|index| AccountFixture::new(key(index), [0; 32], Vec::new()))
+        .collect();
+    ProgramInput::new(&fixtures)
+}
+
+/// The owned form of an instruction built by a CPI struct
+fn owned(instruction: &pinocchio::instruction::Instruction) -> Instruction {
+    Instruction {
+        program_id: solana_pubkey::Pubkey::new_from_array(*instruction.program_id),
+        accounts: instruction
+            .accounts
+            .iter()
+            .map(|meta| AccountMeta {
+                pubkey: solana_pubkey::Pubkey::new_from_array(*meta.pubkey),
+                is_signer: meta.is_signer,
+                is_writable: meta.is_writable,
+            })
+            .collect(),
+        data: instruction.data.to_vec(),
+    }
+}
+
+/// Build `$ty` from both modules, the accounts in order keyed by `key`, and check that they
+/// build the same instruction
+macro_rules! assert_same_instruction {
+    ($ty:ident { $($account:ident),* $(,)? } $(, $arg:ident: $value:expr)* $(,)?) => {{
+        let input = input([$(stringify!($account)),*].len());
+        let a = input.accounts();
+
+        let mut index = 0..;
+        let cpi = instructions::$ty {
+            $($account: &a[index.next().unwrap()],)*
+            $($arg: $value,)*
+        };
+        let mut index = 0..;
+        let client = client::$ty {
+            $($account: solana_pubkey::Pubkey::new_from_array(key(index.next().unwrap())),)*
+            $($arg: $value,)*
+        };
+
+        assert_eq!(cpi.with_instruction(|instruction, _| owned(instruction)), client.instruction());
+    }};
+}
+
+#[test]
+fn initialize() {
+    assert_same_instruction!(
+        Initialize {
+            creator, amm_config, authority, pool_state, token_0_mint, token_1_mint, lp_mint,
+            creator_token_0, creator_token_1, creator_lp_token, token_0_vault, token_1_vault,
+            create_pool_fee, observation_state, token_program, token_0_program, token_1_program,
+            associated_token_program, system_program, rent,
+        },
+        init_amount_0: 1_000,
+        init_amount_1: 2_000,
+        open_time: 3_000,
+    );
+}
+
+#[test]
+fn deposit() {
+    assert_same_instruction!(
+        Deposit {
+            owner, authority, pool_state, owner_lp_token, token_0_account, token_1_account,
+            token_0_vault, token_1_vault, token_program, token_program_2022, vault_0_mint,
+            vault_1_mint, lp_mint,
+        },
+        lp_token_amount: 100,
+        maximum_token_0_amount: 200,
+        maximum_token_1_amount: 300,
+    );
+}
+
+#[test]
+fn withdraw() {
+    assert_same_instruction!(
+        Withdraw {
+            owner, authority, pool_state, owner_lp_token, token_0_account, token_1_account,
+            token_0_vault, token_1_vault, token_program, token_program_2022, vault_0_mint,
+            vault_1_mint, lp_mint, memo_program,
+        },
+        lp_token_amount: 100,
+        minimum_token_0_amount: 200,
+        minimum_token_1_amount: 300,
+    );
+}
+
+#[test]
+fn swap_base_input() {
+    assert_same_instruction!(
+        SwapBaseInput {
+            payer, authority, amm_config, pool_state, input_token_account, output_token_account,
+            input_vault, output_vault, input_token_program, output_token_program,
+            input_token_mint, output_token_mint, observation_state,
+        },
+        amount_in: 20_000,
+        minimum_amount_out: 19_000,
+    );
+}
+
+#[test]
+fn swap_base_output() {
+    assert_same_instruction!(
+        SwapBaseOutput {
+            payer, authority, amm_config, pool_state, input_token_account, output_token_account,
+            input_vault, output_vault, input_token_program, output_token_program,
+            input_token_mint, output_token_mint, observation_state,
+        },
+        max_amount_in: 21_000,
+        amount_out: 20_000,
+    );
+}
+
+#[test]
+fn create_amm_config() {
+    assert_same_instruction!(
+        CreateAmmConfig { owner, amm_config, system_program },
+        index: 7,
+        trade_fee_rate: 2_500,
+        protocol_fee_rate: 120_000,
+        fund_fee_rate: 40_000,
+        create_pool_fee: 150_000_000,
+        creator_fee_rate: 500,
+    );
+}
+
+#[test]
+fn update_amm_config() {
+    let input = input(2);
+    let a = input.accounts();
+    let [owner, amm_config] = [0, 1].map(|index| solana_pubkey::Pubkey::new_from_array(key(index)));
+
+    let cpi = instructions::UpdateAmmConfig {
+        owner: &a[0],
+        amm_config: &a[1],
+        param: instructions::UpdateAmmConfigParam::TradeFeeRate(3_000),
+    };
+    let client = client::UpdateAmmConfig {
+        owner,
+        amm_config,
+        param: client::UpdateAmmConfigParam::TradeFeeRate(3_000),
+    };
+
+    assert_eq!(cpi.with_instruction(|instruction, _| owned(instruction)), client.instruction());
+}
+
+#[test]
+fn update_amm_config_new_owner() {
+    let input = input(3);
+    let a = input.accounts();
+    let [owner, amm_config, new_owner] =
+        [0, 1, 2].map(|index| solana_pubkey::Pubkey::new_from_array(key(index)));
+
+    let cpi = instructions::UpdateAmmConfig {
+        owner: &a[0],
+        amm_config: &a[1],
+        param: instructions::UpdateAmmConfigParam::NewFundOwner(&a[2]),
+    };
+    let client = client::UpdateAmmConfig {
+        owner,
+        amm_config,
+        param: client::UpdateAmmConfigParam::NewFundOwner(new_owner),
+    };
+
+    assert_eq!(cpi.with_instruction(|instruction, _| owned(instruction)), client.instruction());
+}
+
+#[test]
+fn update_pool_status() {
+    assert_same_instruction!(UpdatePoolStatus { authority, pool_state }, status: 4);
+}
+
+#[test]
+fn collect_protocol_fee() {
+    assert_same_instruction!(
+        CollectProtocolFee {
+            owner, authority, pool_state, amm_config, token_0_vault, token_1_vault, vault_0_mint,
+            vault_1_mint, recipient_token_0_account, recipient_token_1_account, token_program,
+            token_program_2022,
+        },
+        amount_0_requested: 45,
+        amount_1_requested: 3,
+    );
+}
+
+#[test]
+fn collect_fund_fee() {
+    assert_same_instruction!(
+        CollectFundFee {
+            owner, authority, pool_state, amm_config, token_0_vault, token_1_vault, vault_0_mint,
+            vault_1_mint, recipient_token_0_account, recipient_token_1_account, token_program,
+            token_program_2022,
+        },
+        amount_0_requested: 15,
+        amount_1_requested: 1,
+    );
+}
+
+#[test]
+fn initialize_with_permission() {
+    assert_same_instruction!(
+        InitializeWithPermission {
+            payer, creator, amm_config, authority, pool_state, token_0_mint, token_1_mint,
+            lp_mint, payer_token_0, payer_token_1, payer_lp_token, token_0_vault, token_1_vault,
+            create_pool_fee, observation_state, permission, token_program, token_0_program,
+            token_1_program, associated_token_program, system_program,
+        },
+        init_amount_0: 1_000,
+        init_amount_1: 2_000,
+        open_time: 3_000,
+        creator_fee_on: CreatorFeeOn::OnlyToken1,
+    );
+}
+
+#[test]
+fn collect_creator_fee() {
+    assert_same_instruction!(CollectCreatorFee {
+        creator, authority, pool_state, amm_config, token_0_vault, token_1_vault, vault_0_mint,
+        vault_1_mint, creator_token_0, creator_token_1, token_0_program, token_1_program,
+        associated_token_program, system_program,
+    });
+}
+
+#[test]
+fn create_permission_pda() {
+    assert_same_instruction!(CreatePermissionPda {
+        owner, permission_authority, permission, system_program,
+    });
+}
+
+#[test]
+fn close_permission_pda() {
+    assert_same_instruction!(ClosePermissionPda {
+        owner, permission_authority, permission, system_program,
+    });
+}
@@ -0,0 +1,215 @@
+//! Runs the generator in `codegen/` on the IDL in `idl/` and checks its output against the
+//! crate: the discriminator, data length and accounts of every instruction, and the fields,
+//! discriminator and length of every state.
+//!
+//! A failure here means `src/` has drifted from what the generator emits, regenerate as
+//! described in `codegen/src/main.rs` and apply the difference.
+
+use pinocchio_raydium_cpmm_cpi::{
+    instructions::{encoding::AccountRole, *},
+    states::{AmmConfig, ObservationState, Permission, PoolState, OBSERVATION_NUM},
+};
+
+use idl::{Idl, TypeKind};
+
+const IDL: &str = include_str!("../idl/raydium_cp_swap.json");
+
+/// The sources of `src/states`, where a generated state can live in any module
+const STATES: [&str; 6] = [
+    include_str!("../src/states/amm_config.rs"),
+    include_str!("../src/states/mod.rs"),
+    include_str!("../src/states/observation.rs"),
+    include_str!("../src/states/observation_state.rs"),
+    include_str!("../src/states/permission.rs"),
+    include_str!("../src/states/pool_state.rs"),
+];
+
+fn idl() -> Idl {
+    Idl::parse(&json::parse(IDL).expect("invalid IDL")).expect("unsupported IDL")
+}
+
+/// The discriminator, data length and accounts of each instruction struct, by IDL name
+struct Crate {
+    discriminator: [u8; 8],
+    data_len: usize,
+    account_names: &'static [&'static str],
+    accounts: &'static [AccountRole],
+}
+
+macro_rules! instruction {
+    ($ty:ident) => {
+        Crate {
+            discriminator: $ty::DISCRIMINATOR,
+            data_len: $ty::DATA_LEN,
+            account_names: &$ty::ACCOUNT_NAMES,
+            accounts: &$ty::ACCOUNTS,
+        }
+    };
+}
+
+fn crate_instruction(name: &str) -> Crate {
+    match name {
+        "initialize" => instruction!(Initialize),
+        "deposit" => instruction!(Deposit),
+        "withdraw" => instruction!(Withdraw),
+        "swap_base_input" => instruction!(SwapBaseInput),
+        "swap_base_output" => instruction!(SwapBaseOutput),
+        "create_amm_config" => instruction!(CreateAmmConfig),
+        "update_amm_config" => instruction!(UpdateAmmConfig),
+        "update_pool_status" => instruction!(UpdatePoolStatus),
+        "collect_protocol_fee" => instruction!(CollectProtocolFee),
+        "collect_fund_fee" => instruction!(CollectFundFee),
+        "initialize_with_permission" => instruction!(InitializeWithPermission),
+        "collect_creator_fee" => instruction!(CollectCreatorFee),
+        "create_permission_pda" => instruction!(CreatePermissionPda),
+        "close_permission_pda" => instruction!(ClosePermissionPda),
+        name => panic!("{name} is not implemented by the crate"),
+    }
+}
+
+/// Return the value of `pub const <name>` in generated `code`, without its trailing `;`
+fn constant<'a>(code: &'a str, name: &str) -> &'a str {
+    let start = code
+        .find(&format!("pub const {name}:"))
+        .unwrap_or_else(|| panic!("{name} is not generated"));
+    let value = &code[start..];
+    let value = &value[value.find(" = ").unwrap() + 3..];
+    value[..value.find(';').unwrap()].trim()
+}
+
+/// Return the comma separated items of a `[...]` or `&[...]` list
+fn items(list: &str) -> Vec<&str> {
+    list.trim_start_matches('&')
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+fn bytes(list: &str) -> Vec<u8> {
+    items(list).iter().map(|byte| byte.parse().unwrap()).collect()
+}
+
+fn role(name: &str) -> AccountRole {
+    match name {
+        "AccountRole::READONLY" => AccountRole::READONLY,
+        "AccountRole::WRITABLE" => AccountRole::WRITABLE,
+        "AccountRole::READONLY_SIGNER" => AccountRole::READONLY_SIGNER,
+        "AccountRole::WRITABLE_SIGNER" => AccountRole::WRITABLE_SIGNER,
+        name => panic!("unknown role {name}"),
+    }
+}
+
+/// Return the fields or variants of the item opened by `header`, without docs, attributes
+/// and blank lines
+fn body<'a>(code: &'a str, header: &str) -> Option<Vec<&'a str>> {
+    let start = code.find(header)? + header.len();
+    let end = start + code[start..].find("\n}").unwrap();
+    Some(
+        code[start..end]
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with("//") && !line.starts_with('#'))
+            .collect(),
+    )
+}
+
+#[test]
+fn instructions_match_the_generator() {
+    let idl = idl();
+    assert_eq!(idl.instructions.len(), 14);
+
+    for ix in &idl.instructions {
+        let code = instruction::emit(&idl, ix).unwrap();
+        let expected = crate_instruction(&ix.name);
+
+        assert_eq!(
+            bytes(constant(&code, "DISCRIMINATOR")),
+            expected.discriminator,
+            "{}",
+            ix.name
+        );
+        assert_eq!(
+            constant(&code, "DATA_LEN").parse::<usize>().unwrap(),
+            expected.data_len,
+            "{}",
+            ix.name
+        );
+        let accounts: Vec<_> = items(constant(&code, "ACCOUNTS")).into_iter().map(role).collect();
+        assert_eq!(accounts, expected.accounts, "{}", ix.name);
+        let names: Vec<_> = items(constant(&code, "ACCOUNT_NAMES"))
+            .iter()
+            .map(|name| name.trim_matches('"'))
+            .collect();
+        assert_eq!(names, expected.account_names, "{}", ix.name);
+    }
+}
+
+#[test]
+fn state_layouts_match_the_generator() {
+    let idl = idl();
+    let types = state::types(&idl).unwrap();
+    assert_eq!(types.len(), 6);
+
+    for type_def in types {
+        let code = state::emit(&idl, type_def).unwrap();
+        let header = match type_def.kind {
+            TypeKind::Struct(_) => format!("pub struct {} {{", type_def.name),
+            TypeKind::Enum(_) => format!("pub enum {} {{", type_def.name),
+        };
+        let generated = body(&code, &header).unwrap();
+        // The generator writes array lengths as literals, the crate names some of them
+        let implemented: Vec<_> = STATES
+            .iter()
+            .find_map(|source| body(source, &header))
+            .unwrap_or_else(|| panic!("{} is not implemented by the crate", type_def.name))
+            .iter()
+            .map(|line| line.replace("OBSERVATION_NUM", &OBSERVATION_NUM.to_string()))
+            .collect();
+
+        assert_eq!(implemented, generated, "{}", type_def.name);
+    }
+}
+
+#[test]
+fn account_discriminators_and_lengths_match_the_generator() {
+    let idl = idl();
+    let accounts = [
+        ("AmmConfig", AmmConfig::DISCRIMINATOR, AmmConfig::LEN),
+        ("ObservationState", ObservationState::DISCRIMINATOR, ObservationState::LEN),
+        ("Permission", Permission::DISCRIMINATOR, Permission::LEN),
+        ("PoolState", PoolState::DISCRIMINATOR, PoolState::LEN),
+    ];
+    assert_eq!(idl.accounts.len(), accounts.len());
+
+    for (name, discriminator, len) in accounts {
+        let type_def = idl.type_def(name).unwrap();
+        let code = state::emit(&idl, type_def).unwrap();
+
+        assert_eq!(bytes(constant(&code, "DISCRIMINATOR")), discriminator, "{name}");
+        assert_eq!(
+            idl.size_of(&idl::Type::Defined(name.to_string())).unwrap(),
+            len,
+            "{name}"
+        );
+    }
+}
+
+// The generator modules, of which the test only runs the emitters
+#[allow(dead_code)]
+#[path = "../codegen/src/emit.rs"]
+mod emit;
+#[allow(dead_code)]
+#[path = "../codegen/src/idl.rs"]
+mod idl;
+#[allow(dead_code)]
+#[path = "../codegen/src/instruction.rs"]
+mod instruction;
+#[allow(dead_code)]
+#[path = "../codegen/src/json.rs"]
+mod json;
+#[allow(dead_code)]
+#[path = "../codegen/src/state.rs"]
+mod state;
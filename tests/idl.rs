@@ -0,0 +1,551 @@
+//! Golden vectors of the instruction encoding, checked against the CPMM IDL in `idl/`.
+//!
+//! Every discriminator is recomputed from its name, and each instruction struct is invoked
+//! through the `test_utils::svm` harness with its fields bound by IDL name, so a renamed,
+//! reordered, added or removed argument or account fails here. To check a new program
+//! release, replace `idl/raydium_cp_swap.json` with the IDL of the release.
+
+use pinocchio::{account_info::AccountInfo, pubkey::Pubkey, ProgramResult};
+use pinocchio_raydium_cpmm_cpi::{
+    associated_token_program,
+    error::CpmmError,
+    events::{LpChangeEvent, SwapEvent},
+    instructions::*,
+    memo_program, program_id, rent_sysvar,
+    states::{AmmConfig, CreatorFeeOn, ObservationState, Permission, PoolState},
+    system_program,
+    test_utils::{
+        svm::{Execute, InvokedAccount, Svm},
+        AccountFixture, ProgramInput,
+    },
+    token_program, token_program_2022,
+};
+use sha2::{Digest, Sha256};
+
+use json::Value;
+
+const IDL: &str = include_str!("../idl/raydium_cp_swap.json");
+
+fn idl() -> Value {
+    json::parse(IDL).expect("invalid IDL")
+}
+
+/// Return the Anchor discriminator of `preimage`, e.g. `global:swap_base_input`
+fn discriminator(preimage: &str) -> [u8; 8] {
+    Sha256::digest(preimage.as_bytes())[..8].try_into().unwrap()
+}
+
+fn idl_discriminator(value: &Value) -> [u8; 8] {
+    let bytes: Vec<u8> = value["discriminator"]
+        .as_array()
+        .iter()
+        .map(|byte| byte.as_u64() as u8)
+        .collect();
+    bytes.try_into().expect("discriminators are 8 bytes")
+}
+
+/// Return the IDL entry of `collection` named `name`
+fn find<'a>(collection: &'a Value, name: &str) -> &'a Value {
+    collection
+        .as_array()
+        .iter()
+        .find(|entry| entry["name"].as_str() == name)
+        .unwrap_or_else(|| panic!("{name} is not in the IDL"))
+}
+
+/// The discriminator, data length and accounts of each instruction struct, by IDL name
+struct Instruction {
+    name: &'static str,
+    discriminator: [u8; 8],
+    data_len: usize,
+    account_names: &'static [&'static str],
+    accounts: &'static [AccountRole],
+}
+
+macro_rules! instructions {
+    ($($name:literal => $ty:ident),* $(,)?) => {
+        [$(Instruction {
+            name: $name,
+            discriminator: $ty::DISCRIMINATOR,
+            data_len: $ty::DATA_LEN,
+            account_names: &$ty::ACCOUNT_NAMES,
+            accounts: &$ty::ACCOUNTS,
+        }),*]
+    };
+}
+
+const INSTRUCTIONS: [Instruction; 14] = instructions![
+    "initialize" => Initialize,
+    "deposit" => Deposit,
+    "withdraw" => Withdraw,
+    "swap_base_input" => SwapBaseInput,
+    "swap_base_output" => SwapBaseOutput,
+    "create_amm_config" => CreateAmmConfig,
+    "update_amm_config" => UpdateAmmConfig,
+    "update_pool_status" => UpdatePoolStatus,
+    "collect_protocol_fee" => CollectProtocolFee,
+    "collect_fund_fee" => CollectFundFee,
+    "initialize_with_permission" => InitializeWithPermission,
+    "collect_creator_fee" => CollectCreatorFee,
+    "create_permission_pda" => CreatePermissionPda,
+    "close_permission_pda" => ClosePermissionPda,
+];
+
+#[test]
+fn every_instruction_is_implemented() {
+    let idl = idl();
+    let mut names: Vec<_> = idl["instructions"]
+        .as_array()
+        .iter()
+        .map(|instruction| instruction["name"].as_str())
+        .collect();
+    let mut implemented: Vec<_> = INSTRUCTIONS.iter().map(|instruction| instruction.name).collect();
+    names.sort_unstable();
+    implemented.sort_unstable();
+
+    assert_eq!(names, implemented);
+}
+
+#[test]
+fn instruction_discriminators() {
+    let idl = idl();
+    for instruction in &INSTRUCTIONS {
+        let expected = discriminator(&format!("global:{}", instruction.name));
+        let entry = find(&idl["instructions"], instruction.name);
+
+        assert_eq!(idl_discriminator(entry), expected, "{}", instruction.name);
+        assert_eq!(instruction.discriminator, expected, "{}", instruction.name);
+    }
+}
+
+#[test]
+fn instruction_data_lengths() {
+    let idl = idl();
+    for instruction in &INSTRUCTIONS {
+        let entry = find(&idl["instructions"], instruction.name);
+        let args_len: usize = entry["args"]
+            .as_array()
+            .iter()
+            .map(|arg| type_len(&idl, &arg["type"]))
+            .sum();
+
+        assert_eq!(instruction.data_len, 8 + args_len, "{}", instruction.name);
+    }
+}
+
+#[test]
+fn instruction_accounts() {
+    let idl = idl();
+    for instruction in &INSTRUCTIONS {
+        let expected: Vec<_> = find(&idl["instructions"], instruction.name)["accounts"]
+            .as_array()
+            .iter()
+            .map(|account| (account["name"].as_str(), idl_role(account)))
+            .collect();
+        let accounts: Vec<_> = instruction
+            .account_names
+            .iter()
+            .copied()
+            .zip(instruction.accounts.iter().copied())
+            .collect();
+
+        assert_eq!(accounts, expected, "{}", instruction.name);
+    }
+}
+
+#[test]
+fn fixed_account_addresses() {
+    let idl = idl();
+    let known = [
+        ("token_program", token_program::ID),
+        ("token_program_2022", token_program_2022::ID),
+        ("associated_token_program", associated_token_program::ID),
+        ("system_program", system_program::ID),
+        ("rent", rent_sysvar::ID),
+        ("memo_program", memo_program::ID),
+    ];
+    for instruction in idl["instructions"].as_array() {
+        for account in instruction["accounts"].as_array() {
+            let Some(address) = account.get("address") else {
+                continue;
+            };
+            let name = account["name"].as_str();
+            let (_, expected) = known
+                .iter()
+                .find(|(known, _)| *known == name)
+                .unwrap_or_else(|| panic!("unexpected fixed account {name}"));
+
+            assert_eq!(pinocchio_pubkey::from_str(address.as_str()), *expected, "{name}");
+        }
+    }
+}
+
+#[cfg(not(any(feature = "devnet", feature = "localnet")))]
+#[test]
+fn program_address() {
+    assert_eq!(pinocchio_pubkey::from_str(idl()["address"].as_str()), pinocchio_raydium_cpmm_cpi::ID);
+}
+
+/// Invoke each instruction struct with its fields bound by IDL name, and compare the
+/// instruction data and account metas to the IDL encoding
+#[test]
+fn instruction_encoding() {
+    let idl = idl();
+    let svm = Svm::new().with_program(*program_id(), accept);
+
+    for instruction in &INSTRUCTIONS {
+        let entry = find(&idl["instructions"], instruction.name);
+        let accounts = entry["accounts"].as_array();
+        let args = entry["args"].as_array();
+
+        let fixtures: Vec<_> = (0..accounts.len())
+            .map(|index| AccountFixture::new(key(index), [0; 32], Vec::new()).signer())
+            .collect();
+        let input = ProgramInput::new(&fixtures);
+        let account = |name: &str| {
+            let index = accounts
+                .iter()
+                .position(|account| account["name"].as_str() == name)
+                .unwrap_or_else(|| panic!("{} has no account {name} in the IDL", instruction.name));
+            &input.accounts()[index]
+        };
+        let arg = |name: &str| {
+            let index = args
+                .iter()
+                .position(|arg| arg["name"].as_str() == name)
+                .unwrap_or_else(|| panic!("{} has no arg {name} in the IDL", instruction.name));
+            arg_value(&idl, index, &args[index]["type"])
+        };
+
+        svm.clear();
+        invoke(&svm, instruction.name, account, arg).unwrap();
+
+        let mut data = idl_discriminator(entry).to_vec();
+        for (index, arg) in args.iter().enumerate() {
+            let value = arg_value(&idl, index, &arg["type"]);
+            data.extend_from_slice(&value.to_le_bytes()[..type_len(&idl, &arg["type"])]);
+        }
+        let metas: Vec<_> = accounts
+            .iter()
+            .enumerate()
+            .map(|(index, account)| {
+                let role = idl_role(account);
+                InvokedAccount {
+                    pubkey: key(index),
+                    is_signer: role.is_signer,
+                    is_writable: role.is_writable,
+                }
+            })
+            .collect();
+
+        let invocation = svm.last_invocation().unwrap();
+        assert_eq!(invocation.data, data, "{}", instruction.name);
+        assert_eq!(invocation.accounts, metas, "{}", instruction.name);
+
+        let decoded = CpmmInstruction::decode(&data).unwrap();
+        assert_eq!(decoded.name(), instruction.name);
+        assert_eq!(decoded.account_names(), instruction.account_names);
+    }
+}
+
+#[test]
+fn account_discriminators() {
+    let idl = idl();
+    let accounts = [
+        ("AmmConfig", AmmConfig::DISCRIMINATOR, AmmConfig::LEN),
+        ("ObservationState", ObservationState::DISCRIMINATOR, ObservationState::LEN),
+        ("Permission", Permission::DISCRIMINATOR, Permission::LEN),
+        ("PoolState", PoolState::DISCRIMINATOR, PoolState::LEN),
+    ];
+    assert_eq!(idl["accounts"].as_array().len(), accounts.len());
+
+    for (name, crate_discriminator, len) in accounts {
+        let expected = discriminator(&format!("account:{name}"));
+
+        assert_eq!(idl_discriminator(find(&idl["accounts"], name)), expected, "{name}");
+        assert_eq!(crate_discriminator, expected, "{name}");
+        assert_eq!(len, defined_len(&idl, name), "{name}");
+    }
+}
+
+#[test]
+fn event_discriminators() {
+    let idl = idl();
+    let events = [
+        ("LpChangeEvent", LpChangeEvent::DISCRIMINATOR, LpChangeEvent::LEN),
+        ("SwapEvent", SwapEvent::DISCRIMINATOR, SwapEvent::LEN),
+    ];
+    assert_eq!(idl["events"].as_array().len(), events.len());
+
+    for (name, crate_discriminator, len) in events {
+        let expected = discriminator(&format!("event:{name}"));
+
+        assert_eq!(idl_discriminator(find(&idl["events"], name)), expected, "{name}");
+        assert_eq!(crate_discriminator, expected, "{name}");
+        assert_eq!(len, defined_len(&idl, name), "{name}");
+    }
+}
+
+#[test]
+fn errors() {
+    let idl = idl();
+    for error in idl["errors"].as_array() {
+        let code = error["code"].as_u64() as u32;
+        let cpmm_error = CpmmError::from_code(code).unwrap_or_else(|| panic!("unknown error {code}"));
+
+        assert_eq!(cpmm_error.name(), error["name"].as_str());
+        assert_eq!(cpmm_error.message(), error["msg"].as_str());
+    }
+    assert_eq!(CpmmError::from_code(6000 + idl["errors"].as_array().len() as u32), None);
+}
+
+fn key(index: usize) -> Pubkey {
+    [index as u8 + 1; 32]
+}
+
+/// A program accepting any instruction
+fn accept(_: &Pubkey, _: &[AccountInfo], _: &[u8]) -> ProgramResult {
+    Ok(())
+}
+
+fn idl_role(account: &Value) -> AccountRole {
+    let flag = |name| account.get(name).is_some_and(Value::as_bool);
+    AccountRole {
+        is_signer: flag("signer"),
+        is_writable: flag("writable"),
+    }
+}
+
+/// Return a value for the `index`-th argument, distinct from the other arguments so that
+/// a reordering changes the data
+fn arg_value(idl: &Value, index: usize, ty: &Value) -> u64 {
+    let index = index as u64 + 1;
+    if ty.get("defined").is_some() {
+        // a valid variant index
+        return index % defined_variants(idl, ty);
+    }
+    match type_len(idl, ty) {
+        1 => index,
+        2 => 0x0100 | index,
+        _ => 0x0102_0304_0506_0700 | index,
+    }
+}
+
+/// Return the Borsh length of `ty`
+fn type_len(idl: &Value, ty: &Value) -> usize {
+    if let Some(array) = ty.get("array") {
+        let [element, len] = array.as_array() else {
+            panic!("invalid array type");
+        };
+        return type_len(idl, element) * len.as_u64() as usize;
+    }
+    if let Some(defined) = ty.get("defined") {
+        return defined_len(idl, defined["name"].as_str());
+    }
+    match ty.as_str() {
+        "u8" | "bool" => 1,
+        "u16" => 2,
+        "u64" => 8,
+        "u128" => 16,
+        "pubkey" => 32,
+        other => panic!("unsupported type {other}"),
+    }
+}
+
+/// Return the Borsh length of the type `name`, only unit enum variants are supported
+fn defined_len(idl: &Value, name: &str) -> usize {
+    let ty = &find(&idl["types"], name)["type"];
+    match ty["kind"].as_str() {
+        "struct" => ty["fields"]
+            .as_array()
+            .iter()
+            .map(|field| type_len(idl, &field["type"]))
+            .sum(),
+        "enum" => 1,
+        kind => panic!("unsupported type kind {kind}"),
+    }
+}
+
+fn defined_variants(idl: &Value, ty: &Value) -> u64 {
+    find(&idl["types"], ty["defined"]["name"].as_str())["type"]["variants"]
+        .as_array()
+        .len() as u64
+}
+
+/// Invoke the instruction struct of `name`, reading its accounts and args by IDL name
+fn invoke<'a>(
+    svm: &Svm,
+    name: &str,
+    a: impl Fn(&str) -> &'a AccountInfo,
+    arg: impl Fn(&str) -> u64,
+) -> ProgramResult {
+    match name {
+        "initialize" => Initialize {
+            creator: a("creator"), amm_config: a("amm_config"), authority: a("authority"),
+            pool_state: a("pool_state"), token_0_mint: a("token_0_mint"),
+            token_1_mint: a("token_1_mint"), lp_mint: a("lp_mint"),
+            creator_token_0: a("creator_token_0"), creator_token_1: a("creator_token_1"),
+            creator_lp_token: a("creator_lp_token"), token_0_vault: a("token_0_vault"),
+            token_1_vault: a("token_1_vault"), create_pool_fee: a("create_pool_fee"),
+            observation_state: a("observation_state"), token_program: a("token_program"),
+            token_0_program: a("token_0_program"), token_1_program: a("token_1_program"),
+            associated_token_program: a("associated_token_program"),
+            system_program: a("system_program"), rent: a("rent"),
+            init_amount_0: arg("init_amount_0"),
+            init_amount_1: arg("init_amount_1"),
+            open_time: arg("open_time"),
+        }
+        .execute(svm),
+        "deposit" => Deposit {
+            owner: a("owner"), authority: a("authority"), pool_state: a("pool_state"),
+            owner_lp_token: a("owner_lp_token"), token_0_account: a("token_0_account"),
+            token_1_account: a("token_1_account"), token_0_vault: a("token_0_vault"),
+            token_1_vault: a("token_1_vault"), token_program: a("token_program"),
+            token_program_2022: a("token_program_2022"), vault_0_mint: a("vault_0_mint"),
+            vault_1_mint: a("vault_1_mint"), lp_mint: a("lp_mint"),
+            lp_token_amount: arg("lp_token_amount"),
+            maximum_token_0_amount: arg("maximum_token_0_amount"),
+            maximum_token_1_amount: arg("maximum_token_1_amount"),
+        }
+        .execute(svm),
+        "withdraw" => Withdraw {
+            owner: a("owner"), authority: a("authority"), pool_state: a("pool_state"),
+            owner_lp_token: a("owner_lp_token"), token_0_account: a("token_0_account"),
+            token_1_account: a("token_1_account"), token_0_vault: a("token_0_vault"),
+            token_1_vault: a("token_1_vault"), token_program: a("token_program"),
+            token_program_2022: a("token_program_2022"), vault_0_mint: a("vault_0_mint"),
+            vault_1_mint: a("vault_1_mint"), lp_mint: a("lp_mint"),
+            memo_program: a("memo_program"),
+            lp_token_amount: arg("lp_token_amount"),
+            minimum_token_0_amount: arg("minimum_token_0_amount"),
+            minimum_token_1_amount: arg("minimum_token_1_amount"),
+        }
+        .execute(svm),
+        "swap_base_input" => SwapBaseInput {
+            payer: a("payer"), authority: a("authority"), amm_config: a("amm_config"),
+            pool_state: a("pool_state"), input_token_account: a("input_token_account"),
+            output_token_account: a("output_token_account"), input_vault: a("input_vault"),
+            output_vault: a("output_vault"), input_token_program: a("input_token_program"),
+            output_token_program: a("output_token_program"),
+            input_token_mint: a("input_token_mint"), output_token_mint: a("output_token_mint"),
+            observation_state: a("observation_state"),
+            amount_in: arg("amount_in"),
+            minimum_amount_out: arg("minimum_amount_out"),
+        }
+        .execute(svm),
+        "swap_base_output" => SwapBaseOutput {
+            payer: a("payer"), authority: a("authority"), amm_config: a("amm_config"),
+            pool_state: a("pool_state"), input_token_account: a("input_token_account"),
+            output_token_account: a("output_token_account"), input_vault: a("input_vault"),
+            output_vault: a("output_vault"), input_token_program: a("input_token_program"),
+            output_token_program: a("output_token_program"),
+            input_token_mint: a("input_token_mint"), output_token_mint: a("output_token_mint"),
+            observation_state: a("observation_state"),
+            max_amount_in: arg("max_amount_in"),
+            amount_out: arg("amount_out"),
+        }
+        .execute(svm),
+        "create_amm_config" => CreateAmmConfig {
+            owner: a("owner"),
+            amm_config: a("amm_config"),
+            system_program: a("system_program"),
+            index: arg("index") as u16,
+            trade_fee_rate: arg("trade_fee_rate"),
+            protocol_fee_rate: arg("protocol_fee_rate"),
+            fund_fee_rate: arg("fund_fee_rate"),
+            create_pool_fee: arg("create_pool_fee"),
+            creator_fee_rate: arg("creator_fee_rate"),
+        }
+        .execute(svm),
+        "update_amm_config" => UpdateAmmConfig {
+            owner: a("owner"),
+            amm_config: a("amm_config"),
+            param: match arg("param") as u8 {
+                UpdateAmmConfigParam::TRADE_FEE_RATE => UpdateAmmConfigParam::TradeFeeRate(arg("value")),
+                UpdateAmmConfigParam::PROTOCOL_FEE_RATE => UpdateAmmConfigParam::ProtocolFeeRate(arg("value")),
+                UpdateAmmConfigParam::FUND_FEE_RATE => UpdateAmmConfigParam::FundFeeRate(arg("value")),
+                UpdateAmmConfigParam::CREATE_POOL_FEE => UpdateAmmConfigParam::CreatePoolFee(arg("value")),
+                UpdateAmmConfigParam::CREATOR_FEE_RATE => UpdateAmmConfigParam::CreatorFeeRate(arg("value")),
+                param => panic!("no fee rate param {param}"),
+            },
+        }
+        .execute(svm),
+        "update_pool_status" => UpdatePoolStatus {
+            authority: a("authority"),
+            pool_state: a("pool_state"),
+            status: arg("status") as u8,
+        }
+        .execute(svm),
+        "collect_protocol_fee" => CollectProtocolFee {
+            owner: a("owner"), authority: a("authority"), pool_state: a("pool_state"),
+            amm_config: a("amm_config"), token_0_vault: a("token_0_vault"),
+            token_1_vault: a("token_1_vault"), vault_0_mint: a("vault_0_mint"),
+            vault_1_mint: a("vault_1_mint"),
+            recipient_token_0_account: a("recipient_token_0_account"),
+            recipient_token_1_account: a("recipient_token_1_account"),
+            token_program: a("token_program"), token_program_2022: a("token_program_2022"),
+            amount_0_requested: arg("amount_0_requested"),
+            amount_1_requested: arg("amount_1_requested"),
+        }
+        .execute(svm),
+        "collect_fund_fee" => CollectFundFee {
+            owner: a("owner"), authority: a("authority"), pool_state: a("pool_state"),
+            amm_config: a("amm_config"), token_0_vault: a("token_0_vault"),
+            token_1_vault: a("token_1_vault"), vault_0_mint: a("vault_0_mint"),
+            vault_1_mint: a("vault_1_mint"),
+            recipient_token_0_account: a("recipient_token_0_account"),
+            recipient_token_1_account: a("recipient_token_1_account"),
+            token_program: a("token_program"), token_program_2022: a("token_program_2022"),
+            amount_0_requested: arg("amount_0_requested"),
+            amount_1_requested: arg("amount_1_requested"),
+        }
+        .execute(svm),
+        "initialize_with_permission" => InitializeWithPermission {
+            payer: a("payer"), creator: a("creator"), amm_config: a("amm_config"),
+            authority: a("authority"), pool_state: a("pool_state"),
+            token_0_mint: a("token_0_mint"), token_1_mint: a("token_1_mint"),
+            lp_mint: a("lp_mint"), payer_token_0: a("payer_token_0"),
+            payer_token_1: a("payer_token_1"), payer_lp_token: a("payer_lp_token"),
+            token_0_vault: a("token_0_vault"), token_1_vault: a("token_1_vault"),
+            create_pool_fee: a("create_pool_fee"), observation_state: a("observation_state"),
+            permission: a("permission"), token_program: a("token_program"),
+            token_0_program: a("token_0_program"), token_1_program: a("token_1_program"),
+            associated_token_program: a("associated_token_program"),
+            system_program: a("system_program"),
+            init_amount_0: arg("init_amount_0"),
+            init_amount_1: arg("init_amount_1"),
+            open_time: arg("open_time"),
+            creator_fee_on: CreatorFeeOn::try_from(arg("creator_fee_on") as u8).unwrap(),
+        }
+        .execute(svm),
+        "collect_creator_fee" => CollectCreatorFee {
+            creator: a("creator"), authority: a("authority"), pool_state: a("pool_state"),
+            amm_config: a("amm_config"), token_0_vault: a("token_0_vault"),
+            token_1_vault: a("token_1_vault"), vault_0_mint: a("vault_0_mint"),
+            vault_1_mint: a("vault_1_mint"), creator_token_0: a("creator_token_0"),
+            creator_token_1: a("creator_token_1"), token_0_program: a("token_0_program"),
+            token_1_program: a("token_1_program"),
+            associated_token_program: a("associated_token_program"),
+            system_program: a("system_program"),
+        }
+        .execute(svm),
+        "create_permission_pda" => CreatePermissionPda {
+            owner: a("owner"),
+            permission_authority: a("permission_authority"),
+            permission: a("permission"),
+            system_program: a("system_program"),
+        }
+        .execute(svm),
+        "close_permission_pda" => ClosePermissionPda {
+            owner: a("owner"),
+            permission_authority: a("permission_authority"),
+            permission: a("permission"),
+            system_program: a("system_program"),
+        }
+        .execute(svm),
+        name => panic!("no instruction struct for {name}"),
+    }
+}
+
+#[path = "../codegen/src/json.rs"]
+mod json;
@@ -0,0 +1,701 @@
+//! The instruction data and account metas of every instruction struct, as invoked through
+//! the `test_utils::svm` harness. The expected account roles are read from the checked-in IDL.
+
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    seeds, ProgramResult,
+};
+use pinocchio_raydium_cpmm_cpi::{
+    instructions::*,
+    pda,
+    program_id,
+    states::CreatorFeeOn,
+    test_utils::{
+        svm::{Execute, Invocation, InvokedAccount, Svm},
+        AccountFixture, ProgramInput,
+    },
+};
+
+use json::Value;
+
+fn key(index: usize) -> Pubkey {
+    [index as u8 + 1; 32]
+}
+
+/// `count` accounts keyed `[1; 32]`, `[2; 32]`, ..., writable and signing when in `signers`
+fn input(count: usize, signers: &[usize]) -> ProgramInput {
+    let fixtures: Vec<_> = (0..count)
+        .map(|index| {
+            let fixture = AccountFixture::new(key(index), [0; 32], Vec::new());
+            if signers.contains(&index) {
+                fixture.signer()
+            } else {
+                fixture
+            }
+        })
+        .collect();
+    ProgramInput::new(&fixtures)
+}
+
+/// A program accepting any instruction
+fn accept(_: &Pubkey, _: &[AccountInfo], _: &[u8]) -> ProgramResult {
+    Ok(())
+}
+
+fn svm() -> Svm {
+    Svm::new().with_program(*program_id(), accept)
+}
+
+const IDL: &str = include_str!("../idl/raydium_cp_swap.json");
+
+/// The accounts of `input`, with the roles of the accounts of instruction `name` in the IDL
+fn metas(name: &str) -> Vec<InvokedAccount> {
+    let idl = json::parse(IDL).expect("invalid IDL");
+    let instruction = idl["instructions"]
+        .as_array()
+        .iter()
+        .find(|instruction| instruction["name"].as_str() == name)
+        .unwrap_or_else(|| panic!("{name} is not in the IDL"));
+    instruction["accounts"]
+        .as_array()
+        .iter()
+        .enumerate()
+        .map(|(index, account)| {
+            let flag = |name| account.get(name).is_some_and(Value::as_bool);
+            InvokedAccount {
+                pubkey: key(index),
+                is_signer: flag("signer"),
+                is_writable: flag("writable"),
+            }
+        })
+        .collect()
+}
+
+fn data(discriminator: [u8; 8], args: &[&[u8]]) -> Vec<u8> {
+    let mut data = discriminator.to_vec();
+    for arg in args {
+        data.extend_from_slice(arg);
+    }
+    data
+}
+
+fn assert_invoked(svm: &Svm, accounts: Vec<InvokedAccount>, data: Vec<u8>) {
+    assert_eq!(
+        svm.invocations(),
+        vec![Invocation {
+            program_id: *program_id(),
+            accounts,
+            data,
+            depth: 1,
+        }]
+    );
+}
+
+#[test]
+fn initialize() {
+    let svm = svm();
+    let input = input(20, &[0]);
+    let a = input.accounts();
+
+    Initialize {
+        creator: &a[0], amm_config: &a[1], authority: &a[2], pool_state: &a[3],
+        token_0_mint: &a[4], token_1_mint: &a[5], lp_mint: &a[6], creator_token_0: &a[7],
+        creator_token_1: &a[8], creator_lp_token: &a[9], token_0_vault: &a[10],
+        token_1_vault: &a[11], create_pool_fee: &a[12], observation_state: &a[13],
+        token_program: &a[14], token_0_program: &a[15], token_1_program: &a[16],
+        associated_token_program: &a[17], system_program: &a[18], rent: &a[19],
+        init_amount_0: 1_000,
+        init_amount_1: 2_000,
+        open_time: 3_000,
+    }
+    .execute(&svm)
+    .unwrap();
+
+    assert_invoked(
+        &svm,
+        metas("initialize"),
+        data(
+            [175, 175, 109, 31, 13, 152, 155, 237],
+            &[&1_000u64.to_le_bytes(), &2_000u64.to_le_bytes(), &3_000u64.to_le_bytes()],
+        ),
+    );
+}
+
+#[test]
+fn initialize_with_permission() {
+    let svm = svm();
+    let input = input(21, &[0]);
+    let a = input.accounts();
+
+    InitializeWithPermission {
+        payer: &a[0], creator: &a[1], amm_config: &a[2], authority: &a[3], pool_state: &a[4],
+        token_0_mint: &a[5], token_1_mint: &a[6], lp_mint: &a[7], payer_token_0: &a[8],
+        payer_token_1: &a[9], payer_lp_token: &a[10], token_0_vault: &a[11],
+        token_1_vault: &a[12], create_pool_fee: &a[13], observation_state: &a[14],
+        permission: &a[15], token_program: &a[16], token_0_program: &a[17],
+        token_1_program: &a[18], associated_token_program: &a[19], system_program: &a[20],
+        init_amount_0: 1_000,
+        init_amount_1: 2_000,
+        open_time: 3_000,
+        creator_fee_on: CreatorFeeOn::OnlyToken1,
+    }
+    .execute(&svm)
+    .unwrap();
+
+    assert_invoked(
+        &svm,
+        metas("initialize_with_permission"),
+        data(
+            [63, 55, 254, 65, 49, 178, 89, 121],
+            &[&1_000u64.to_le_bytes(), &2_000u64.to_le_bytes(), &3_000u64.to_le_bytes(), &[2]],
+        ),
+    );
+}
+
+#[test]
+fn deposit() {
+    let svm = svm();
+    let input = input(13, &[0]);
+    let a = input.accounts();
+
+    Deposit {
+        owner: &a[0], authority: &a[1], pool_state: &a[2], owner_lp_token: &a[3],
+        token_0_account: &a[4], token_1_account: &a[5], token_0_vault: &a[6],
+        token_1_vault: &a[7], token_program: &a[8], token_program_2022: &a[9],
+        vault_0_mint: &a[10], vault_1_mint: &a[11], lp_mint: &a[12],
+        lp_token_amount: 10,
+        maximum_token_0_amount: 20,
+        maximum_token_1_amount: u64::MAX,
+    }
+    .execute(&svm)
+    .unwrap();
+
+    assert_invoked(
+        &svm,
+        metas("deposit"),
+        data(
+            [242, 35, 198, 137, 82, 225, 242, 182],
+            &[&10u64.to_le_bytes(), &20u64.to_le_bytes(), &u64::MAX.to_le_bytes()],
+        ),
+    );
+}
+
+#[test]
+fn withdraw() {
+    let svm = svm();
+    let input = input(14, &[0]);
+    let a = input.accounts();
+
+    Withdraw {
+        owner: &a[0], authority: &a[1], pool_state: &a[2], owner_lp_token: &a[3],
+        token_0_account: &a[4], token_1_account: &a[5], token_0_vault: &a[6],
+        token_1_vault: &a[7], token_program: &a[8], token_program_2022: &a[9],
+        vault_0_mint: &a[10], vault_1_mint: &a[11], lp_mint: &a[12], memo_program: &a[13],
+        lp_token_amount: 10,
+        minimum_token_0_amount: 20,
+        minimum_token_1_amount: 30,
+    }
+    .execute(&svm)
+    .unwrap();
+
+    assert_invoked(
+        &svm,
+        metas("withdraw"),
+        data(
+            [183, 18, 70, 156, 148, 109, 161, 34],
+            &[&10u64.to_le_bytes(), &20u64.to_le_bytes(), &30u64.to_le_bytes()],
+        ),
+    );
+}
+
+#[test]
+fn swap_base_input() {
+    let svm = svm();
+    let input = input(13, &[0]);
+    let a = input.accounts();
+
+    SwapBaseInput {
+        payer: &a[0], authority: &a[1], amm_config: &a[2], pool_state: &a[3],
+        input_token_account: &a[4], output_token_account: &a[5], input_vault: &a[6],
+        output_vault: &a[7], input_token_program: &a[8], output_token_program: &a[9],
+        input_token_mint: &a[10], output_token_mint: &a[11], observation_state: &a[12],
+        amount_in: 1_000,
+        minimum_amount_out: 900,
+    }
+    .execute(&svm)
+    .unwrap();
+
+    // The swap payer only signs, the program never writes to it
+    assert_eq!(metas("swap_base_input")[0], InvokedAccount::readonly_signer(key(0)));
+    assert_invoked(
+        &svm,
+        metas("swap_base_input"),
+        data(
+            [143, 190, 90, 218, 196, 30, 51, 222],
+            &[&1_000u64.to_le_bytes(), &900u64.to_le_bytes()],
+        ),
+    );
+}
+
+#[test]
+fn swap_base_output() {
+    let svm = svm();
+    let input = input(13, &[0]);
+    let a = input.accounts();
+
+    SwapBaseOutput {
+        payer: &a[0], authority: &a[1], amm_config: &a[2], pool_state: &a[3],
+        input_token_account: &a[4], output_token_account: &a[5], input_vault: &a[6],
+        output_vault: &a[7], input_token_program: &a[8], output_token_program: &a[9],
+        input_token_mint: &a[10], output_token_mint: &a[11], observation_state: &a[12],
+        max_amount_in: 1_100,
+        amount_out: 1_000,
+    }
+    .execute(&svm)
+    .unwrap();
+
+    assert_invoked(
+        &svm,
+        metas("swap_base_output"),
+        data(
+            [55, 217, 98, 86, 163, 74, 180, 173],
+            &[&1_100u64.to_le_bytes(), &1_000u64.to_le_bytes()],
+        ),
+    );
+}
+
+#[test]
+fn create_amm_config() {
+    let svm = svm();
+    let input = input(3, &[0]);
+    let a = input.accounts();
+
+    CreateAmmConfig {
+        owner: &a[0],
+        amm_config: &a[1],
+        system_program: &a[2],
+        index: 0x0102,
+        trade_fee_rate: 2_500,
+        protocol_fee_rate: 120_000,
+        fund_fee_rate: 40_000,
+        create_pool_fee: 150_000_000,
+        creator_fee_rate: 1_000,
+    }
+    .execute(&svm)
+    .unwrap();
+
+    assert_invoked(
+        &svm,
+        metas("create_amm_config"),
+        data(
+            [137, 52, 237, 212, 215, 117, 108, 104],
+            &[
+                &[0x02, 0x01],
+                &2_500u64.to_le_bytes(),
+                &120_000u64.to_le_bytes(),
+                &40_000u64.to_le_bytes(),
+                &150_000_000u64.to_le_bytes(),
+                &1_000u64.to_le_bytes(),
+            ],
+        ),
+    );
+}
+
+#[test]
+fn update_amm_config() {
+    let svm = svm();
+    let input = input(2, &[0]);
+    let a = input.accounts();
+
+    UpdateAmmConfig {
+        owner: &a[0],
+        amm_config: &a[1],
+        param: UpdateAmmConfigParam::DisableCreatePool(true),
+    }
+    .execute(&svm)
+    .unwrap();
+
+    assert_invoked(
+        &svm,
+        metas("update_amm_config"),
+        data([49, 60, 174, 136, 154, 28, 116, 200], &[&[6], &1u64.to_le_bytes()]),
+    );
+}
+
+#[test]
+fn update_amm_config_new_owner() {
+    let svm = svm();
+    let input = input(3, &[0]);
+    let a = input.accounts();
+
+    UpdateAmmConfig {
+        owner: &a[0],
+        amm_config: &a[1],
+        param: UpdateAmmConfigParam::NewFundOwner(&a[2]),
+    }
+    .execute(&svm)
+    .unwrap();
+
+    assert_invoked(
+        &svm,
+        [metas("update_amm_config"), vec![InvokedAccount::readonly(key(2))]].concat(),
+        data([49, 60, 174, 136, 154, 28, 116, 200], &[&[4], &0u64.to_le_bytes()]),
+    );
+}
+
+#[test]
+fn update_pool_status() {
+    let svm = svm();
+    let input = input(2, &[0]);
+    let a = input.accounts();
+
+    UpdatePoolStatus {
+        authority: &a[0],
+        pool_state: &a[1],
+        status: 0b101,
+    }
+    .execute(&svm)
+    .unwrap();
+
+    assert_invoked(
+        &svm,
+        metas("update_pool_status"),
+        data([130, 87, 108, 6, 46, 224, 117, 123], &[&[0b101]]),
+    );
+}
+
+#[test]
+fn collect_protocol_fee() {
+    let svm = svm();
+    let input = input(12, &[0]);
+    let a = input.accounts();
+
+    CollectProtocolFee {
+        owner: &a[0], authority: &a[1], pool_state: &a[2], amm_config: &a[3],
+        token_0_vault: &a[4], token_1_vault: &a[5], vault_0_mint: &a[6], vault_1_mint: &a[7],
+        recipient_token_0_account: &a[8], recipient_token_1_account: &a[9],
+        token_program: &a[10], token_program_2022: &a[11],
+        amount_0_requested: u64::MAX,
+        amount_1_requested: 7,
+    }
+    .execute(&svm)
+    .unwrap();
+
+    assert_invoked(
+        &svm,
+        metas("collect_protocol_fee"),
+        data(
+            [136, 136, 252, 221, 194, 66, 126, 89],
+            &[&u64::MAX.to_le_bytes(), &7u64.to_le_bytes()],
+        ),
+    );
+}
+
+#[test]
+fn collect_fund_fee() {
+    let svm = svm();
+    let input = input(12, &[0]);
+    let a = input.accounts();
+
+    CollectFundFee {
+        owner: &a[0], authority: &a[1], pool_state: &a[2], amm_config: &a[3],
+        token_0_vault: &a[4], token_1_vault: &a[5], vault_0_mint: &a[6], vault_1_mint: &a[7],
+        recipient_token_0_account: &a[8], recipient_token_1_account: &a[9],
+        token_program: &a[10], token_program_2022: &a[11],
+        amount_0_requested: 7,
+        amount_1_requested: u64::MAX,
+    }
+    .execute(&svm)
+    .unwrap();
+
+    assert_invoked(
+        &svm,
+        metas("collect_fund_fee"),
+        data(
+            [167, 138, 78, 149, 223, 194, 6, 126],
+            &[&7u64.to_le_bytes(), &u64::MAX.to_le_bytes()],
+        ),
+    );
+}
+
+#[test]
+fn collect_creator_fee() {
+    let svm = svm();
+    let input = input(14, &[0]);
+    let a = input.accounts();
+
+    CollectCreatorFee {
+        creator: &a[0], authority: &a[1], pool_state: &a[2], amm_config: &a[3],
+        token_0_vault: &a[4], token_1_vault: &a[5], vault_0_mint: &a[6], vault_1_mint: &a[7],
+        creator_token_0: &a[8], creator_token_1: &a[9], token_0_program: &a[10],
+        token_1_program: &a[11], associated_token_program: &a[12], system_program: &a[13],
+    }
+    .execute(&svm)
+    .unwrap();
+
+    assert_invoked(
+        &svm,
+        metas("collect_creator_fee"),
+        data([20, 22, 86, 123, 198, 28, 219, 132], &[]),
+    );
+}
+
+#[test]
+fn create_permission_pda() {
+    let svm = svm();
+    let input = input(4, &[0]);
+    let a = input.accounts();
+
+    CreatePermissionPda {
+        owner: &a[0],
+        permission_authority: &a[1],
+        permission: &a[2],
+        system_program: &a[3],
+    }
+    .execute(&svm)
+    .unwrap();
+
+    assert_invoked(&svm, metas("create_permission_pda"), data([135, 136, 2, 216, 137, 169, 181, 202], &[]));
+}
+
+#[test]
+fn close_permission_pda() {
+    let svm = svm();
+    let input = input(4, &[0]);
+    let a = input.accounts();
+
+    ClosePermissionPda {
+        owner: &a[0],
+        permission_authority: &a[1],
+        permission: &a[2],
+        system_program: &a[3],
+    }
+    .execute(&svm)
+    .unwrap();
+
+    assert_invoked(&svm, metas("close_permission_pda"), data([156, 84, 32, 118, 69, 135, 70, 123], &[]));
+}
+
+#[test]
+fn rejects_missing_signature() {
+    let svm = svm();
+    let input = input(2, &[]);
+    let a = input.accounts();
+
+    let result = UpdatePoolStatus {
+        authority: &a[0],
+        pool_state: &a[1],
+        status: 0,
+    }
+    .execute(&svm);
+
+    assert_eq!(result, Err(ProgramError::MissingRequiredSignature));
+    assert_eq!(svm.invocations().len(), 1);
+}
+
+/// The program signing for its PDA in `signs_for_pda_of_the_caller`
+const CALLER: Pubkey = [42; 32];
+
+/// `UpdatePoolStatus` with a PDA of `CALLER` as unsigned authority, signed with `seeds`
+fn update_pool_status_signed_by(svm: &Svm, seeds: &[Seed]) -> ProgramResult {
+    let (authority, _) = pda::try_find_program_address(&[b"authority"], &CALLER).unwrap();
+    let input = ProgramInput::new(&[
+        AccountFixture::new(authority, [0; 32], Vec::new()),
+        AccountFixture::new(key(1), [0; 32], Vec::new()),
+    ]);
+    let a = input.accounts();
+
+    UpdatePoolStatus {
+        authority: &a[0],
+        pool_state: &a[1],
+        status: 0,
+    }
+    .execute_signed(svm, &[Signer::from(seeds)])
+}
+
+#[test]
+fn signs_for_pda_of_the_caller() {
+    let svm = svm().with_caller(CALLER);
+    let (_, bump) = pda::try_find_program_address(&[b"authority"], &CALLER).unwrap();
+
+    let result = update_pool_status_signed_by(&svm, &seeds!(b"authority", &[bump]));
+
+    assert_eq!(result, Ok(()));
+}
+
+#[test]
+fn rejects_signer_seeds_of_another_address() {
+    let svm = svm().with_caller(CALLER);
+    let (_, bump) = pda::try_find_program_address(&[b"other"], &CALLER).unwrap();
+
+    let result = update_pool_status_signed_by(&svm, &seeds!(b"other", &[bump]));
+
+    assert_eq!(result, Err(ProgramError::MissingRequiredSignature));
+}
+
+#[test]
+fn rejects_signer_seeds_of_another_program() {
+    let svm = svm();
+    let (_, bump) = pda::try_find_program_address(&[b"authority"], &CALLER).unwrap();
+
+    let result = update_pool_status_signed_by(&svm, &seeds!(b"authority", &[bump]));
+
+    assert_eq!(result, Err(ProgramError::MissingRequiredSignature));
+}
+
+#[test]
+fn rejects_readonly_account_as_writable() {
+    let svm = svm();
+    let input = ProgramInput::new(&[
+        AccountFixture::new(key(0), [0; 32], Vec::new()).signer(),
+        AccountFixture::new(key(1), [0; 32], Vec::new()).readonly(),
+    ]);
+    let a = input.accounts();
+
+    let result = UpdatePoolStatus {
+        authority: &a[0],
+        pool_state: &a[1],
+        status: 0,
+    }
+    .execute(&svm);
+
+    assert_eq!(result, Err(ProgramError::InvalidArgument));
+}
+
+#[test]
+fn rejects_unknown_program() {
+    let svm = Svm::new();
+    let input = input(2, &[0]);
+    let a = input.accounts();
+
+    let result = UpdatePoolStatus {
+        authority: &a[0],
+        pool_state: &a[1],
+        status: 0,
+    }
+    .execute(&svm);
+
+    assert_eq!(result, Err(ProgramError::IncorrectProgramId));
+    assert_eq!(svm.invocations().len(), 1);
+}
+
+/// Invoke `$ix::from_accounts` on `$accounts` and check the accounts are bound in order
+macro_rules! assert_from_accounts {
+    ($svm:expr, $accounts:expr, $ix:ident $(, $arg:expr)*) => {{
+        let len = $ix::ACCOUNTS.len();
+        $svm.clear();
+        $ix::from_accounts(&$accounts[..len] $(, $arg)*).unwrap().execute(&$svm).unwrap();
+        let invocation = $svm.last_invocation().unwrap();
+        let keys: Vec<Pubkey> = invocation.accounts.iter().map(|account| account.pubkey).collect();
+        assert_eq!(keys, (0..len).map(key).collect::<Vec<_>>(), stringify!($ix));
+        assert_eq!(invocation.data.len(), $ix::DATA_LEN, stringify!($ix));
+
+        // An extra account is rejected rather than ignored
+        assert!(
+            matches!(
+                $ix::from_accounts(&$accounts[..len + 1] $(, $arg)*),
+                Err(ProgramError::InvalidArgument)
+            ),
+            stringify!($ix)
+        );
+    }};
+}
+
+#[test]
+fn from_accounts() {
+    let svm = svm();
+    // One more account than the largest instruction takes
+    let input = input(22, &[0]);
+    let a = input.accounts();
+
+    assert_from_accounts!(svm, a, Initialize, 1_000, 2_000, 3_000);
+    assert_from_accounts!(svm, a, InitializeWithPermission, 1_000, 2_000, 3_000, CreatorFeeOn::BothToken);
+    assert_from_accounts!(svm, a, Deposit, 10, 20, 30);
+    assert_from_accounts!(svm, a, Withdraw, 10, 20, 30);
+    assert_from_accounts!(svm, a, SwapBaseInput, 1_000, 900);
+    assert_from_accounts!(svm, a, SwapBaseOutput, 1_100, 1_000);
+    assert_from_accounts!(svm, a, CreateAmmConfig, 0, 2_500, 120_000, 40_000, 150_000_000, 1_000);
+    assert_from_accounts!(svm, a, UpdateAmmConfig, UpdateAmmConfigParam::TradeFeeRate(2_500));
+    assert_from_accounts!(svm, a, UpdatePoolStatus, 0);
+    assert_from_accounts!(svm, a, CollectProtocolFee, 10, 20);
+    assert_from_accounts!(svm, a, CollectFundFee, 10, 20);
+    assert_from_accounts!(svm, a, CollectCreatorFee);
+    assert_from_accounts!(svm, a, CreatePermissionPda);
+    assert_from_accounts!(svm, a, ClosePermissionPda);
+}
+
+#[test]
+fn update_amm_config_from_accounts_with_the_new_owner() {
+    let input = input(4, &[0]);
+    let a = input.accounts();
+    let new_owner = |owner| UpdateAmmConfigParam::NewFundOwner(owner);
+
+    // The new owner may follow the accounts of the instruction, or be left out of them
+    let update = UpdateAmmConfig::from_accounts(&a[..3], new_owner(&a[2])).unwrap();
+    assert_eq!(update.amm_config.key(), &key(1));
+    assert!(UpdateAmmConfig::from_accounts(&a[..2], new_owner(&a[2])).is_ok());
+
+    // Any other account is rejected
+    assert!(matches!(
+        UpdateAmmConfig::from_accounts(&a[..3], new_owner(&a[3])),
+        Err(ProgramError::InvalidArgument)
+    ));
+    assert!(matches!(
+        UpdateAmmConfig::from_accounts(a, new_owner(&a[2])),
+        Err(ProgramError::InvalidArgument)
+    ));
+}
+
+#[test]
+fn from_accounts_binds_arguments() {
+    let svm = svm();
+    let input = input(13, &[0]);
+
+    SwapBaseInput::from_accounts(input.accounts(), 1_000, 900).unwrap().execute(&svm).unwrap();
+
+    assert_invoked(
+        &svm,
+        metas("swap_base_input"),
+        data(
+            [143, 190, 90, 218, 196, 30, 51, 222],
+            &[&1_000u64.to_le_bytes(), &900u64.to_le_bytes()],
+        ),
+    );
+}
+
+#[test]
+fn from_accounts_rejects_missing_accounts() {
+    let input = input(12, &[0]);
+    let a = input.accounts();
+
+    assert!(matches!(
+        SwapBaseInput::from_accounts(a, 1_000, 900),
+        Err(ProgramError::NotEnoughAccountKeys)
+    ));
+    assert!(matches!(SwapAccounts::from_accounts(a), Err(ProgramError::NotEnoughAccountKeys)));
+    assert!(matches!(
+        UpdateAmmConfig::from_accounts(&a[..1], UpdateAmmConfigParam::DisableCreatePool(true)),
+        Err(ProgramError::NotEnoughAccountKeys)
+    ));
+}
+
+#[test]
+fn swap_accounts_from_accounts() {
+    let input = input(14, &[0]);
+    let a = input.accounts();
+
+    let accounts = SwapAccounts::from_accounts(&a[..13]).unwrap();
+
+    assert_eq!(accounts.payer.key(), &key(0));
+    assert_eq!(accounts.input_vault.key(), &key(6));
+    assert_eq!(accounts.observation_state.key(), &key(12));
+    assert!(matches!(SwapAccounts::from_accounts(a), Err(ProgramError::InvalidArgument)));
+}
+
+// Shared with the generator, which reads more of the IDL than the metas
+#[allow(dead_code)]
+#[path = "../codegen/src/json.rs"]
+mod json;
@@ -0,0 +1,39 @@
+//! Best-effort layout revision of pool and config accounts
+
+use pinocchio_raydium_cpmm_cpi::{
+    states::{CreatorFeeOn, LayoutVersion},
+    test_utils::{AmmConfigBuilder, PoolStateBuilder},
+};
+
+#[test]
+fn pool_layout_version() {
+    let legacy = PoolStateBuilder::new().build();
+    assert_eq!(legacy.guess_layout_version(), LayoutVersion::Legacy);
+
+    let creator_fee = PoolStateBuilder::new().creator_fee_on(CreatorFeeOn::OnlyToken1).build();
+    assert_eq!(creator_fee.guess_layout_version(), LayoutVersion::CreatorFee);
+}
+
+#[test]
+fn pool_with_set_padding_is_legacy() {
+    // Legacy padding bytes read as creator fee amounts or an invalid mode
+    let mut pool = PoolStateBuilder::new().creator_fees(7, 9).build();
+    assert_eq!(pool.guess_layout_version(), LayoutVersion::Legacy);
+
+    pool.creator_fee_on = 3;
+    pool.enable_creator_fee = true;
+    assert_eq!(pool.guess_layout_version(), LayoutVersion::Legacy);
+}
+
+#[test]
+fn config_layout_version() {
+    assert_eq!(AmmConfigBuilder::new().build().guess_layout_version(), LayoutVersion::Legacy);
+    assert_eq!(
+        AmmConfigBuilder::new().creator_fee_rate(1_000).build().guess_layout_version(),
+        LayoutVersion::CreatorFee
+    );
+    assert_eq!(
+        AmmConfigBuilder::new().creator_fee_rate(u64::MAX).build().guess_layout_version(),
+        LayoutVersion::Legacy
+    );
+}
@@ -0,0 +1,966 @@
+//! The state changes of the instructions supported by `mock_program`, invoked through the
+//! `test_utils::svm` harness.
+
+use pinocchio::{
+    account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey, ProgramResult,
+};
+use pinocchio_raydium_cpmm_cpi::{
+    curve::TradeDirection,
+    error::{CpmmError, ValidationError},
+    admin,
+    instructions::{UpdateAmmConfigParam as Param, *},
+    memo_program,
+    mock_program::{self, AnchorError},
+    pda, program_id,
+    states::{AmmConfig, CreatorFeeOn, ObservationState, Permission, PoolState},
+    test_utils::{
+        account_data, mint_data, mint_data_with_authority,
+        svm::{Execute, Invocation, Svm},
+        token_account_data, AccountFixture, AmmConfigBuilder, ObservationStateBuilder,
+        PoolStateBuilder, ProgramInput,
+    },
+    token_program, token_program_2022,
+};
+
+/// The unix timestamp seen by the mock program in every test
+const NOW: u64 = 1_700_000_000;
+
+const PAYER: usize = 0;
+const AUTHORITY: usize = 1;
+const AMM_CONFIG: usize = 2;
+const POOL_STATE: usize = 3;
+const TOKEN_0_MINT: usize = 4;
+const TOKEN_1_MINT: usize = 5;
+const LP_MINT: usize = 6;
+const PAYER_TOKEN_0: usize = 7;
+const PAYER_TOKEN_1: usize = 8;
+const PAYER_LP_TOKEN: usize = 9;
+const TOKEN_0_VAULT: usize = 10;
+const TOKEN_1_VAULT: usize = 11;
+const OBSERVATION_STATE: usize = 12;
+const CREATE_POOL_FEE: usize = 13;
+const TOKEN_PROGRAM: usize = 14;
+const TOKEN_PROGRAM_2022: usize = 15;
+const MEMO_PROGRAM: usize = 16;
+const ASSOCIATED_TOKEN_PROGRAM: usize = 17;
+const SYSTEM_PROGRAM: usize = 18;
+const RENT: usize = 19;
+const ADMIN: usize = 20;
+/// The uninitialized `AmmConfig` of index 1
+const NEW_AMM_CONFIG: usize = 21;
+/// The uninitialized `Permission` of the payer
+const PERMISSION: usize = 22;
+const CREATOR: usize = 23;
+
+/// The lamports of the permission account, returned to the admin when closed
+const PERMISSION_RENT: u64 = 1_350_000;
+
+/// The balance of each payer token account before the instruction
+const PAYER_BALANCE: u64 = 1_000_000;
+const PAYER_LP_BALANCE: u64 = 500_000;
+
+fn key(index: usize) -> Pubkey {
+    match index {
+        AUTHORITY => pda::find_authority_address().0,
+        ADMIN => admin::ID,
+        NEW_AMM_CONFIG => pda::find_amm_config_address(1).0,
+        PERMISSION => pda::find_permission_address(&key(PAYER)).0,
+        index => [index as u8 + 1; 32],
+    }
+}
+
+/// The accounts of a pool holding `vault_0` and `vault_1`, or of a pool to initialize when
+/// `pool_state` is `None`
+struct Fixture {
+    input: ProgramInput,
+}
+
+impl Fixture {
+    fn new(pool_state: Option<PoolState>, vault_0: u64, vault_1: u64) -> Self {
+        mock_program::set_unix_timestamp(NOW);
+
+        let (pool_state, observation_state, lp_supply, payer_lp) = match pool_state {
+            Some(pool_state) => (
+                account_data(&pool_state),
+                ObservationStateBuilder::new().pool_id(key(POOL_STATE)).account_data(),
+                pool_state.lp_supply,
+                PAYER_LP_BALANCE,
+            ),
+            None => (
+                vec![0; PoolState::LEN + 8],
+                vec![0; ObservationState::LEN + 8],
+                0,
+                0,
+            ),
+        };
+        let token = |mint: usize, owner: usize, amount: u64| {
+            AccountFixture::new(key(mint), token_program::ID, token_account_data(&key(mint), &key(owner), amount))
+        };
+        let with_key = |fixture: AccountFixture, index: usize| AccountFixture {
+            key: key(index),
+            ..fixture
+        };
+
+        let fixtures = [
+            AccountFixture::new(key(PAYER), [0; 32], Vec::new()).signer(),
+            AccountFixture::new(key(AUTHORITY), [0; 32], Vec::new()).readonly(),
+            AccountFixture::cpmm(key(AMM_CONFIG), amm_config().account_data()),
+            AccountFixture::cpmm(key(POOL_STATE), pool_state),
+            AccountFixture::new(key(TOKEN_0_MINT), token_program::ID, mint_data(10_000_000, 6)).readonly(),
+            AccountFixture::new(key(TOKEN_1_MINT), token_program::ID, mint_data(10_000_000, 9)).readonly(),
+            AccountFixture::new(
+                key(LP_MINT),
+                token_program::ID,
+                mint_data_with_authority(&key(AUTHORITY), lp_supply, 9),
+            ),
+            with_key(token(TOKEN_0_MINT, PAYER, PAYER_BALANCE), PAYER_TOKEN_0),
+            with_key(token(TOKEN_1_MINT, PAYER, PAYER_BALANCE), PAYER_TOKEN_1),
+            with_key(token(LP_MINT, PAYER, payer_lp), PAYER_LP_TOKEN),
+            with_key(token(TOKEN_0_MINT, AUTHORITY, vault_0), TOKEN_0_VAULT),
+            with_key(token(TOKEN_1_MINT, AUTHORITY, vault_1), TOKEN_1_VAULT),
+            AccountFixture::cpmm(key(OBSERVATION_STATE), observation_state),
+            with_key(token(TOKEN_0_MINT, CREATE_POOL_FEE, 0), CREATE_POOL_FEE),
+            AccountFixture::program(token_program::ID),
+            AccountFixture::program(token_program_2022::ID),
+            AccountFixture::program(memo_program::ID),
+            AccountFixture::program(key(ASSOCIATED_TOKEN_PROGRAM)),
+            AccountFixture::program(key(SYSTEM_PROGRAM)),
+            AccountFixture::program(key(RENT)),
+            AccountFixture::new(key(ADMIN), [0; 32], Vec::new()).signer(),
+            AccountFixture::cpmm(key(NEW_AMM_CONFIG), vec![0; AmmConfig::LEN + 8]),
+            AccountFixture::cpmm(key(PERMISSION), vec![0; Permission::LEN + 8]).lamports(PERMISSION_RENT),
+            AccountFixture::new(key(CREATOR), [0; 32], Vec::new()).signer().readonly(),
+        ];
+
+        Fixture {
+            input: ProgramInput::new(&fixtures),
+        }
+    }
+
+    /// An open pool of `1_000_000` token 0 and `2_000_000` token 1
+    fn pool() -> Self {
+        Self::new(Some(pool_state()), 1_000_000, 2_000_000)
+    }
+
+    fn account(&self, index: usize) -> &AccountInfo {
+        &self.input.accounts()[index]
+    }
+
+    fn amount(&self, index: usize) -> u64 {
+        let data = self.account(index).try_borrow_data().unwrap();
+        u64::from_le_bytes(data[64..72].try_into().unwrap())
+    }
+
+    fn supply(&self, index: usize) -> u64 {
+        let data = self.account(index).try_borrow_data().unwrap();
+        u64::from_le_bytes(data[36..44].try_into().unwrap())
+    }
+
+    fn pool_state(&self) -> PoolState {
+        *PoolState::from_account_info(self.account(POOL_STATE)).unwrap()
+    }
+
+    fn amm_config(&self, index: usize) -> AmmConfig {
+        *AmmConfig::from_account_info(self.account(index)).unwrap()
+    }
+
+    fn observation_state(&self) -> ObservationState {
+        *ObservationState::from_account_info(self.account(OBSERVATION_STATE)).unwrap()
+    }
+
+    fn swap_accounts(&self, direction: TradeDirection) -> SwapAccounts<'_> {
+        let token_0 = (PAYER_TOKEN_0, TOKEN_0_VAULT, TOKEN_0_MINT);
+        let token_1 = (PAYER_TOKEN_1, TOKEN_1_VAULT, TOKEN_1_MINT);
+        let (input, output) = match direction {
+            TradeDirection::ZeroForOne => (token_0, token_1),
+            TradeDirection::OneForZero => (token_1, token_0),
+        };
+        SwapAccounts {
+            payer: self.account(PAYER),
+            authority: self.account(AUTHORITY),
+            amm_config: self.account(AMM_CONFIG),
+            pool_state: self.account(POOL_STATE),
+            input_token_account: self.account(input.0),
+            output_token_account: self.account(output.0),
+            input_vault: self.account(input.1),
+            output_vault: self.account(output.1),
+            input_token_program: self.account(TOKEN_PROGRAM),
+            output_token_program: self.account(TOKEN_PROGRAM),
+            input_token_mint: self.account(input.2),
+            output_token_mint: self.account(output.2),
+            observation_state: self.account(OBSERVATION_STATE),
+        }
+    }
+}
+
+fn amm_config() -> AmmConfigBuilder {
+    AmmConfigBuilder::new()
+        .trade_fee_rate(2_500)
+        .protocol_fee_rate(120_000)
+        .fund_fee_rate(40_000)
+        .protocol_owner(key(PAYER))
+        .fund_owner(key(PAYER))
+}
+
+fn pool_state() -> PoolState {
+    PoolStateBuilder::new()
+        .amm_config(key(AMM_CONFIG))
+        .pool_creator(key(PAYER))
+        .mints(key(TOKEN_0_MINT), key(TOKEN_1_MINT))
+        .vaults(key(TOKEN_0_VAULT), key(TOKEN_1_VAULT))
+        .lp_mint(key(LP_MINT))
+        .observation_key(key(OBSERVATION_STATE))
+        .auth_bump(pda::find_authority_address().1)
+        .decimals(9, 6, 9)
+        .lp_supply(1_000_000)
+        .build()
+}
+
+/// Return the program and depth of each invocation, and the first byte of its data
+fn trace(invocations: &[Invocation]) -> Vec<(Pubkey, usize, u8)> {
+    invocations
+        .iter()
+        .map(|invocation| (invocation.program_id, invocation.depth, invocation.data[0]))
+        .collect()
+}
+
+/// `Initialize` of a pool to initialize, with the accounts of `replace` substituted
+fn initialize_with(
+    svm: &Svm,
+    fixture: &Fixture,
+    replace: &[(usize, usize)],
+) -> Result<(), ProgramError> {
+    let a = |index| {
+        let index = replace.iter().find(|(from, _)| *from == index).map_or(index, |(_, to)| *to);
+        fixture.account(index)
+    };
+    Initialize {
+        creator: a(PAYER), amm_config: a(AMM_CONFIG), authority: a(AUTHORITY),
+        pool_state: a(POOL_STATE), token_0_mint: a(TOKEN_0_MINT), token_1_mint: a(TOKEN_1_MINT),
+        lp_mint: a(LP_MINT), creator_token_0: a(PAYER_TOKEN_0), creator_token_1: a(PAYER_TOKEN_1),
+        creator_lp_token: a(PAYER_LP_TOKEN), token_0_vault: a(TOKEN_0_VAULT),
+        token_1_vault: a(TOKEN_1_VAULT), create_pool_fee: a(CREATE_POOL_FEE),
+        observation_state: a(OBSERVATION_STATE), token_program: a(TOKEN_PROGRAM),
+        token_0_program: a(TOKEN_PROGRAM), token_1_program: a(TOKEN_PROGRAM),
+        associated_token_program: a(ASSOCIATED_TOKEN_PROGRAM), system_program: a(SYSTEM_PROGRAM),
+        rent: a(RENT),
+        init_amount_0: 400_000,
+        init_amount_1: 900_000,
+        open_time: 0,
+    }
+    .execute(svm)
+}
+
+fn svm() -> Svm {
+    Svm::new().with_mock_cpmm()
+}
+
+#[test]
+fn initialize() {
+    let svm = svm();
+    let fixture = Fixture::new(None, 0, 0);
+
+    initialize_with(&svm, &fixture, &[]).unwrap();
+
+    // liquidity is sqrt(400_000 * 900_000), of which 100 lp tokens are locked
+    assert_eq!(fixture.amount(PAYER_TOKEN_0), PAYER_BALANCE - 400_000);
+    assert_eq!(fixture.amount(PAYER_TOKEN_1), PAYER_BALANCE - 900_000);
+    assert_eq!(fixture.amount(TOKEN_0_VAULT), 400_000);
+    assert_eq!(fixture.amount(TOKEN_1_VAULT), 900_000);
+    assert_eq!(fixture.amount(PAYER_LP_TOKEN), 599_900);
+    assert_eq!(fixture.supply(LP_MINT), 599_900);
+
+    let pool_state = fixture.pool_state();
+    assert_eq!({ pool_state.lp_supply }, 600_000);
+    assert_eq!(pool_state.amm_config, key(AMM_CONFIG));
+    assert_eq!(pool_state.pool_creator, key(PAYER));
+    assert_eq!(pool_state.token_0_vault, key(TOKEN_0_VAULT));
+    assert_eq!(pool_state.token_1_vault, key(TOKEN_1_VAULT));
+    assert_eq!(pool_state.lp_mint, key(LP_MINT));
+    assert_eq!(pool_state.observation_key, key(OBSERVATION_STATE));
+    assert_eq!((pool_state.mint_0_decimals, pool_state.mint_1_decimals), (6, 9));
+    assert_eq!({ pool_state.open_time }, NOW + 1);
+    assert_eq!(pool_state.auth_bump, pda::find_authority_address().1);
+    assert_eq!(fixture.observation_state().pool_id, key(POOL_STATE));
+
+    assert_eq!(
+        trace(&svm.invocations()),
+        vec![
+            (*program_id(), 1, Initialize::DISCRIMINATOR[0]),
+            (token_program::ID, 2, 12),
+            (token_program::ID, 2, 12),
+            (token_program::ID, 2, 7),
+        ]
+    );
+}
+
+#[test]
+fn initialize_with_other_authority() {
+    let svm = svm();
+    let fixture = Fixture::new(None, 0, 0);
+
+    let result = initialize_with(&svm, &fixture, &[(AUTHORITY, CREATE_POOL_FEE)]);
+
+    assert_eq!(result, Err(AnchorError::ConstraintSeeds.into()));
+    assert_eq!(fixture.amount(PAYER_TOKEN_0), PAYER_BALANCE);
+}
+
+#[test]
+fn initialize_with_other_mint_token_program() {
+    let svm = svm();
+    let fixture = Fixture::new(None, 0, 0);
+
+    let result = initialize_with(&svm, &fixture, &[(TOKEN_PROGRAM, TOKEN_PROGRAM_2022)]);
+
+    assert_eq!(result, Err(AnchorError::ConstraintMintTokenProgram.into()));
+}
+
+#[test]
+fn deposit() {
+    let svm = svm();
+    let fixture = Fixture::pool();
+    let a = |index| fixture.account(index);
+
+    Deposit {
+        owner: a(PAYER), authority: a(AUTHORITY), pool_state: a(POOL_STATE),
+        owner_lp_token: a(PAYER_LP_TOKEN), token_0_account: a(PAYER_TOKEN_0),
+        token_1_account: a(PAYER_TOKEN_1), token_0_vault: a(TOKEN_0_VAULT),
+        token_1_vault: a(TOKEN_1_VAULT), token_program: a(TOKEN_PROGRAM),
+        token_program_2022: a(TOKEN_PROGRAM_2022), vault_0_mint: a(TOKEN_0_MINT),
+        vault_1_mint: a(TOKEN_1_MINT), lp_mint: a(LP_MINT),
+        lp_token_amount: 100_000,
+        maximum_token_0_amount: 100_000,
+        maximum_token_1_amount: 200_000,
+    }
+    .execute(&svm)
+    .unwrap();
+
+    assert_eq!(fixture.amount(PAYER_TOKEN_0), PAYER_BALANCE - 100_000);
+    assert_eq!(fixture.amount(PAYER_TOKEN_1), PAYER_BALANCE - 200_000);
+    assert_eq!(fixture.amount(TOKEN_0_VAULT), 1_100_000);
+    assert_eq!(fixture.amount(TOKEN_1_VAULT), 2_200_000);
+    assert_eq!(fixture.amount(PAYER_LP_TOKEN), PAYER_LP_BALANCE + 100_000);
+    assert_eq!(fixture.supply(LP_MINT), 1_100_000);
+    assert_eq!({ fixture.pool_state().lp_supply }, 1_100_000);
+
+    assert_eq!(
+        trace(&svm.invocations()),
+        vec![
+            (*program_id(), 1, Deposit::DISCRIMINATOR[0]),
+            (token_program::ID, 2, 12),
+            (token_program::ID, 2, 12),
+            (token_program::ID, 2, 7),
+        ]
+    );
+}
+
+#[test]
+fn deposit_exceeding_slippage() {
+    let svm = svm();
+    let fixture = Fixture::pool();
+    let a = |index| fixture.account(index);
+
+    let result = Deposit {
+        owner: a(PAYER), authority: a(AUTHORITY), pool_state: a(POOL_STATE),
+        owner_lp_token: a(PAYER_LP_TOKEN), token_0_account: a(PAYER_TOKEN_0),
+        token_1_account: a(PAYER_TOKEN_1), token_0_vault: a(TOKEN_0_VAULT),
+        token_1_vault: a(TOKEN_1_VAULT), token_program: a(TOKEN_PROGRAM),
+        token_program_2022: a(TOKEN_PROGRAM_2022), vault_0_mint: a(TOKEN_0_MINT),
+        vault_1_mint: a(TOKEN_1_MINT), lp_mint: a(LP_MINT),
+        lp_token_amount: 100_000,
+        maximum_token_0_amount: 100_000,
+        maximum_token_1_amount: 199_999,
+    }
+    .execute(&svm);
+
+    assert_eq!(result, Err(CpmmError::ExceededSlippage.into()));
+    assert_eq!(fixture.amount(PAYER_TOKEN_1), PAYER_BALANCE);
+    assert_eq!({ fixture.pool_state().lp_supply }, 1_000_000);
+}
+
+#[test]
+fn withdraw() {
+    let svm = svm();
+    let fixture = Fixture::pool();
+    let a = |index| fixture.account(index);
+
+    Withdraw {
+        owner: a(PAYER), authority: a(AUTHORITY), pool_state: a(POOL_STATE),
+        owner_lp_token: a(PAYER_LP_TOKEN), token_0_account: a(PAYER_TOKEN_0),
+        token_1_account: a(PAYER_TOKEN_1), token_0_vault: a(TOKEN_0_VAULT),
+        token_1_vault: a(TOKEN_1_VAULT), token_program: a(TOKEN_PROGRAM),
+        token_program_2022: a(TOKEN_PROGRAM_2022), vault_0_mint: a(TOKEN_0_MINT),
+        vault_1_mint: a(TOKEN_1_MINT), lp_mint: a(LP_MINT), memo_program: a(MEMO_PROGRAM),
+        lp_token_amount: 100_000,
+        minimum_token_0_amount: 100_000,
+        minimum_token_1_amount: 200_000,
+    }
+    .execute(&svm)
+    .unwrap();
+
+    assert_eq!(fixture.amount(PAYER_TOKEN_0), PAYER_BALANCE + 100_000);
+    assert_eq!(fixture.amount(PAYER_TOKEN_1), PAYER_BALANCE + 200_000);
+    assert_eq!(fixture.amount(TOKEN_0_VAULT), 900_000);
+    assert_eq!(fixture.amount(TOKEN_1_VAULT), 1_800_000);
+    assert_eq!(fixture.amount(PAYER_LP_TOKEN), PAYER_LP_BALANCE - 100_000);
+    assert_eq!(fixture.supply(LP_MINT), 900_000);
+    assert_eq!({ fixture.pool_state().lp_supply }, 900_000);
+
+    assert_eq!(
+        trace(&svm.invocations()),
+        vec![
+            (*program_id(), 1, Withdraw::DISCRIMINATOR[0]),
+            (token_program::ID, 2, 8),
+            (token_program::ID, 2, 12),
+            (token_program::ID, 2, 12),
+        ]
+    );
+}
+
+#[test]
+fn swap_base_input() {
+    let svm = svm();
+    let fixture = Fixture::pool();
+
+    let swap = SwapBaseInput::with_slippage(fixture.swap_accounts(TradeDirection::ZeroForOne), 10_000, 0).unwrap();
+    // a 25 trade fee, then 2_000_000 * 9_975 / (1_000_000 + 9_975) rounded down
+    assert_eq!(swap.minimum_amount_out, 19_752);
+    swap.execute(&svm).unwrap();
+
+    assert_eq!(fixture.amount(PAYER_TOKEN_0), PAYER_BALANCE - 10_000);
+    assert_eq!(fixture.amount(PAYER_TOKEN_1), PAYER_BALANCE + 19_752);
+    assert_eq!(fixture.amount(TOKEN_0_VAULT), 1_010_000);
+    assert_eq!(fixture.amount(TOKEN_1_VAULT), 2_000_000 - 19_752);
+
+    // a 25 trade fee, of which 3.0 go to the protocol and 1.0 to the fund
+    let pool_state = fixture.pool_state();
+    assert_eq!({ pool_state.protocol_fees_token_0 }, 3);
+    assert_eq!({ pool_state.fund_fees_token_0 }, 1);
+    assert_eq!({ pool_state.protocol_fees_token_1 }, 0);
+
+    let observation_state = fixture.observation_state();
+    assert!(observation_state.initialized);
+    assert_eq!({ observation_state.observations[0].block_timestamp }, NOW);
+
+    assert_eq!(
+        trace(&svm.invocations()),
+        vec![
+            (*program_id(), 1, SwapBaseInput::DISCRIMINATOR[0]),
+            (token_program::ID, 2, 12),
+            (token_program::ID, 2, 12),
+        ]
+    );
+}
+
+#[test]
+fn swap_base_input_exceeding_slippage() {
+    let svm = svm();
+    let fixture = Fixture::pool();
+
+    let result = SwapBaseInput {
+        minimum_amount_out: 19_753,
+        ..SwapBaseInput::with_slippage(fixture.swap_accounts(TradeDirection::ZeroForOne), 10_000, 0).unwrap()
+    }
+    .execute(&svm);
+
+    assert_eq!(result, Err(CpmmError::ExceededSlippage.into()));
+    assert_eq!(fixture.amount(PAYER_TOKEN_0), PAYER_BALANCE);
+    assert!(!fixture.observation_state().initialized);
+}
+
+#[test]
+fn swap_base_output() {
+    let svm = svm();
+    let fixture = Fixture::pool();
+
+    let swap = SwapBaseOutput::with_slippage(fixture.swap_accounts(TradeDirection::OneForZero), 10_000, 0).unwrap();
+    // 2_000_000 * 10_000 / 990_000 = 20_202.02 rounded up, then 20_203 / 0.9975 = 20_253.6
+    assert_eq!(swap.max_amount_in, 20_254);
+    swap.execute(&svm).unwrap();
+
+    assert_eq!(fixture.amount(PAYER_TOKEN_0), PAYER_BALANCE + 10_000);
+    assert_eq!(fixture.amount(PAYER_TOKEN_1), PAYER_BALANCE - 20_254);
+    assert_eq!(fixture.amount(TOKEN_0_VAULT), 990_000);
+    assert_eq!(fixture.amount(TOKEN_1_VAULT), 2_000_000 + 20_254);
+
+    // a 51 trade fee, of which 6.12 go to the protocol and 2.04 to the fund
+    let pool_state = fixture.pool_state();
+    assert_eq!({ pool_state.protocol_fees_token_1 }, 6);
+    assert_eq!({ pool_state.fund_fees_token_1 }, 2);
+    // the constant product of the vaults less the fees they owe does not decrease
+    let (vault_0, vault_1) = pool_state
+        .vault_amount_without_fee(fixture.amount(TOKEN_0_VAULT), fixture.amount(TOKEN_1_VAULT))
+        .unwrap();
+    assert!(u128::from(vault_0) * u128::from(vault_1) >= 1_000_000 * 2_000_000);
+    assert!(fixture.observation_state().initialized);
+
+    assert_eq!(
+        trace(&svm.invocations()),
+        vec![
+            (*program_id(), 1, SwapBaseOutput::DISCRIMINATOR[0]),
+            (token_program::ID, 2, 12),
+            (token_program::ID, 2, 12),
+        ]
+    );
+}
+
+#[test]
+fn swaps_with_slippage() {
+    let fixture = Fixture::pool();
+
+    // 19_752 lowered by 0.5%, 19_653.24
+    let swap = SwapBaseInput::with_slippage(fixture.swap_accounts(TradeDirection::ZeroForOne), 10_000, 50).unwrap();
+    assert_eq!(swap.minimum_amount_out, 19_653);
+    // 20_254 raised by 0.5%, 20_355.27
+    let swap = SwapBaseOutput::with_slippage(fixture.swap_accounts(TradeDirection::OneForZero), 10_000, 50).unwrap();
+    assert_eq!(swap.max_amount_in, 20_356);
+
+    let result = SwapBaseInput::with_slippage(fixture.swap_accounts(TradeDirection::ZeroForOne), 10_000, 10_001);
+    assert_eq!(result.err(), Some(ProgramError::InvalidArgument));
+}
+
+#[test]
+fn swap_quotes_with_other_accounts() {
+    let fixture = Fixture::pool();
+    let accounts = fixture.swap_accounts(TradeDirection::ZeroForOne);
+
+    let other_config = SwapAccounts { amm_config: fixture.account(PAYER), ..accounts };
+    assert_eq!(
+        SwapBaseInput::with_slippage(other_config, 10_000, 0).err(),
+        Some(ValidationError::InvalidAmmConfig.into())
+    );
+
+    let same_vaults = SwapAccounts { output_vault: accounts.input_vault, ..accounts };
+    assert_eq!(
+        SwapBaseOutput::with_slippage(same_vaults, 10_000, 0).err(),
+        Some(ValidationError::InvalidSwapVaults.into())
+    );
+}
+
+#[test]
+fn swap_with_other_authority() {
+    let svm = svm();
+    let fixture = Fixture::pool();
+
+    let result = SwapBaseInput {
+        authority: fixture.account(PAYER),
+        ..SwapBaseInput::with_slippage(fixture.swap_accounts(TradeDirection::ZeroForOne), 10_000, 0).unwrap()
+    }
+    .execute(&svm);
+
+    assert_eq!(result, Err(AnchorError::ConstraintSeeds.into()));
+}
+
+#[test]
+fn swap_with_other_auth_bump() {
+    let svm = svm();
+    let mut pool_state = pool_state();
+    pool_state.auth_bump = pool_state.auth_bump.wrapping_sub(1);
+    let fixture = Fixture::new(Some(pool_state), 1_000_000, 2_000_000);
+
+    let result = SwapBaseInput::with_slippage(fixture.swap_accounts(TradeDirection::ZeroForOne), 10_000, 0)
+        .unwrap()
+        .execute(&svm);
+
+    assert_eq!(result, Err(AnchorError::ConstraintSeeds.into()));
+}
+
+#[test]
+fn swap_overflowing_protocol_fees() {
+    let svm = svm();
+    // A vault full of protocol fees, leaving a single token 0 to trade against
+    let pool_state = PoolState {
+        protocol_fees_token_0: u64::MAX - 1,
+        ..pool_state()
+    };
+    let fixture = Fixture::new(Some(pool_state), u64::MAX, 2_000_000);
+
+    let result = SwapBaseInput {
+        minimum_amount_out: 0,
+        ..SwapBaseInput::with_slippage(fixture.swap_accounts(TradeDirection::ZeroForOne), 10_000, 0).unwrap()
+    }
+    .execute(&svm);
+
+    assert_eq!(result, Err(CpmmError::MathOverflow.into()));
+    assert_eq!({ fixture.pool_state().protocol_fees_token_0 }, u64::MAX - 1);
+    assert_eq!(fixture.amount(PAYER_TOKEN_0), PAYER_BALANCE);
+}
+
+fn create_amm_config(
+    svm: &Svm,
+    fixture: &Fixture,
+    owner: usize,
+    trade_fee_rate: u64,
+) -> ProgramResult {
+    let a = |index| fixture.account(index);
+    CreateAmmConfig {
+        owner: a(owner),
+        amm_config: a(NEW_AMM_CONFIG),
+        system_program: a(SYSTEM_PROGRAM),
+        index: 1,
+        trade_fee_rate,
+        protocol_fee_rate: 120_000,
+        fund_fee_rate: 40_000,
+        create_pool_fee: 150_000_000,
+        creator_fee_rate: 1_000,
+    }
+    .execute(svm)
+}
+
+#[test]
+fn create_amm_config_by_admin() {
+    let svm = svm();
+    let fixture = Fixture::pool();
+
+    create_amm_config(&svm, &fixture, ADMIN, 2_500).unwrap();
+
+    let amm_config = fixture.amm_config(NEW_AMM_CONFIG);
+    assert_eq!(amm_config.bump, pda::find_amm_config_address(1).1);
+    assert!(!amm_config.disable_create_pool);
+    assert_eq!({ amm_config.index }, 1);
+    assert_eq!({ amm_config.trade_fee_rate }, 2_500);
+    assert_eq!({ amm_config.protocol_fee_rate }, 120_000);
+    assert_eq!({ amm_config.fund_fee_rate }, 40_000);
+    assert_eq!({ amm_config.create_pool_fee }, 150_000_000);
+    assert_eq!({ amm_config.creator_fee_rate }, 1_000);
+    assert_eq!(amm_config.protocol_owner, admin::ID);
+    assert_eq!(amm_config.fund_owner, admin::ID);
+}
+
+#[test]
+fn create_amm_config_rejects() {
+    let svm = svm();
+    let fixture = Fixture::pool();
+
+    let result = create_amm_config(&svm, &fixture, PAYER, 2_500);
+    assert_eq!(result, Err(CpmmError::InvalidOwner.into()));
+
+    // The trade fee rate must stay below 100%
+    let result = create_amm_config(&svm, &fixture, ADMIN, 1_000_000);
+    assert_eq!(result, Err(CpmmError::InvalidInput.into()));
+
+    create_amm_config(&svm, &fixture, ADMIN, 2_500).unwrap();
+    let result = create_amm_config(&svm, &fixture, ADMIN, 2_500);
+    assert_eq!(result, Err(ProgramError::AccountAlreadyInitialized));
+}
+
+#[test]
+fn create_amm_config_at_other_address() {
+    let svm = svm();
+    let fixture = Fixture::pool();
+
+    let result = CreateAmmConfig {
+        owner: fixture.account(ADMIN),
+        amm_config: fixture.account(NEW_AMM_CONFIG),
+        system_program: fixture.account(SYSTEM_PROGRAM),
+        index: 2,
+        trade_fee_rate: 2_500,
+        protocol_fee_rate: 120_000,
+        fund_fee_rate: 40_000,
+        create_pool_fee: 0,
+        creator_fee_rate: 0,
+    }
+    .execute(&svm);
+
+    assert_eq!(result, Err(AnchorError::ConstraintSeeds.into()));
+}
+
+fn update_amm_config(svm: &Svm, fixture: &Fixture, owner: usize, param: Param) -> ProgramResult {
+    UpdateAmmConfig {
+        owner: fixture.account(owner),
+        amm_config: fixture.account(AMM_CONFIG),
+        param,
+    }
+    .execute(svm)
+}
+
+#[test]
+fn update_amm_config_by_admin() {
+    let svm = svm();
+    let fixture = Fixture::pool();
+    let a = |index| fixture.account(index);
+
+    update_amm_config(&svm, &fixture, ADMIN, Param::TradeFeeRate(3_000)).unwrap();
+    update_amm_config(&svm, &fixture, ADMIN, Param::ProtocolFeeRate(100_000)).unwrap();
+    update_amm_config(&svm, &fixture, ADMIN, Param::FundFeeRate(50_000)).unwrap();
+    update_amm_config(&svm, &fixture, ADMIN, Param::NewProtocolOwner(a(CREATOR))).unwrap();
+    update_amm_config(&svm, &fixture, ADMIN, Param::NewFundOwner(a(ADMIN))).unwrap();
+    update_amm_config(&svm, &fixture, ADMIN, Param::CreatePoolFee(1_000)).unwrap();
+    update_amm_config(&svm, &fixture, ADMIN, Param::DisableCreatePool(true)).unwrap();
+    update_amm_config(&svm, &fixture, ADMIN, Param::CreatorFeeRate(500)).unwrap();
+
+    let amm_config = fixture.amm_config(AMM_CONFIG);
+    assert_eq!({ amm_config.trade_fee_rate }, 3_000);
+    assert_eq!({ amm_config.protocol_fee_rate }, 100_000);
+    assert_eq!({ amm_config.fund_fee_rate }, 50_000);
+    assert_eq!(amm_config.protocol_owner, key(CREATOR));
+    assert_eq!(amm_config.fund_owner, admin::ID);
+    assert_eq!({ amm_config.create_pool_fee }, 1_000);
+    assert!(amm_config.disable_create_pool);
+    assert_eq!({ amm_config.creator_fee_rate }, 500);
+}
+
+#[test]
+fn update_amm_config_rejects() {
+    let svm = svm();
+    let fixture = Fixture::pool();
+
+    let result = update_amm_config(&svm, &fixture, PAYER, Param::TradeFeeRate(3_000));
+    assert_eq!(result, Err(CpmmError::InvalidOwner.into()));
+
+    // Protocol and fund fees together must stay within 100% of the trade fee
+    let result = update_amm_config(&svm, &fixture, ADMIN, Param::FundFeeRate(900_000));
+    assert_eq!(result, Err(CpmmError::InvalidInput.into()));
+    assert_eq!({ fixture.amm_config(AMM_CONFIG).fund_fee_rate }, 40_000);
+}
+
+#[test]
+fn update_pool_status_disables_swaps() {
+    let svm = svm();
+    let fixture = Fixture::pool();
+    let update_pool_status = |authority, status| {
+        UpdatePoolStatus {
+            authority: fixture.account(authority),
+            pool_state: fixture.account(POOL_STATE),
+            status,
+        }
+        .execute(&svm)
+    };
+
+    assert_eq!(update_pool_status(PAYER, 0b100), Err(CpmmError::InvalidOwner.into()));
+    update_pool_status(ADMIN, 0b100).unwrap();
+
+    assert_eq!(fixture.pool_state().status, 0b100);
+    let result = SwapBaseInput::with_slippage(fixture.swap_accounts(TradeDirection::ZeroForOne), 10_000, 0)
+        .unwrap()
+        .execute(&svm);
+    assert_eq!(result, Err(CpmmError::NotApproved.into()));
+}
+
+#[test]
+fn swap_from_open_time() {
+    let svm = svm();
+    let swap = |open_time| {
+        let pool_state = PoolState { open_time, ..pool_state() };
+        let fixture = Fixture::new(Some(pool_state), 1_000_000, 2_000_000);
+        SwapBaseInput::with_slippage(fixture.swap_accounts(TradeDirection::ZeroForOne), 10_000, 0)
+            .unwrap()
+            .execute(&svm)
+    };
+
+    assert_eq!(swap(NOW + 1), Err(CpmmError::NotApproved.into()));
+    assert_eq!(swap(NOW), Ok(()));
+}
+
+/// A pool holding `1_000` and `2_000` protocol and fund fees, and `300` creator fees of token 0
+fn pool_with_fees() -> Fixture {
+    let pool_state = PoolState {
+        protocol_fees_token_0: 1_000,
+        protocol_fees_token_1: 2_000,
+        fund_fees_token_0: 1_000,
+        fund_fees_token_1: 2_000,
+        creator_fees_token_0: 300,
+        ..pool_state()
+    };
+    Fixture::new(Some(pool_state), 1_000_000, 2_000_000)
+}
+
+#[test]
+fn collect_protocol_fee() {
+    let svm = svm();
+    let fixture = pool_with_fees();
+    let a = |index| fixture.account(index);
+
+    CollectProtocolFee {
+        owner: a(PAYER), authority: a(AUTHORITY), pool_state: a(POOL_STATE),
+        amm_config: a(AMM_CONFIG), token_0_vault: a(TOKEN_0_VAULT), token_1_vault: a(TOKEN_1_VAULT),
+        vault_0_mint: a(TOKEN_0_MINT), vault_1_mint: a(TOKEN_1_MINT),
+        recipient_token_0_account: a(PAYER_TOKEN_0), recipient_token_1_account: a(PAYER_TOKEN_1),
+        token_program: a(TOKEN_PROGRAM), token_program_2022: a(TOKEN_PROGRAM_2022),
+        amount_0_requested: 600,
+        amount_1_requested: u64::MAX,
+    }
+    .execute(&svm)
+    .unwrap();
+
+    // The requests are capped to the fees owed
+    assert_eq!(fixture.amount(PAYER_TOKEN_0), PAYER_BALANCE + 600);
+    assert_eq!(fixture.amount(PAYER_TOKEN_1), PAYER_BALANCE + 2_000);
+    assert_eq!(fixture.amount(TOKEN_0_VAULT), 1_000_000 - 600);
+    assert_eq!(fixture.amount(TOKEN_1_VAULT), 2_000_000 - 2_000);
+    let pool_state = fixture.pool_state();
+    assert_eq!({ pool_state.protocol_fees_token_0 }, 400);
+    assert_eq!({ pool_state.protocol_fees_token_1 }, 0);
+    assert_eq!({ pool_state.fund_fees_token_0 }, 1_000);
+}
+
+#[test]
+fn collect_fund_fee() {
+    let svm = svm();
+    let fixture = pool_with_fees();
+    let a = |index| fixture.account(index);
+    let collect_fund_fee = |owner| {
+        CollectFundFee {
+            owner: a(owner), authority: a(AUTHORITY), pool_state: a(POOL_STATE),
+            amm_config: a(AMM_CONFIG), token_0_vault: a(TOKEN_0_VAULT),
+            token_1_vault: a(TOKEN_1_VAULT), vault_0_mint: a(TOKEN_0_MINT),
+            vault_1_mint: a(TOKEN_1_MINT), recipient_token_0_account: a(PAYER_TOKEN_0),
+            recipient_token_1_account: a(PAYER_TOKEN_1), token_program: a(TOKEN_PROGRAM),
+            token_program_2022: a(TOKEN_PROGRAM_2022),
+            amount_0_requested: 0,
+            amount_1_requested: 1_500,
+        }
+        .execute(&svm)
+    };
+
+    // Neither the admin nor the fund owner
+    assert_eq!(collect_fund_fee(CREATOR), Err(CpmmError::InvalidOwner.into()));
+    // The admin can always collect
+    collect_fund_fee(ADMIN).unwrap();
+
+    assert_eq!(fixture.amount(PAYER_TOKEN_0), PAYER_BALANCE);
+    assert_eq!(fixture.amount(PAYER_TOKEN_1), PAYER_BALANCE + 1_500);
+    let pool_state = fixture.pool_state();
+    assert_eq!({ pool_state.fund_fees_token_0 }, 1_000);
+    assert_eq!({ pool_state.fund_fees_token_1 }, 500);
+    assert_eq!({ pool_state.protocol_fees_token_1 }, 2_000);
+    // No transfer of the zero token 0 amount
+    assert_eq!(
+        trace(&svm.invocations())[1..],
+        [(*program_id(), 1, CollectFundFee::DISCRIMINATOR[0]), (token_program::ID, 2, 12)]
+    );
+}
+
+fn collect_creator_fee(svm: &Svm, fixture: &Fixture) -> ProgramResult {
+    let a = |index| fixture.account(index);
+    CollectCreatorFee {
+        creator: a(PAYER), authority: a(AUTHORITY), pool_state: a(POOL_STATE),
+        amm_config: a(AMM_CONFIG), token_0_vault: a(TOKEN_0_VAULT), token_1_vault: a(TOKEN_1_VAULT),
+        vault_0_mint: a(TOKEN_0_MINT), vault_1_mint: a(TOKEN_1_MINT),
+        creator_token_0: a(PAYER_TOKEN_0), creator_token_1: a(PAYER_TOKEN_1),
+        token_0_program: a(TOKEN_PROGRAM), token_1_program: a(TOKEN_PROGRAM),
+        associated_token_program: a(ASSOCIATED_TOKEN_PROGRAM), system_program: a(SYSTEM_PROGRAM),
+    }
+    .execute(svm)
+}
+
+#[test]
+fn collect_creator_fee_to_the_pool_creator() {
+    let svm = svm();
+    let fixture = pool_with_fees();
+
+    collect_creator_fee(&svm, &fixture).unwrap();
+
+    assert_eq!(fixture.amount(PAYER_TOKEN_0), PAYER_BALANCE + 300);
+    assert_eq!(fixture.amount(PAYER_TOKEN_1), PAYER_BALANCE);
+    let pool_state = fixture.pool_state();
+    assert_eq!({ pool_state.creator_fees_token_0 }, 0);
+    assert_eq!({ pool_state.protocol_fees_token_0 }, 1_000);
+
+    let result = collect_creator_fee(&svm, &fixture);
+    assert_eq!(result, Err(CpmmError::NoFeeCollect.into()));
+}
+
+#[test]
+fn collect_creator_fee_of_other_creator() {
+    let svm = svm();
+    let pool_state = PoolState {
+        pool_creator: key(CREATOR),
+        creator_fees_token_0: 300,
+        ..pool_state()
+    };
+    let fixture = Fixture::new(Some(pool_state), 1_000_000, 2_000_000);
+
+    let result = collect_creator_fee(&svm, &fixture);
+
+    assert_eq!(result, Err(CpmmError::InvalidOwner.into()));
+    assert_eq!(fixture.amount(PAYER_TOKEN_0), PAYER_BALANCE);
+}
+
+/// The admin, the payer as permission authority, its permission and the system program
+fn permission_pda(fixture: &Fixture) -> [&AccountInfo; 4] {
+    [ADMIN, PAYER, PERMISSION, SYSTEM_PROGRAM].map(|index| fixture.account(index))
+}
+
+#[test]
+fn create_and_close_permission_pda() {
+    let svm = svm();
+    let fixture = Fixture::pool();
+    let [owner, permission_authority, permission, system_program] = permission_pda(&fixture);
+
+    CreatePermissionPda { owner, permission_authority, permission, system_program }
+        .execute(&svm)
+        .unwrap();
+
+    let state = *Permission::from_account_info(permission).unwrap();
+    assert_eq!(state.authority, key(PAYER));
+
+    let admin_lamports = owner.lamports();
+    ClosePermissionPda { owner, permission_authority, permission, system_program }
+        .execute(&svm)
+        .unwrap();
+
+    assert_eq!(owner.lamports(), admin_lamports + PERMISSION_RENT);
+    assert_eq!(permission.lamports(), 0);
+    assert_eq!(permission.data_len(), 0);
+    assert!(permission.is_owned_by(&[0; 32]));
+}
+
+#[test]
+fn permission_pda_by_other_owner() {
+    let svm = svm();
+    let fixture = Fixture::pool();
+    let [_, permission_authority, permission, system_program] = permission_pda(&fixture);
+    let owner = fixture.account(PAYER);
+
+    let result = CreatePermissionPda { owner, permission_authority, permission, system_program }
+        .execute(&svm);
+
+    assert_eq!(result, Err(CpmmError::InvalidOwner.into()));
+}
+
+fn initialize_with_permission(svm: &Svm, fixture: &Fixture) -> ProgramResult {
+    let a = |index| fixture.account(index);
+    InitializeWithPermission {
+        payer: a(PAYER), creator: a(CREATOR), amm_config: a(AMM_CONFIG), authority: a(AUTHORITY),
+        pool_state: a(POOL_STATE), token_0_mint: a(TOKEN_0_MINT), token_1_mint: a(TOKEN_1_MINT),
+        lp_mint: a(LP_MINT), payer_token_0: a(PAYER_TOKEN_0), payer_token_1: a(PAYER_TOKEN_1),
+        payer_lp_token: a(PAYER_LP_TOKEN), token_0_vault: a(TOKEN_0_VAULT),
+        token_1_vault: a(TOKEN_1_VAULT), create_pool_fee: a(CREATE_POOL_FEE),
+        observation_state: a(OBSERVATION_STATE), permission: a(PERMISSION),
+        token_program: a(TOKEN_PROGRAM), token_0_program: a(TOKEN_PROGRAM),
+        token_1_program: a(TOKEN_PROGRAM), associated_token_program: a(ASSOCIATED_TOKEN_PROGRAM),
+        system_program: a(SYSTEM_PROGRAM),
+        init_amount_0: 400_000,
+        init_amount_1: 900_000,
+        open_time: 0,
+        creator_fee_on: CreatorFeeOn::OnlyToken1,
+    }
+    .execute(svm)
+}
+
+#[test]
+fn initialize_with_permission_for_creator() {
+    let svm = svm();
+    let fixture = Fixture::new(None, 0, 0);
+    let [owner, permission_authority, permission, system_program] = permission_pda(&fixture);
+
+    // The payer holds no permission yet
+    let result = initialize_with_permission(&svm, &fixture);
+    assert_eq!(result, Err(ProgramError::InvalidAccountData));
+
+    CreatePermissionPda { owner, permission_authority, permission, system_program }
+        .execute(&svm)
+        .unwrap();
+    initialize_with_permission(&svm, &fixture).unwrap();
+
+    // The payer funds the pool and receives the lp tokens, the creator collects the fees
+    assert_eq!(fixture.amount(PAYER_TOKEN_0), PAYER_BALANCE - 400_000);
+    assert_eq!(fixture.amount(PAYER_TOKEN_1), PAYER_BALANCE - 900_000);
+    assert_eq!(fixture.amount(PAYER_LP_TOKEN), 599_900);
+    let pool_state = fixture.pool_state();
+    assert_eq!(pool_state.pool_creator, key(CREATOR));
+    assert!(pool_state.enable_creator_fee);
+    assert_eq!(pool_state.creator_fee_on, CreatorFeeOn::OnlyToken1 as u8);
+    assert_eq!({ pool_state.lp_supply }, 600_000);
+}
@@ -0,0 +1,72 @@
+use pinocchio::program_error::ProgramError;
+use pinocchio_raydium_cpmm_cpi::{
+    pda::{
+        create_authority_address, create_program_address, find_authority_address,
+        find_observation_address, try_find_program_address, verify_authority,
+    },
+    test_utils::PoolStateBuilder,
+    ID,
+};
+
+// The mainnet pool vault and lp mint authority
+const AUTHORITY: [u8; 32] =
+    pinocchio_pubkey::from_str("GpMZbSM2GgvTKHJirzeGfMFoaZ8UR2X7F4v8vHTvxFbL");
+
+#[test]
+fn finds_the_mainnet_authority() {
+    assert_eq!(find_authority_address(), (AUTHORITY, 253));
+    assert_eq!(create_authority_address(253), Ok(AUTHORITY));
+}
+
+#[test]
+fn verifies_the_authority_with_the_pool_bump() {
+    let pool_state = PoolStateBuilder::new().auth_bump(253).build();
+    assert_eq!(verify_authority(&AUTHORITY, &pool_state), Ok(()));
+
+    let pool_state = PoolStateBuilder::new().auth_bump(252).build();
+    assert_eq!(
+        verify_authority(&AUTHORITY, &pool_state),
+        Err(ProgramError::InvalidSeeds)
+    );
+}
+
+#[test]
+fn found_addresses_are_created_from_their_bump() {
+    let pool_state = [7; 32];
+    let (address, bump) = find_observation_address(&pool_state);
+    assert_eq!(
+        create_program_address(&[b"observation", &pool_state, &[bump]], &ID),
+        Ok(address)
+    );
+    assert_eq!(
+        try_find_program_address(&[b"observation", &pool_state], &ID),
+        Some((address, bump))
+    );
+}
+
+#[test]
+fn rejects_addresses_on_the_curve() {
+    // Canonical bumps are the highest off the curve, some higher bump must land on it
+    let (_, bump) = find_authority_address();
+    let on_curve = (bump + 1..=u8::MAX)
+        .map(create_authority_address)
+        .find(Result::is_err);
+    assert_eq!(on_curve, Some(Err(ProgramError::InvalidSeeds)));
+}
+
+#[test]
+fn rejects_too_many_or_too_long_seeds() {
+    let seeds = [&[0u8][..]; 17];
+    assert_eq!(
+        create_program_address(&seeds, &ID),
+        Err(ProgramError::MaxSeedLengthExceeded)
+    );
+    assert_eq!(try_find_program_address(&seeds[..16], &ID), None);
+
+    let long = [0u8; 33];
+    assert_eq!(
+        create_program_address(&[&long], &ID),
+        Err(ProgramError::MaxSeedLengthExceeded)
+    );
+    assert_eq!(try_find_program_address(&[&long], &ID), None);
+}
@@ -0,0 +1,118 @@
+//! The program id override of `set_program_id` and the addresses selected by the cluster
+//! features.
+//!
+//! The override is process wide, so the test setting it is the only one of this binary
+//! depending on `program_id`.
+
+use pinocchio::pubkey::Pubkey;
+use pinocchio_raydium_cpmm_cpi::{
+    admin, client,
+    instructions::UpdatePoolStatus,
+    pda, program_id, reset_program_id, set_program_id,
+    states::PoolState,
+    test_utils::{AccountFixture, PoolStateBuilder, ProgramInput},
+    AUTH_SEED, ID,
+};
+
+/// A CPMM build deployed at a custom address
+static CUSTOM_ID: Pubkey = [9; 32];
+
+/// What the crate uses as the program id
+#[derive(Debug, PartialEq)]
+struct Observed {
+    program_id: Pubkey,
+    instruction: Pubkey,
+    client_instruction: Pubkey,
+    authority: Pubkey,
+    created_authority: Pubkey,
+    client_authority: Pubkey,
+    /// The owners accepted by `ProgramAccount::from_account_info`, of `ID` and `CUSTOM_ID`
+    accepted_owners: Vec<Pubkey>,
+}
+
+fn observe() -> Observed {
+    let data = PoolStateBuilder::new().account_data();
+    let input = ProgramInput::new(&[
+        AccountFixture::new([1; 32], [0; 32], Vec::new()).signer(),
+        AccountFixture::new([2; 32], ID, data.clone()),
+        AccountFixture::new([3; 32], CUSTOM_ID, data),
+    ]);
+    let a = input.accounts();
+
+    let instruction = UpdatePoolStatus { authority: &a[0], pool_state: &a[1], status: 0 }
+        .with_instruction(|instruction, _| *instruction.program_id);
+    let client_instruction = client::UpdatePoolStatus {
+        authority: solana_pubkey::Pubkey::new_from_array([1; 32]),
+        pool_state: solana_pubkey::Pubkey::new_from_array([2; 32]),
+        status: 0,
+    }
+    .instruction()
+    .program_id
+    .to_bytes();
+    let (authority, bump) = pda::find_authority_address();
+
+    Observed {
+        program_id: *program_id(),
+        instruction,
+        client_instruction,
+        authority,
+        created_authority: pda::create_authority_address(bump).unwrap(),
+        client_authority: client::authority_address().to_bytes(),
+        accepted_owners: [ID, CUSTOM_ID]
+            .into_iter()
+            .zip(&a[1..])
+            .filter(|(_, account)| PoolState::from_account_info(account).is_ok())
+            .map(|(owner, _)| owner)
+            .collect(),
+    }
+}
+
+/// What the crate should use for `program_id`
+fn expected(program_id: Pubkey) -> Observed {
+    let (authority, _) =
+        pda::try_find_program_address(&[AUTH_SEED.as_bytes()], &program_id).unwrap();
+    Observed {
+        program_id,
+        instruction: program_id,
+        client_instruction: program_id,
+        authority,
+        created_authority: authority,
+        client_authority: authority,
+        accepted_owners: vec![program_id],
+    }
+}
+
+#[test]
+fn overrides_the_program_id_until_reset() {
+    assert_eq!(observe(), expected(ID));
+
+    set_program_id(&CUSTOM_ID);
+    let overridden = observe();
+    reset_program_id();
+
+    assert_eq!(overridden, expected(CUSTOM_ID));
+    assert_eq!(observe(), expected(ID));
+}
+
+#[test]
+fn cluster_addresses() {
+    let (program, admin) = if cfg!(feature = "localnet") {
+        (
+            option_env!("CPMM_PROGRAM_ID").unwrap_or("CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C"),
+            option_env!("CPMM_ADMIN").unwrap_or("GThUX1Atko4tqhN2NaiTazWSeFWMuiUvfFnyJyUghFMJ"),
+        )
+    } else if cfg!(feature = "devnet") {
+        (
+            "CPMDWBwJDtYax9qW7AyRuVC19Cc4L4Vcy4n2BHAbHkCW",
+            "adMCyoCgfkg7bQiJ9aBJ59H3BXLY3r5LNLfPpQfMzBe",
+        )
+    } else {
+        (
+            "CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C",
+            "GThUX1Atko4tqhN2NaiTazWSeFWMuiUvfFnyJyUghFMJ",
+        )
+    };
+
+    assert_eq!(ID, solana_pubkey::Pubkey::from_str_const(program).to_bytes());
+    assert_eq!(admin::ID, solana_pubkey::Pubkey::from_str_const(admin).to_bytes());
+}
@@ -0,0 +1,400 @@
+use pinocchio::{account_info::AccountInfo, pubkey::Pubkey};
+use pinocchio_raydium_cpmm_cpi::{
+    admin, associated_token_program,
+    error::ValidationError::{self, *},
+    instructions::{
+        ClosePermissionPda, CollectCreatorFee, CollectFundFee, CollectProtocolFee, CreateAmmConfig,
+        CreatePermissionPda, Deposit, Initialize, InitializeWithPermission, SwapBaseInput,
+        SwapBaseOutput, UpdateAmmConfig, UpdateAmmConfigParam, UpdatePoolStatus, Withdraw,
+    },
+    memo_program, pda, rent_sysvar,
+    states::CreatorFeeOn,
+    system_program,
+    test_utils::{
+        mint_data, permission_data, AccountFixture, AmmConfigBuilder, PoolStateBuilder,
+        ProgramInput,
+    },
+    token_program, token_program_2022,
+};
+
+const AMM_CONFIG: Pubkey = [1; 32];
+const POOL_STATE: Pubkey = [2; 32];
+const MINT_0: Pubkey = [3; 32];
+const MINT_1: Pubkey = [4; 32];
+const VAULT_0: Pubkey = [5; 32];
+const VAULT_1: Pubkey = [6; 32];
+const LP_MINT: Pubkey = [7; 32];
+const OBSERVATION: Pubkey = [8; 32];
+const CREATOR: Pubkey = [9; 32];
+const PAYER: Pubkey = [10; 32];
+const PERMISSION: Pubkey = [11; 32];
+const PROTOCOL_OWNER: Pubkey = [12; 32];
+const FUND_OWNER: Pubkey = [13; 32];
+const OTHER: Pubkey = [0xff; 32];
+
+fn pool_state(builder: impl FnOnce(PoolStateBuilder) -> PoolStateBuilder) -> AccountFixture {
+    let pool_state = PoolStateBuilder::new()
+        .amm_config(AMM_CONFIG)
+        .pool_creator(CREATOR)
+        .mints(MINT_0, MINT_1)
+        .vaults(VAULT_0, VAULT_1)
+        .token_programs(token_program::ID, token_program_2022::ID)
+        .lp_mint(LP_MINT)
+        .observation_key(OBSERVATION)
+        .auth_bump(pda::find_authority_address().1);
+    AccountFixture::cpmm(POOL_STATE, builder(pool_state).account_data())
+}
+
+fn amm_config(builder: impl FnOnce(AmmConfigBuilder) -> AmmConfigBuilder) -> AccountFixture {
+    let amm_config = AmmConfigBuilder::new()
+        .protocol_owner(PROTOCOL_OWNER)
+        .fund_owner(FUND_OWNER);
+    AccountFixture::cpmm(AMM_CONFIG, builder(amm_config).account_data())
+}
+
+/// An account with another key, holding no data
+fn other() -> AccountFixture {
+    AccountFixture::new(OTHER, [0; 32], Vec::new())
+}
+
+fn signer(key: Pubkey) -> AccountFixture {
+    AccountFixture::new(key, [0; 32], Vec::new()).signer()
+}
+
+fn mint(key: Pubkey, owner: Pubkey) -> AccountFixture {
+    AccountFixture::new(key, owner, mint_data(0, 6))
+}
+
+/// The account named `name` in a valid pool, token 0 on SPL token and token 1 on Token-2022
+fn fixture(name: &str) -> AccountFixture {
+    match name {
+        "owner" => signer(admin::ID),
+        "creator" => signer(CREATOR),
+        "payer" | "permission_authority" => signer(PAYER),
+        "authority" => AccountFixture::new(pda::find_authority_address().0, [0; 32], Vec::new()),
+        "amm_config" => amm_config(|builder| builder),
+        "pool_state" => pool_state(|builder| builder),
+        "permission" => AccountFixture::cpmm(PERMISSION, permission_data(PAYER)),
+        "token_0_mint" | "vault_0_mint" | "input_token_mint" => mint(MINT_0, token_program::ID),
+        "token_1_mint" | "vault_1_mint" | "output_token_mint" => {
+            mint(MINT_1, token_program_2022::ID)
+        }
+        "token_0_vault" | "input_vault" => AccountFixture::new(VAULT_0, [0; 32], Vec::new()),
+        "token_1_vault" | "output_vault" => AccountFixture::new(VAULT_1, [0; 32], Vec::new()),
+        "lp_mint" => AccountFixture::new(LP_MINT, [0; 32], Vec::new()),
+        "observation_state" => AccountFixture::new(OBSERVATION, [0; 32], Vec::new()),
+        "token_program" | "token_0_program" | "input_token_program" => {
+            AccountFixture::program(token_program::ID)
+        }
+        "token_program_2022" | "token_1_program" | "output_token_program" => {
+            AccountFixture::program(token_program_2022::ID)
+        }
+        "memo_program" => AccountFixture::program(memo_program::ID),
+        "associated_token_program" => AccountFixture::program(associated_token_program::ID),
+        "system_program" => AccountFixture::program(system_program::ID),
+        "rent" => AccountFixture::program(rent_sysvar::ID),
+        // Token accounts of the users, not checked by `validate`
+        name => {
+            let mut key = [0; 32];
+            key[..name.len()].copy_from_slice(name.as_bytes());
+            AccountFixture::new(key, [0; 32], Vec::new())
+        }
+    }
+}
+
+/// Validate the instruction built by `validate` over a valid pool, with the accounts named in
+/// `replace` substituted
+fn validate(
+    names: &[&str],
+    replace: &[(&str, AccountFixture)],
+    validate: impl FnOnce(&[AccountInfo]) -> Result<(), ValidationError>,
+) -> Result<(), ValidationError> {
+    let fixtures: Vec<AccountFixture> = names
+        .iter()
+        .map(
+            |name| match replace.iter().find(|(replaced, _)| replaced == name) {
+                Some((_, fixture)) => fixture.clone(),
+                None => fixture(name),
+            },
+        )
+        .collect();
+    let input = ProgramInput::new(&fixtures);
+    validate(input.accounts())
+}
+
+macro_rules! validate_tests {
+    ($module:ident, $instruction:ty, |$accounts:ident| $from_accounts:expr, {
+        $($case:ident: [$(($name:literal, $fixture:expr)),+] => $expected:expr,)*
+    }) => {
+        mod $module {
+            use super::*;
+
+            fn check(replace: &[(&str, AccountFixture)]) -> Result<(), ValidationError> {
+                validate(&<$instruction>::ACCOUNT_NAMES, replace, |$accounts| {
+                    $from_accounts.unwrap().validate()
+                })
+            }
+
+            #[test]
+            fn accepts_valid_accounts() {
+                assert_eq!(check(&[]), Ok(()));
+            }
+
+            $(
+                #[test]
+                fn $case() {
+                    assert_eq!(check(&[$(($name, $fixture)),+]), $expected);
+                }
+            )*
+        }
+    };
+}
+
+macro_rules! swap_tests {
+    ($module:ident, $instruction:ty) => {
+        validate_tests!($module, $instruction, |accounts| {
+            <$instruction>::from_accounts(accounts, 1, 1)
+        }, {
+            accepts_token_1_to_token_0: [
+                ("input_vault", fixture("output_vault")),
+                ("output_vault", fixture("input_vault")),
+                ("input_token_mint", fixture("output_token_mint")),
+                ("output_token_mint", fixture("input_token_mint")),
+                ("input_token_program", fixture("output_token_program")),
+                ("output_token_program", fixture("input_token_program"))
+            ] => Ok(()),
+            rejects_pool_state_of_other_program: [
+                ("pool_state", AccountFixture::new(POOL_STATE, OTHER, fixture("pool_state").data))
+            ] => Err(InvalidPoolState),
+            rejects_other_amm_config: [("amm_config", other())] => Err(InvalidAmmConfig),
+            rejects_other_authority: [("authority", other())] => Err(InvalidAuthority),
+            rejects_wrong_auth_bump: [("pool_state", pool_state(|b| b.auth_bump(0)))]
+                => Err(InvalidAuthority),
+            rejects_other_observation_state: [("observation_state", other())]
+                => Err(InvalidObservationState),
+            rejects_other_input_vault: [("input_vault", other())] => Err(InvalidSwapVaults),
+            rejects_same_vaults: [("output_vault", fixture("input_vault"))]
+                => Err(InvalidSwapVaults),
+            rejects_other_input_mint: [("input_token_mint", other())] => Err(InvalidSwapMint),
+            rejects_other_output_mint: [("output_token_mint", other())] => Err(InvalidSwapMint),
+            rejects_other_input_program: [("input_token_program", fixture("token_program_2022"))]
+                => Err(InvalidSwapTokenProgram),
+            rejects_other_output_program: [("output_token_program", fixture("token_program"))]
+                => Err(InvalidSwapTokenProgram),
+        });
+    };
+}
+
+swap_tests!(swap_base_input, SwapBaseInput);
+swap_tests!(swap_base_output, SwapBaseOutput);
+
+macro_rules! liquidity_tests {
+    ($module:ident, $instruction:ty, {
+        $($case:ident: [$(($name:literal, $fixture:expr)),+] => $expected:expr,)*
+    }) => {
+        validate_tests!($module, $instruction, |accounts| {
+            <$instruction>::from_accounts(accounts, 1, 1, 1)
+        }, {
+            rejects_other_pool_state: [("pool_state", other())] => Err(InvalidPoolState),
+            rejects_other_authority: [("authority", other())] => Err(InvalidAuthority),
+            rejects_other_token_0_vault: [("token_0_vault", other())] => Err(InvalidToken0Vault),
+            rejects_other_token_1_vault: [("token_1_vault", other())] => Err(InvalidToken1Vault),
+            rejects_other_vault_0_mint: [("vault_0_mint", other())] => Err(InvalidToken0Mint),
+            rejects_other_vault_1_mint: [("vault_1_mint", other())] => Err(InvalidToken1Mint),
+            rejects_other_lp_mint: [("lp_mint", other())] => Err(InvalidLpMint),
+            rejects_other_token_program: [("token_program", fixture("token_program_2022"))]
+                => Err(InvalidTokenProgram),
+            rejects_other_token_program_2022: [("token_program_2022", fixture("token_program"))]
+                => Err(InvalidTokenProgram2022),
+            $($case: [$(($name, $fixture)),+] => $expected,)*
+        });
+    };
+}
+
+liquidity_tests!(deposit, Deposit, {});
+liquidity_tests!(withdraw, Withdraw, {
+    rejects_other_memo_program: [("memo_program", other())] => Err(InvalidMemoProgram),
+});
+
+macro_rules! fee_tests {
+    ($module:ident, $instruction:ty, $owner:expr, $owner_field:ident) => {
+        validate_tests!($module, $instruction, |accounts| {
+            <$instruction>::from_accounts(accounts, 1, 1)
+        }, {
+            accepts_config_owner: [("owner", signer($owner))] => Ok(()),
+            rejects_other_owner: [("owner", other())] => Err(InvalidOwner),
+            rejects_owner_of_other_config: [
+                ("owner", signer($owner)),
+                ("amm_config", amm_config(|b| b.$owner_field(OTHER)))
+            ] => Err(InvalidOwner),
+            rejects_other_pool_state: [("pool_state", other())] => Err(InvalidPoolState),
+            rejects_config_of_other_pool: [("amm_config", other())] => Err(InvalidAmmConfig),
+            rejects_invalid_config: [("amm_config", AccountFixture::new(AMM_CONFIG, OTHER, vec![]))]
+                => Err(InvalidAmmConfig),
+            rejects_other_authority: [("authority", other())] => Err(InvalidAuthority),
+            rejects_other_token_0_vault: [("token_0_vault", other())] => Err(InvalidToken0Vault),
+            rejects_other_token_1_vault: [("token_1_vault", other())] => Err(InvalidToken1Vault),
+            rejects_other_vault_0_mint: [("vault_0_mint", other())] => Err(InvalidToken0Mint),
+            rejects_other_vault_1_mint: [("vault_1_mint", other())] => Err(InvalidToken1Mint),
+            rejects_other_token_program: [("token_program", other())] => Err(InvalidTokenProgram),
+            rejects_other_token_program_2022: [("token_program_2022", other())]
+                => Err(InvalidTokenProgram2022),
+        });
+    };
+}
+
+fee_tests!(collect_protocol_fee, CollectProtocolFee, PROTOCOL_OWNER, protocol_owner);
+fee_tests!(collect_fund_fee, CollectFundFee, FUND_OWNER, fund_owner);
+
+validate_tests!(collect_creator_fee, CollectCreatorFee, |accounts| {
+    CollectCreatorFee::from_accounts(accounts)
+}, {
+    rejects_other_pool_state: [("pool_state", other())] => Err(InvalidPoolState),
+    rejects_other_creator: [("creator", other())] => Err(InvalidPoolCreator),
+    rejects_config_of_other_pool: [("amm_config", other())] => Err(InvalidAmmConfig),
+    rejects_other_authority: [("authority", other())] => Err(InvalidAuthority),
+    rejects_other_token_0_vault: [("token_0_vault", other())] => Err(InvalidToken0Vault),
+    rejects_other_token_1_vault: [("token_1_vault", other())] => Err(InvalidToken1Vault),
+    rejects_other_vault_0_mint: [("vault_0_mint", other())] => Err(InvalidToken0Mint),
+    rejects_other_vault_1_mint: [("vault_1_mint", other())] => Err(InvalidToken1Mint),
+    rejects_other_token_0_program: [("token_0_program", fixture("token_1_program"))]
+        => Err(InvalidToken0Program),
+    rejects_other_token_1_program: [("token_1_program", fixture("token_0_program"))]
+        => Err(InvalidToken1Program),
+    rejects_other_associated_token_program: [("associated_token_program", other())]
+        => Err(InvalidAssociatedTokenProgram),
+    rejects_other_system_program: [("system_program", other())] => Err(InvalidSystemProgram),
+});
+
+macro_rules! initialize_tests {
+    ($module:ident, $instruction:ty, |$accounts:ident| $from_accounts:expr, {
+        $($case:ident: [$(($name:literal, $fixture:expr)),+] => $expected:expr,)*
+    }) => {
+        validate_tests!($module, $instruction, |$accounts| $from_accounts, {
+            accepts_token_2022_token_0: [
+                ("token_0_mint", mint(MINT_0, token_program_2022::ID)),
+                ("token_0_program", fixture("token_program_2022"))
+            ] => Ok(()),
+            rejects_invalid_config: [("amm_config", other())] => Err(InvalidAmmConfig),
+            rejects_disabled_config: [("amm_config", amm_config(|b| b.disable_create_pool(true)))]
+                => Err(PoolCreationDisabled),
+            rejects_unordered_mints: [
+                ("token_0_mint", fixture("token_1_mint")),
+                ("token_1_mint", fixture("token_0_mint"))
+            ] => Err(InvalidMintOrder),
+            rejects_same_mints: [("token_1_mint", fixture("token_0_mint"))]
+                => Err(InvalidMintOrder),
+            rejects_other_token_program: [("token_program", fixture("token_program_2022"))]
+                => Err(InvalidTokenProgram),
+            rejects_other_token_0_program: [("token_0_program", other())]
+                => Err(InvalidToken0Program),
+            rejects_token_0_program_of_other_mint: [("token_0_program", fixture("token_1_program"))]
+                => Err(InvalidToken0Program),
+            rejects_other_token_1_program: [("token_1_program", other())]
+                => Err(InvalidToken1Program),
+            rejects_token_1_program_of_other_mint: [("token_1_program", fixture("token_0_program"))]
+                => Err(InvalidToken1Program),
+            rejects_other_associated_token_program: [("associated_token_program", other())]
+                => Err(InvalidAssociatedTokenProgram),
+            rejects_other_system_program: [("system_program", other())]
+                => Err(InvalidSystemProgram),
+            $($case: [$(($name, $fixture)),+] => $expected,)*
+        });
+    };
+}
+
+initialize_tests!(initialize, Initialize, |accounts| Initialize::from_accounts(accounts, 1, 1, 0), {
+    rejects_other_rent_sysvar: [("rent", other())] => Err(InvalidRentSysvar),
+});
+
+initialize_tests!(
+    initialize_with_permission,
+    InitializeWithPermission,
+    |accounts| InitializeWithPermission::from_accounts(accounts, 1, 1, 0, CreatorFeeOn::BothToken),
+    {
+        rejects_missing_permission: [("permission", other())] => Err(InvalidPermission),
+        rejects_permission_of_other_payer: [
+            ("permission", AccountFixture::cpmm(PERMISSION, permission_data(OTHER)))
+        ] => Err(InvalidPermission),
+    }
+);
+
+validate_tests!(create_amm_config, CreateAmmConfig, |accounts| {
+    CreateAmmConfig::from_accounts(accounts, 0, 0, 0, 0, 0, 0)
+}, {
+    rejects_other_owner: [("owner", other())] => Err(InvalidOwner),
+    rejects_other_system_program: [("system_program", other())] => Err(InvalidSystemProgram),
+});
+
+validate_tests!(update_amm_config, UpdateAmmConfig, |accounts| {
+    UpdateAmmConfig::from_accounts(accounts, UpdateAmmConfigParam::TradeFeeRate(0))
+}, {
+    rejects_other_owner: [("owner", other())] => Err(InvalidOwner),
+    rejects_invalid_config: [("amm_config", other())] => Err(InvalidAmmConfig),
+});
+
+mod update_pool_status {
+    use super::*;
+
+    // The admin signs as the `authority` of `UpdatePoolStatus`
+    fn check(authority: AccountFixture, pool_state: AccountFixture) -> Result<(), ValidationError> {
+        let replace = [("authority", authority), ("pool_state", pool_state)];
+        validate(&UpdatePoolStatus::ACCOUNT_NAMES, &replace, |accounts| {
+            UpdatePoolStatus::from_accounts(accounts, 0)
+                .unwrap()
+                .validate()
+        })
+    }
+
+    #[test]
+    fn accepts_valid_accounts() {
+        assert_eq!(check(fixture("owner"), fixture("pool_state")), Ok(()));
+    }
+
+    #[test]
+    fn rejects_other_authority() {
+        assert_eq!(
+            check(fixture("authority"), fixture("pool_state")),
+            Err(InvalidOwner)
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_pool_state() {
+        assert_eq!(
+            check(fixture("owner"), other()),
+            Err(InvalidPoolState)
+        );
+    }
+}
+
+validate_tests!(create_permission_pda, CreatePermissionPda, |accounts| {
+    CreatePermissionPda::from_accounts(accounts)
+}, {
+    rejects_other_owner: [("owner", other())] => Err(InvalidOwner),
+    rejects_other_system_program: [("system_program", other())] => Err(InvalidSystemProgram),
+});
+
+validate_tests!(close_permission_pda, ClosePermissionPda, |accounts| {
+    ClosePermissionPda::from_accounts(accounts)
+}, {
+    rejects_other_owner: [("owner", other())] => Err(InvalidOwner),
+    rejects_missing_permission: [("permission", other())] => Err(InvalidPermission),
+    rejects_other_permission_authority: [("permission_authority", other())]
+        => Err(InvalidPermission),
+    rejects_other_system_program: [("system_program", other())] => Err(InvalidSystemProgram),
+});
+
+#[test]
+fn error_codes_start_at_400() {
+    use pinocchio::program_error::ProgramError;
+
+    assert_eq!(
+        ProgramError::from(ValidationError::InvalidPoolState),
+        ProgramError::Custom(400)
+    );
+    assert_eq!(
+        ProgramError::from(ValidationError::InvalidPermission),
+        ProgramError::Custom(424)
+    );
+}